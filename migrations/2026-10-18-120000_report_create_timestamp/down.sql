@@ -0,0 +1,3 @@
+ALTER TABLE IF EXISTS public.report
+    ALTER COLUMN create_date TYPE date USING create_date::date,
+    ALTER COLUMN create_date SET DEFAULT now();
@@ -0,0 +1,3 @@
+ALTER TABLE IF EXISTS public.report
+    ALTER COLUMN create_date TYPE timestamp without time zone USING create_date::timestamp,
+    ALTER COLUMN create_date SET DEFAULT now();
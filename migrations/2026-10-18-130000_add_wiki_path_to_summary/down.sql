@@ -0,0 +1 @@
+ALTER TABLE IF EXISTS public.summary DROP COLUMN IF EXISTS wiki_path;
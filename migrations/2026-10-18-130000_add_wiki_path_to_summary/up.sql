@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS public.summary
+    ADD COLUMN wiki_path text;
@@ -0,0 +1,3 @@
+ALTER TABLE IF EXISTS public.report
+    DROP COLUMN IF EXISTS trello_card_id,
+    DROP COLUMN IF EXISTS trello_comment_id;
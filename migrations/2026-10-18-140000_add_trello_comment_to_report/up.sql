@@ -0,0 +1,3 @@
+ALTER TABLE IF EXISTS public.report
+    ADD COLUMN trello_card_id text,
+    ADD COLUMN trello_comment_id text;
@@ -0,0 +1,19 @@
+CREATE TABLE public.onboarding
+(
+    id uuid NOT NULL DEFAULT gen_random_uuid (),
+    member_id uuid NOT NULL,
+    step integer NOT NULL DEFAULT 0,
+    dm_channel_id text,
+    dm_message_id text,
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now(),
+    reminders_sent integer NOT NULL DEFAULT 0,
+    reminded_at timestamp,
+    last_error text,
+    PRIMARY KEY (id),
+    CONSTRAINT "UQ_onboarding_member" UNIQUE (member_id),
+    CONSTRAINT "FK_onboarding_member" FOREIGN KEY (member_id)
+        REFERENCES public.member (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
@@ -0,0 +1,20 @@
+CREATE TABLE public.offboarding
+(
+    id uuid NOT NULL DEFAULT gen_random_uuid (),
+    -- cleared when the member is deleted, the archive is kept
+    member_id uuid,
+    reason text NOT NULL,
+    offboarded_at timestamp NOT NULL DEFAULT now(),
+    previous_role integer NOT NULL,
+    discord_id text,
+    trello_id text,
+    trello_report_card_id text,
+    wiki_id bigint,
+    failed_steps text[] NOT NULL DEFAULT '{}',
+    reinstated_at timestamp,
+    PRIMARY KEY (id),
+    CONSTRAINT "FK_offboarding_member" FOREIGN KEY (member_id)
+        REFERENCES public.member (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE SET NULL
+);
@@ -0,0 +1,5 @@
+ALTER TABLE IF EXISTS public.offboarding DROP CONSTRAINT IF EXISTS "FK_offboarding_role";
+
+ALTER TABLE IF EXISTS public.member DROP CONSTRAINT IF EXISTS "FK_member_role";
+
+DROP TABLE IF EXISTS public.role;
@@ -0,0 +1,30 @@
+CREATE TABLE public.role
+(
+    id integer GENERATED BY DEFAULT AS IDENTITY (START WITH 2),
+    name text NOT NULL,
+    discord_role_id text,
+    wiki_group_id bigint,
+    activity_threshold_days integer,
+    permissions text[] NOT NULL DEFAULT '{}',
+    PRIMARY KEY (id),
+    CONSTRAINT "UQ_role_name" UNIQUE (name)
+);
+
+-- the values of the former MemberRole enum
+INSERT INTO public.role (id, name, permissions)
+VALUES
+    (-1, 'Ex-Member', '{}'),
+    (0, 'Member', '{}'),
+    (1, 'Apprentice', '{}');
+
+ALTER TABLE IF EXISTS public.member
+    ADD CONSTRAINT "FK_member_role" FOREIGN KEY (role)
+        REFERENCES public.role (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE NO ACTION;
+
+ALTER TABLE IF EXISTS public.offboarding
+    ADD CONSTRAINT "FK_offboarding_role" FOREIGN KEY (previous_role)
+        REFERENCES public.role (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE NO ACTION;
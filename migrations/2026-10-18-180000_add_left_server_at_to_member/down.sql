@@ -0,0 +1 @@
+ALTER TABLE IF EXISTS public.member DROP COLUMN IF EXISTS left_server_at;
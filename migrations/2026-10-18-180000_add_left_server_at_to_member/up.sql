@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS public.member
+    ADD COLUMN left_server_at timestamp;
@@ -0,0 +1,15 @@
+CREATE TABLE public.invite
+(
+    code text NOT NULL,
+    role integer NOT NULL,
+    created_by text NOT NULL,
+    created_at timestamp NOT NULL DEFAULT now(),
+    expires_at timestamp,
+    max_uses integer NOT NULL,
+    uses integer NOT NULL DEFAULT 0,
+    PRIMARY KEY (code),
+    CONSTRAINT "FK_invite_role" FOREIGN KEY (role)
+        REFERENCES public.role (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS public.team_members;
+
+DROP TABLE IF EXISTS public.team;
+
+ALTER TABLE IF EXISTS public.member
+    DROP COLUMN IF EXISTS custom_fields,
+    DROP COLUMN IF EXISTS skills;
+
+DROP TABLE IF EXISTS public.profile_field;
@@ -0,0 +1,33 @@
+CREATE TABLE public.profile_field
+(
+    name text NOT NULL,
+    kind integer NOT NULL,
+    PRIMARY KEY (name)
+);
+
+ALTER TABLE IF EXISTS public.member
+    ADD COLUMN custom_fields jsonb NOT NULL DEFAULT '{}',
+    ADD COLUMN skills text[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE public.team
+(
+    id integer GENERATED BY DEFAULT AS IDENTITY,
+    name text NOT NULL,
+    PRIMARY KEY (id),
+    CONSTRAINT "UQ_team_name" UNIQUE (name)
+);
+
+CREATE TABLE public.team_members
+(
+    team_id integer NOT NULL,
+    member_id uuid NOT NULL,
+    PRIMARY KEY (team_id, member_id),
+    CONSTRAINT "FK_team_members_team" FOREIGN KEY (team_id)
+        REFERENCES public.team (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE,
+    CONSTRAINT "FK_team_members_member" FOREIGN KEY (member_id)
+        REFERENCES public.member (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+ALTER TABLE IF EXISTS public.member
+    DROP CONSTRAINT IF EXISTS "FK_member_mentor",
+    DROP COLUMN IF EXISTS mentor_id,
+    DROP COLUMN IF EXISTS role_since,
+    DROP COLUMN IF EXISTS promotion_notified_at;
@@ -0,0 +1,26 @@
+ALTER TABLE IF EXISTS public.member
+    ADD COLUMN mentor_id uuid,
+    ADD COLUMN role_since timestamp NOT NULL DEFAULT now(),
+    ADD COLUMN promotion_notified_at timestamp,
+    ADD CONSTRAINT "FK_member_mentor" FOREIGN KEY (mentor_id)
+        REFERENCES public.member (id) MATCH SIMPLE
+        ON UPDATE NO ACTION
+        ON DELETE SET NULL;
+
+-- the day members got their role is unknown, so their history counts from
+-- their earliest report or attended meeting
+UPDATE public.member
+    SET role_since = history.since
+    FROM (
+        SELECT member_id, min(since) AS since
+        FROM (
+            SELECT member_id, create_date AS since
+            FROM public.report
+            UNION ALL
+            SELECT meeting_members.member_id, meeting.start_date
+            FROM public.meeting_members
+            JOIN public.meeting ON meeting.id = meeting_members.meeting_id
+        ) AS activity
+        GROUP BY member_id
+    ) AS history
+    WHERE history.member_id = member.id;
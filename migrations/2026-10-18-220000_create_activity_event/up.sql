@@ -0,0 +1,30 @@
+CREATE TABLE public.activity_event
+(
+    id uuid NOT NULL DEFAULT gen_random_uuid (),
+    member_id uuid NOT NULL,
+    kind integer NOT NULL,
+    -- ID of what the activity is about, e.g. the report or the meeting
+    source_id text NOT NULL,
+    occurred_at timestamp NOT NULL,
+    PRIMARY KEY (id),
+    CONSTRAINT "UQ_activity_event_source" UNIQUE (member_id, kind, source_id),
+    CONSTRAINT "FK_activity_event_member" FOREIGN KEY (member_id)
+        REFERENCES public.member (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
+
+CREATE INDEX "IX_activity_event_occurred_at" ON public.activity_event (occurred_at);
+
+-- the history of reports and attended meetings
+INSERT INTO public.activity_event (member_id, kind, source_id, occurred_at)
+SELECT member_id, 0, id::text, create_date
+FROM public.report
+ON CONFLICT DO NOTHING;
+
+INSERT INTO public.activity_event (member_id, kind, source_id, occurred_at)
+SELECT meeting_members.member_id, 1, meeting.id::text, meeting.end_date
+FROM public.meeting_members
+INNER JOIN public.meeting ON meeting.id = meeting_members.meeting_id
+WHERE meeting.end_date IS NOT NULL
+ON CONFLICT DO NOTHING;
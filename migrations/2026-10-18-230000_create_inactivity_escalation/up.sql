@@ -0,0 +1,16 @@
+CREATE TABLE public.inactivity_escalation
+(
+    id uuid NOT NULL DEFAULT gen_random_uuid (),
+    member_id uuid NOT NULL,
+    step integer NOT NULL,
+    -- start of the inactivity the step was taken for, steps are taken again
+    -- once the member was active and became inactive again
+    inactive_since date NOT NULL,
+    created_at timestamp NOT NULL DEFAULT now(),
+    PRIMARY KEY (id),
+    CONSTRAINT "UQ_inactivity_escalation_step" UNIQUE (member_id, step, inactive_since),
+    CONSTRAINT "FK_inactivity_escalation_member" FOREIGN KEY (member_id)
+        REFERENCES public.member (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
@@ -0,0 +1,19 @@
+CREATE TABLE public.member_leave
+(
+    id uuid NOT NULL DEFAULT gen_random_uuid (),
+    member_id uuid NOT NULL,
+    -- first and last day of the leave
+    start_date date NOT NULL,
+    end_date date NOT NULL,
+    reason text,
+    -- whether the member's return was announced
+    return_announced boolean NOT NULL DEFAULT false,
+    created_at timestamp NOT NULL DEFAULT now(),
+    PRIMARY KEY (id),
+    CONSTRAINT "UQ_member_leave_start" UNIQUE (member_id, start_date),
+    CONSTRAINT "CK_member_leave_period" CHECK (start_date <= end_date),
+    CONSTRAINT "FK_member_leave_member" FOREIGN KEY (member_id)
+        REFERENCES public.member (id) MATCH FULL
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
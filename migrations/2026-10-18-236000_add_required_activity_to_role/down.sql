@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS public.role
+    DROP COLUMN IF EXISTS required_activity;
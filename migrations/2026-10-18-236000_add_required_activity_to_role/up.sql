@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS public.role
+    ADD COLUMN required_activity integer NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS public.member
+    DROP CONSTRAINT IF EXISTS "UQ_member_wiki_id";
@@ -0,0 +1,17 @@
+-- a wiki account belongs to one member, duplicates are kept on current
+-- members rather than ex-members
+UPDATE public.member
+    SET wiki_id = NULL
+    WHERE id IN (
+        SELECT id FROM (
+            SELECT id, row_number() OVER (
+                PARTITION BY wiki_id ORDER BY role = -1, display_name
+            ) AS n
+            FROM public.member
+            WHERE wiki_id IS NOT NULL
+        ) AS holders
+        WHERE n > 1
+    );
+
+ALTER TABLE IF EXISTS public.member
+    ADD CONSTRAINT "UQ_member_wiki_id" UNIQUE (wiki_id);
@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS public.report
+    DROP COLUMN IF EXISTS imported;
@@ -0,0 +1,2 @@
+ALTER TABLE IF EXISTS public.report
+    ADD COLUMN imported boolean NOT NULL DEFAULT false;
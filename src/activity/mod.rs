@@ -0,0 +1,277 @@
+//! Weekly activity of members.
+//!
+//! Everything a member does that counts as activity is recorded as an
+//! activity event by one of the [`sources`], weighted by its source. A member
+//! is active on a day, or in a week, when the weights of their events add up
+//! to at least 1, and the member's `last_activity` is the latest day they
+//! were active. The events are grouped in weeks counted back from now, so the
+//! bot can tell who was active in at least some of the last weeks rather than
+//! only who was active since the threshold.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+use crate::{
+    database::models::{
+        activity_event::{ActivityEvent, ActivityKind},
+        member::Member,
+        role::{RequiredActivity, Role},
+    },
+    error::Error,
+};
+
+pub(crate) mod sources;
+
+use sources::SOURCES;
+
+/// Score a day or week needs to count as active.
+const ACTIVE_SCORE: f64 = 1.0;
+
+/// Whether the score counts as active, ignoring rounding errors of the
+/// weights' sum.
+pub(crate) fn is_active(score: f64) -> bool {
+    score + 1e-9 >= ACTIVE_SCORE
+}
+
+/// Sums the weights of the events in each of the last `weeks` weeks. The
+/// first score is of the last 7 days, the second of the 7 days before them
+/// and so on. Events in the future or older than `weeks` weeks are not
+/// counted.
+pub(crate) fn weekly_scores(
+    now: NaiveDateTime,
+    weeks: u32,
+    events: &[(NaiveDateTime, f64)],
+) -> Vec<f64> {
+    let mut scores = vec![0.0; weeks as usize];
+
+    for (event, weight) in events {
+        if *event > now {
+            continue;
+        }
+
+        // an event exactly 7 days ago belongs to the first week
+        let week = ((now - *event).num_seconds() - 1).max(0) / Duration::weeks(1).num_seconds();
+
+        if let Some(score) = scores.get_mut(week as usize) {
+            *score += weight;
+        }
+    }
+
+    scores
+}
+
+/// Returns in how many of the weeks the member was active.
+pub(crate) fn active_weeks(scores: &[f64]) -> usize {
+    scores.iter().filter(|score| is_active(**score)).count()
+}
+
+/// Returns the latest day whose events' weights add up to an active score.
+pub(crate) fn last_active_day(events: &[(NaiveDateTime, f64)]) -> Option<NaiveDate> {
+    let mut days: HashMap<NaiveDate, f64> = HashMap::new();
+
+    for (event, weight) in events {
+        *days.entry(event.date()).or_default() += weight;
+    }
+
+    days.into_iter()
+        .filter(|(_, score)| is_active(*score))
+        .map(|(day, _)| day)
+        .max()
+}
+
+/// Whether the day is within one of the leaves, given by their first and
+/// last days.
+pub(crate) fn is_on_leave(day: NaiveDate, leaves: &[(NaiveDate, NaiveDate)]) -> bool {
+    leaves
+        .iter()
+        .any(|(start, end)| *start <= day && day <= *end)
+}
+
+/// Counts the days after `since` up to `today` the member was not on leave.
+pub(crate) fn days_inactive(
+    since: NaiveDate,
+    today: NaiveDate,
+    leaves: &[(NaiveDate, NaiveDate)],
+) -> i64 {
+    since
+        .iter_days()
+        .skip(1)
+        .take_while(|day| *day <= today)
+        .filter(|day| !is_on_leave(*day, leaves))
+        .count() as i64
+}
+
+/// Whether the member is inactive: never active, or not active for
+/// `threshold_days` days they were not on leave. Members on leave are not
+/// inactive.
+pub(crate) fn is_inactive(
+    last_activity: Option<NaiveDate>,
+    today: NaiveDate,
+    leaves: &[(NaiveDate, NaiveDate)],
+    threshold_days: i64,
+) -> bool {
+    if is_on_leave(today, leaves) {
+        return false;
+    }
+
+    last_activity
+        .is_none_or(|last_activity| days_inactive(last_activity, today, leaves) >= threshold_days)
+}
+
+/// Formats the score with at most one decimal.
+pub(crate) fn display_score(score: f64) -> String {
+    format!("{}", (score * 10.0).round() / 10.0)
+}
+
+/// Returns the member's weekly activity scores over the last `weeks` weeks,
+/// counting only the activity their role requires.
+pub(crate) fn member_weekly_activity(member: &Member, weeks: u32) -> Result<Vec<f64>, Error> {
+    let now = chrono::Local::now().naive_local();
+    let required = member.role()?.required_activity();
+
+    let mut events =
+        ActivityEvent::occurred_since(member.id(), Some(now - Duration::weeks(weeks.into())))?;
+    events.retain(|(kind, _)| required.counts(*kind));
+
+    Ok(weekly_scores(now, weeks, &SOURCES.weigh(&events)))
+}
+
+/// Returns the current members with their weekly activity scores over the
+/// last `weeks` weeks, counting only the activity their roles require.
+pub(crate) fn weekly_activity(weeks: u32) -> Result<Vec<(Member, Vec<f64>)>, Error> {
+    let now = chrono::Local::now().naive_local();
+    let required = Role::all()?
+        .into_iter()
+        .map(|role| (role.id(), role.required_activity()))
+        .collect::<HashMap<_, _>>();
+
+    let mut events: HashMap<Uuid, Vec<(ActivityKind, NaiveDateTime)>> = HashMap::new();
+
+    for (member_id, kind, occurred_at) in
+        ActivityEvent::list_since(now - Duration::weeks(weeks.into()))?
+    {
+        events
+            .entry(member_id)
+            .or_default()
+            .push((kind, occurred_at));
+    }
+
+    Ok(Member::list_current()?
+        .into_iter()
+        .map(|member| {
+            let required = required
+                .get(&member.role_id())
+                .copied()
+                .unwrap_or(RequiredActivity::Any);
+
+            let events = events
+                .get(&member.id())
+                .into_iter()
+                .flatten()
+                .filter(|(kind, _)| required.counts(*kind))
+                .copied()
+                .collect::<Vec<_>>();
+
+            let scores = weekly_scores(now, weeks, &SOURCES.weigh(&events));
+
+            (member, scores)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+    use super::{
+        active_weeks, days_inactive, display_score, is_inactive, last_active_day, weekly_scores,
+    };
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn events_are_counted_in_their_weeks() {
+        let events = [
+            (now() - Duration::hours(1), 1.0),
+            (now() - Duration::days(3), 1.0),
+            (now() - Duration::days(7), 0.5),
+            (now() - Duration::days(8), 1.0),
+            (now() - Duration::days(22), 0.2),
+            (now() - Duration::days(40), 1.0),
+            (now() + Duration::days(1), 1.0),
+        ];
+
+        assert_eq!(weekly_scores(now(), 4, &events), vec![2.5, 1.0, 0.0, 0.2]);
+    }
+
+    #[test]
+    fn weeks_with_enough_activity_are_active() {
+        assert_eq!(active_weeks(&[2.5, 1.0, 0.0, 0.2]), 2);
+        assert_eq!(active_weeks(&[0.2 + 0.2 + 0.2 + 0.2 + 0.2]), 1);
+        assert_eq!(active_weeks(&[]), 0);
+    }
+
+    #[test]
+    fn last_active_day_needs_enough_activity() {
+        let events = [
+            (now() - Duration::days(1), 0.5),
+            (now() - Duration::days(2), 0.5),
+            (now() - Duration::days(2) + Duration::hours(1), 0.5),
+            (now(), 0.5),
+        ];
+
+        assert_eq!(
+            last_active_day(&events),
+            Some((now() - Duration::days(2)).date())
+        );
+        assert_eq!(last_active_day(&events[..1]), None);
+    }
+
+    #[test]
+    fn leave_days_are_not_inactive() {
+        let today = now().date();
+        let leave = (today - Duration::days(10), today - Duration::days(6));
+
+        assert_eq!(days_inactive(today - Duration::days(20), today, &[]), 20);
+        assert_eq!(
+            days_inactive(today - Duration::days(20), today, &[leave]),
+            15
+        );
+        assert_eq!(days_inactive(today, today, &[leave]), 0);
+
+        assert!(is_inactive(
+            Some(today - Duration::days(20)),
+            today,
+            &[],
+            20
+        ));
+        assert!(!is_inactive(
+            Some(today - Duration::days(20)),
+            today,
+            &[leave],
+            20
+        ));
+    }
+
+    #[test]
+    fn members_on_leave_are_not_inactive() {
+        let today = now().date();
+        let leave = (today - Duration::days(1), today + Duration::days(30));
+
+        assert!(is_inactive(None, today, &[], 20));
+        assert!(!is_inactive(None, today, &[leave], 20));
+    }
+
+    #[test]
+    fn scores_are_displayed_with_one_decimal() {
+        assert_eq!(display_score(2.0), "2");
+        assert_eq!(display_score(0.1 + 0.2), "0.3");
+    }
+}
@@ -0,0 +1,392 @@
+//! Where members' activity comes from.
+//!
+//! Every [`ActivitySource`] records one kind of activity event, with the
+//! weight configured in the `activity_sources` section. Reports and meetings
+//! are recorded by the commands and meetings producing them; messages and
+//! voice sessions are recorded from Discord events, only in the channels
+//! allowed for them. The enabled sources are collected in [`SOURCES`].
+
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::{Duration, NaiveDateTime};
+use lazy_static::lazy_static;
+use poise::serenity_prelude::{ChannelId, Message, UserId, VoiceState};
+use tracing::info;
+
+use crate::{
+    database::models::{
+        activity_event::{ActivityEvent, ActivityKind},
+        member::Member,
+    },
+    error::Error,
+    settings, SETTINGS,
+};
+
+lazy_static! {
+    pub(crate) static ref SOURCES: ActivitySources = ActivitySources::from_settings();
+    /// Users in tracked voice channels, with the channel and since when.
+    static ref VOICE_SESSIONS: Mutex<HashMap<UserId, VoiceSession>> = Mutex::new(HashMap::new());
+}
+
+/// A kind of activity counted towards members' activity.
+pub(crate) trait ActivitySource: Send + Sync {
+    fn kind(&self) -> ActivityKind;
+
+    /// Weight of every event of this source.
+    fn weight(&self) -> f64;
+
+    /// Whether activity in the channel counts. Sources not tied to channels
+    /// count everywhere.
+    fn tracks(&self, _channel: ChannelId) -> bool {
+        true
+    }
+
+    /// Records the past activity that has no event yet.
+    fn backfill(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+pub(crate) struct Reports {
+    pub weight: f64,
+}
+
+impl ActivitySource for Reports {
+    fn kind(&self) -> ActivityKind {
+        ActivityKind::Report
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn backfill(&self) -> Result<(), Error> {
+        ActivityEvent::backfill_reports()
+    }
+}
+
+pub(crate) struct Meetings {
+    pub weight: f64,
+}
+
+impl ActivitySource for Meetings {
+    fn kind(&self) -> ActivityKind {
+        ActivityKind::Meeting
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn backfill(&self) -> Result<(), Error> {
+        ActivityEvent::backfill_meetings()
+    }
+}
+
+pub(crate) struct Messages {
+    pub weight: f64,
+    pub channels: Vec<ChannelId>,
+}
+
+impl ActivitySource for Messages {
+    fn kind(&self) -> ActivityKind {
+        ActivityKind::Message
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn tracks(&self, channel: ChannelId) -> bool {
+        self.channels.contains(&channel)
+    }
+}
+
+pub(crate) struct Voice {
+    pub weight: f64,
+    pub channels: Vec<ChannelId>,
+}
+
+impl ActivitySource for Voice {
+    fn kind(&self) -> ActivityKind {
+        ActivityKind::Voice
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn tracks(&self, channel: ChannelId) -> bool {
+        self.channels.contains(&channel)
+    }
+}
+
+/// The enabled activity sources.
+pub(crate) struct ActivitySources {
+    sources: Vec<Box<dyn ActivitySource>>,
+}
+
+impl ActivitySources {
+    pub(crate) fn new(sources: Vec<Box<dyn ActivitySource>>) -> Self {
+        ActivitySources { sources }
+    }
+
+    fn from_settings() -> Self {
+        let settings::ActivitySources {
+            reports,
+            meetings,
+            messages,
+            voice,
+        } = &SETTINGS.activity_sources;
+
+        let mut sources: Vec<Box<dyn ActivitySource>> = Vec::new();
+
+        if reports.enabled {
+            sources.push(Box::new(Reports {
+                weight: reports.weight,
+            }));
+        }
+
+        if meetings.enabled {
+            sources.push(Box::new(Meetings {
+                weight: meetings.weight,
+            }));
+        }
+
+        if messages.enabled {
+            sources.push(Box::new(Messages {
+                weight: messages.weight,
+                channels: messages.channels.clone(),
+            }));
+        }
+
+        if voice.enabled {
+            sources.push(Box::new(Voice {
+                weight: voice.weight,
+                channels: voice.channels.clone(),
+            }));
+        }
+
+        info!(
+            "Enabled activity sources: {:?}",
+            sources
+                .iter()
+                .map(|source| source.kind())
+                .collect::<Vec<_>>()
+        );
+
+        ActivitySources::new(sources)
+    }
+
+    fn find(&self, kind: ActivityKind) -> Option<&dyn ActivitySource> {
+        self.sources
+            .iter()
+            .find(|source| source.kind() == kind)
+            .map(Box::as_ref)
+    }
+
+    /// Weight of the kind of activity, or `None` if its source is disabled.
+    pub(crate) fn weight(&self, kind: ActivityKind) -> Option<f64> {
+        self.find(kind).map(ActivitySource::weight)
+    }
+
+    /// Whether the kind of activity in the channel counts.
+    pub(crate) fn tracks(&self, kind: ActivityKind, channel: ChannelId) -> bool {
+        self.find(kind).is_some_and(|source| source.tracks(channel))
+    }
+
+    /// Weighs the events, leaving out the ones of disabled sources.
+    pub(crate) fn weigh(
+        &self,
+        events: &[(ActivityKind, NaiveDateTime)],
+    ) -> Vec<(NaiveDateTime, f64)> {
+        events
+            .iter()
+            .filter_map(|(kind, occurred_at)| Some((*occurred_at, self.weight(*kind)?)))
+            .collect()
+    }
+
+    /// Records the past activity of every source that has no event yet.
+    pub(crate) fn backfill(&self) -> Result<(), Error> {
+        for source in &self.sources {
+            source.backfill()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Records the message as activity of its author, if they are a member and
+/// the channel is tracked.
+pub(crate) fn message_sent(message: &Message) -> Result<(), Error> {
+    if message.author.bot
+        || message.guild_id != Some(SETTINGS.discord.server_id)
+        || !SOURCES.tracks(ActivityKind::Message, message.channel_id)
+    {
+        return Ok(());
+    }
+
+    let Ok(mut member) = Member::find_by_discord_id(message.author.id.get().to_string()) else {
+        return Ok(());
+    };
+
+    member.record_activity(
+        ActivityKind::Message,
+        message.id,
+        chrono::Local::now().naive_local(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VoiceSession {
+    channel: ChannelId,
+    joined_at: NaiveDateTime,
+}
+
+/// Returns the session that ended, if any, and the session after the user
+/// moved to `channel`, if it is tracked. Staying in the same channel, e.g.
+/// when muting, keeps the session.
+fn voice_session_change(
+    session: Option<VoiceSession>,
+    channel: Option<ChannelId>,
+    tracks: impl Fn(ChannelId) -> bool,
+    now: NaiveDateTime,
+) -> (Option<VoiceSession>, Option<VoiceSession>) {
+    if session.is_some() && session.map(|session| session.channel) == channel {
+        return (None, session);
+    }
+
+    let current = channel
+        .filter(|channel| tracks(*channel))
+        .map(|channel| VoiceSession {
+            channel,
+            joined_at: now,
+        });
+
+    (session, current)
+}
+
+/// Follows the user between voice channels, and records the time spent in a
+/// tracked channel once they leave it, if it was at least
+/// `activity_sources.voice.min_minutes`. Sessions ongoing when the bot stops
+/// are not recorded.
+pub(crate) fn voice_state_updated(state: &VoiceState) -> Result<(), Error> {
+    if state.guild_id != Some(SETTINGS.discord.server_id) {
+        return Ok(());
+    }
+
+    let now = chrono::Local::now().naive_local();
+
+    let ended = {
+        let mut sessions = VOICE_SESSIONS.lock().unwrap();
+
+        let (ended, current) = voice_session_change(
+            sessions.remove(&state.user_id),
+            state.channel_id,
+            |channel| SOURCES.tracks(ActivityKind::Voice, channel),
+            now,
+        );
+
+        if let Some(current) = current {
+            sessions.insert(state.user_id, current);
+        }
+
+        ended
+    };
+
+    let Some(ended) = ended else {
+        return Ok(());
+    };
+
+    if now - ended.joined_at < Duration::minutes(SETTINGS.activity_sources.voice.min_minutes) {
+        return Ok(());
+    }
+
+    let Ok(mut member) = Member::find_by_discord_id(state.user_id.get().to_string()) else {
+        return Ok(());
+    };
+
+    member.record_activity(
+        ActivityKind::Voice,
+        format!(
+            "{}:{}",
+            ended.channel,
+            ended.joined_at.and_utc().timestamp()
+        ),
+        now,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use poise::serenity_prelude::ChannelId;
+
+    use super::{voice_session_change, ActivitySources, Messages, Reports, VoiceSession};
+    use crate::database::models::activity_event::ActivityKind;
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn disabled_sources_are_left_out() {
+        let sources = ActivitySources::new(vec![
+            Box::new(Reports { weight: 1.0 }),
+            Box::new(Messages {
+                weight: 0.25,
+                channels: vec![ChannelId::new(1)],
+            }),
+        ]);
+
+        let events = [
+            (ActivityKind::Report, at(10)),
+            (ActivityKind::Message, at(11)),
+            (ActivityKind::Meeting, at(12)),
+        ];
+
+        assert_eq!(sources.weigh(&events), vec![(at(10), 1.0), (at(11), 0.25)]);
+        assert!(sources.tracks(ActivityKind::Message, ChannelId::new(1)));
+        assert!(!sources.tracks(ActivityKind::Message, ChannelId::new(2)));
+        assert!(!sources.tracks(ActivityKind::Voice, ChannelId::new(1)));
+    }
+
+    #[test]
+    fn voice_sessions_follow_tracked_channels() {
+        let tracked = ChannelId::new(1);
+        let other = ChannelId::new(2);
+        let tracks = |channel| channel == tracked;
+
+        let session = VoiceSession {
+            channel: tracked,
+            joined_at: at(10),
+        };
+
+        // joining a tracked channel starts a session, others are ignored
+        assert_eq!(
+            voice_session_change(None, Some(tracked), tracks, at(10)),
+            (None, Some(session))
+        );
+        assert_eq!(
+            voice_session_change(None, Some(other), tracks, at(10)),
+            (None, None)
+        );
+
+        // muting keeps the session, leaving or moving ends it
+        assert_eq!(
+            voice_session_change(Some(session), Some(tracked), tracks, at(11)),
+            (None, Some(session))
+        );
+        assert_eq!(
+            voice_session_change(Some(session), None, tracks, at(11)),
+            (Some(session), None)
+        );
+        assert_eq!(
+            voice_session_change(Some(session), Some(other), tracks, at(11)),
+            (Some(session), None)
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    serialize::{Output, ToSql},
+    sql_types::Integer,
+    upsert::excluded,
+    QueryDsl,
+};
+use uuid::Uuid;
+
+use crate::{
+    database::{pagination::Paginate, schema::activity_event, PG_POOL},
+    diesel::{ExpressionMethods, RunQueryDsl},
+    error::Error,
+    i18n::t,
+};
+
+/// Something a member did that counts as activity. `last_activity` of the
+/// member is the date of their latest event.
+#[derive(Queryable, Identifiable, Insertable, Debug, Clone)]
+#[diesel(table_name = activity_event)]
+pub struct ActivityEvent {
+    id: Uuid,
+    member_id: Uuid,
+    kind: ActivityKind,
+    /// ID of what the activity is about, e.g. the report or the meeting.
+    source_id: String,
+    occurred_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, FromSqlRow, PartialEq, Eq, AsExpression)]
+#[diesel(sql_type = diesel::sql_types::Integer)]
+pub enum ActivityKind {
+    /// A report was written.
+    Report = 0,
+    /// A meeting was attended.
+    Meeting = 1,
+    /// A message was sent in a tracked channel.
+    Message = 2,
+    /// Time was spent in a tracked voice channel.
+    Voice = 3, /* if you add more kinds, make sure to update the FromSql and ToSql
+                * implementation below */
+}
+
+impl ActivityKind {
+    pub fn localized(&self, locale: &str) -> String {
+        match self {
+            ActivityKind::Report => t!(locale, "activity.kind_report"),
+            ActivityKind::Meeting => t!(locale, "activity.kind_meeting"),
+            ActivityKind::Message => t!(locale, "activity.kind_message"),
+            ActivityKind::Voice => t!(locale, "activity.kind_voice"),
+        }
+    }
+}
+
+impl<DB> FromSql<Integer, DB> for ActivityKind
+where
+    DB: Backend,
+    i32: FromSql<Integer, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match i32::from_sql(bytes)? {
+            0 => Ok(ActivityKind::Report),
+            1 => Ok(ActivityKind::Meeting),
+            2 => Ok(ActivityKind::Message),
+            3 => Ok(ActivityKind::Voice),
+            x => Err(format!("Unrecognized activity kind: {}", x).into()),
+        }
+    }
+}
+
+impl<DB> ToSql<Integer, DB> for ActivityKind
+where
+    DB: Backend,
+    i32: ToSql<Integer, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> diesel::serialize::Result {
+        match self {
+            ActivityKind::Report => 0.to_sql(out),
+            ActivityKind::Meeting => 1.to_sql(out),
+            ActivityKind::Message => 2.to_sql(out),
+            ActivityKind::Voice => 3.to_sql(out),
+        }
+    }
+}
+
+impl ActivityEvent {
+    /// Records the activity, or moves it to `occurred_at` if it was already
+    /// recorded.
+    pub fn record(
+        member: Uuid,
+        activity_kind: ActivityKind,
+        source: impl ToString,
+        at: NaiveDateTime,
+    ) -> Result<Self, Error> {
+        use crate::database::schema::activity_event::dsl::*;
+
+        let event = ActivityEvent {
+            id: Uuid::new_v4(),
+            member_id: member,
+            kind: activity_kind,
+            source_id: source.to_string(),
+            occurred_at: at,
+        };
+
+        Ok(diesel::insert_into(activity_event)
+            .values(&event)
+            .on_conflict((member_id, kind, source_id))
+            .do_update()
+            .set(occurred_at.eq(excluded(occurred_at)))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    /// Removes the member's activity about the source. Returns `false` if
+    /// there was none.
+    pub fn remove(
+        member: Uuid,
+        activity_kind: ActivityKind,
+        source: impl ToString,
+    ) -> Result<bool, Error> {
+        use crate::database::schema::activity_event::dsl::*;
+
+        let deleted = diesel::delete(
+            activity_event
+                .filter(member_id.eq(member))
+                .filter(kind.eq(activity_kind))
+                .filter(source_id.eq(source.to_string())),
+        )
+        .execute(&mut PG_POOL.get()?)?;
+
+        Ok(deleted > 0)
+    }
+
+    /// Records the reports that have no event yet, e.g. imported reports.
+    pub fn backfill_reports() -> Result<(), Error> {
+        diesel::sql_query(
+            "INSERT INTO activity_event (member_id, kind, source_id, occurred_at) \
+             SELECT member_id, 0, id::text, create_date FROM report \
+             ON CONFLICT DO NOTHING",
+        )
+        .execute(&mut PG_POOL.get()?)?;
+
+        Ok(())
+    }
+
+    /// Records the attended meetings that have no event yet.
+    pub fn backfill_meetings() -> Result<(), Error> {
+        diesel::sql_query(
+            "INSERT INTO activity_event (member_id, kind, source_id, occurred_at) \
+             SELECT meeting_members.member_id, 1, meeting.id::text, meeting.end_date \
+             FROM meeting_members \
+             INNER JOIN meeting ON meeting.id = meeting_members.meeting_id \
+             WHERE meeting.end_date IS NOT NULL \
+             ON CONFLICT DO NOTHING",
+        )
+        .execute(&mut PG_POOL.get()?)?;
+
+        Ok(())
+    }
+
+    /// Returns the member's activity, newest first.
+    pub fn timeline(
+        member: Uuid,
+        page: i64,
+        per_page: Option<i64>,
+    ) -> Result<(Vec<Self>, i64), Error> {
+        use crate::database::schema::activity_event::dsl::*;
+
+        let mut query = activity_event
+            .filter(member_id.eq(member))
+            .order(occurred_at.desc())
+            .into_boxed()
+            .paginate(page);
+
+        if let Some(per_page) = per_page {
+            query = query.per_page(per_page);
+        }
+
+        Ok(query.load_and_count_pages(&mut PG_POOL.get().unwrap())?)
+    }
+
+    /// Returns what the member did when, since the given moment, or ever
+    /// without one.
+    pub fn occurred_since(
+        member: Uuid,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<(ActivityKind, NaiveDateTime)>, Error> {
+        use crate::database::schema::activity_event::dsl::*;
+
+        let mut query = activity_event
+            .select((kind, occurred_at))
+            .filter(member_id.eq(member))
+            .into_boxed();
+
+        if let Some(since) = since {
+            query = query.filter(occurred_at.ge(since));
+        }
+
+        Ok(query.load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns who did what when, since the given moment. Only members with
+    /// activity are included.
+    pub fn list_since(
+        since: NaiveDateTime,
+    ) -> Result<Vec<(Uuid, ActivityKind, NaiveDateTime)>, Error> {
+        use crate::database::schema::activity_event::dsl::*;
+
+        Ok(activity_event
+            .select((member_id, kind, occurred_at))
+            .filter(occurred_at.ge(since))
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn kind(&self) -> ActivityKind {
+        self.kind
+    }
+
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    pub fn occurred_at(&self) -> NaiveDateTime {
+        self.occurred_at
+    }
+}
@@ -0,0 +1,162 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    serialize::{Output, ToSql},
+    sql_types::Integer,
+    QueryDsl,
+};
+use uuid::Uuid;
+
+use crate::{
+    database::{pagination::Paginate, schema::inactivity_escalation, PG_POOL},
+    diesel::{ExpressionMethods, RunQueryDsl},
+    error::Error,
+    i18n::t,
+};
+
+/// Step taken because a member was inactive.
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = inactivity_escalation)]
+pub struct InactivityEscalation {
+    id: Uuid,
+    member_id: Uuid,
+    step: EscalationStep,
+    /// First day of the inactivity the step was taken for.
+    inactive_since: NaiveDate,
+    created_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, FromSqlRow, PartialEq, Eq, AsExpression)]
+#[diesel(sql_type = diesel::sql_types::Integer)]
+pub enum EscalationStep {
+    /// The member was reminded by DM.
+    Warned = 0,
+    /// The leads were told in the lead channel.
+    LeadsNotified = 1,
+    /// Admins were offered to offboard the member.
+    OffboardingProposed = 2,
+    /// The member was offboarded.
+    Offboarded = 3, /* if you add more steps, make sure to update the FromSql and ToSql
+                     * implementation below */
+}
+
+impl EscalationStep {
+    pub fn localized(&self, locale: &str) -> String {
+        match self {
+            EscalationStep::Warned => t!(locale, "inactivity.step_warned"),
+            EscalationStep::LeadsNotified => t!(locale, "inactivity.step_leads_notified"),
+            EscalationStep::OffboardingProposed => {
+                t!(locale, "inactivity.step_offboarding_proposed")
+            }
+            EscalationStep::Offboarded => t!(locale, "inactivity.step_offboarded"),
+        }
+    }
+}
+
+impl<DB> FromSql<Integer, DB> for EscalationStep
+where
+    DB: Backend,
+    i32: FromSql<Integer, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match i32::from_sql(bytes)? {
+            0 => Ok(EscalationStep::Warned),
+            1 => Ok(EscalationStep::LeadsNotified),
+            2 => Ok(EscalationStep::OffboardingProposed),
+            3 => Ok(EscalationStep::Offboarded),
+            x => Err(format!("Unrecognized escalation step: {}", x).into()),
+        }
+    }
+}
+
+impl<DB> ToSql<Integer, DB> for EscalationStep
+where
+    DB: Backend,
+    i32: ToSql<Integer, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> diesel::serialize::Result {
+        match self {
+            EscalationStep::Warned => 0.to_sql(out),
+            EscalationStep::LeadsNotified => 1.to_sql(out),
+            EscalationStep::OffboardingProposed => 2.to_sql(out),
+            EscalationStep::Offboarded => 3.to_sql(out),
+        }
+    }
+}
+
+impl InactivityEscalation {
+    /// Records the step taken for the member's inactivity since the given day.
+    pub fn record(
+        new_member_id: Uuid,
+        new_step: EscalationStep,
+        since: NaiveDate,
+    ) -> Result<Self, Error> {
+        use crate::database::schema::inactivity_escalation::dsl::*;
+
+        Ok(diesel::insert_into(inactivity_escalation)
+            .values((
+                member_id.eq(new_member_id),
+                step.eq(new_step),
+                inactive_since.eq(since),
+            ))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns the steps already taken for the member's inactivity since the
+    /// given day, with the day they were taken.
+    pub fn steps_taken(
+        find_member_id: Uuid,
+        since: NaiveDate,
+    ) -> Result<Vec<(EscalationStep, NaiveDate)>, Error> {
+        use crate::database::schema::inactivity_escalation::dsl::*;
+
+        Ok(inactivity_escalation
+            .select((step, created_at))
+            .filter(member_id.eq(find_member_id))
+            .filter(inactive_since.eq(since))
+            .load::<(EscalationStep, NaiveDateTime)>(&mut PG_POOL.get()?)?
+            .into_iter()
+            .map(|(taken_step, taken_at)| (taken_step, taken_at.date()))
+            .collect())
+    }
+
+    /// Returns the steps taken, newest first, optionally only the member's.
+    pub fn list(
+        page: i64,
+        per_page: Option<i64>,
+        find_member_id: Option<Uuid>,
+    ) -> Result<(Vec<Self>, i64), Error> {
+        use crate::database::schema::inactivity_escalation::dsl::*;
+
+        let mut query = inactivity_escalation.order(created_at.desc()).into_boxed();
+
+        if let Some(find_member_id) = find_member_id {
+            query = query.filter(member_id.eq(find_member_id));
+        }
+
+        let mut query = query.paginate(page);
+
+        if let Some(per_page) = per_page {
+            query = query.per_page(per_page);
+        }
+
+        Ok(query.load_and_count_pages(&mut PG_POOL.get().unwrap())?)
+    }
+
+    pub fn member_id(&self) -> Uuid {
+        self.member_id
+    }
+
+    pub fn step(&self) -> EscalationStep {
+        self.step
+    }
+
+    pub fn inactive_since(&self) -> NaiveDate {
+        self.inactive_since
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::NaiveDateTime;
+use diesel::{query_dsl::SaveChangesDsl, BoolExpressionMethods, QueryDsl};
+
+use super::role::Role;
+use crate::{
+    database::{schema::invite, PG_POOL},
+    diesel::{ExpressionMethods, RunQueryDsl},
+    error::Error,
+};
+
+/// Discord invite created with `/member invite`. Users joining through it
+/// become members with its role.
+#[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = invite, primary_key(code))]
+pub struct Invite {
+    code: String,
+    role: i32,
+    /// Discord ID of the admin who created the invite.
+    created_by: String,
+    created_at: NaiveDateTime,
+    expires_at: Option<NaiveDateTime>,
+    /// `0` for unlimited uses.
+    max_uses: i32,
+    uses: i32,
+}
+
+impl Invite {
+    pub fn new(
+        code: String,
+        role: &Role,
+        created_by: String,
+        expires_at: Option<NaiveDateTime>,
+        max_uses: i32,
+    ) -> Self {
+        Invite {
+            code,
+            role: role.id(),
+            created_by,
+            created_at: chrono::Local::now().naive_local(),
+            expires_at,
+            max_uses,
+            uses: 0,
+        }
+    }
+
+    pub fn insert(&self) -> Result<Self, Error> {
+        Ok(diesel::insert_into(invite::table)
+            .values(self)
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn update(&self) -> Result<Self, Error> {
+        Ok(self.save_changes(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns the invites that have not expired and have uses left.
+    pub fn list_active() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::invite::dsl::*;
+
+        let now = chrono::Local::now().naive_local();
+
+        Ok(invite
+            .filter(expires_at.is_null().or(expires_at.gt(now)))
+            .filter(max_uses.eq(0).or(uses.lt(max_uses)))
+            .order(created_at.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn set_uses(&mut self, new_uses: i32) -> Result<Self, Error> {
+        self.uses = new_uses;
+
+        self.update()
+    }
+
+    /// Stops tracking an invite that is gone from the server, because it was
+    /// used up, deleted or expired. `used` counts the join it was last used
+    /// for.
+    pub fn close(&mut self, used: bool) -> Result<Self, Error> {
+        if used {
+            self.uses += 1;
+        }
+        self.expires_at = Some(chrono::Local::now().naive_local());
+
+        self.update()
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn role(&self) -> Result<Role, Error> {
+        Role::find(self.role)
+    }
+
+    pub fn role_id(&self) -> i32 {
+        self.role
+    }
+
+    pub fn max_uses(&self) -> i32 {
+        self.max_uses
+    }
+
+    pub fn uses(&self) -> i32 {
+        self.uses
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{OptionalExtension, QueryDsl};
+use uuid::Uuid;
+
+use crate::{
+    database::{pagination::Paginate, schema::member_leave, PG_POOL},
+    diesel::{ExpressionMethods, RunQueryDsl},
+    error::Error,
+};
+
+/// Period a member is away, e.g. during exams, which does not count towards
+/// their inactivity.
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = member_leave)]
+pub struct Leave {
+    id: Uuid,
+    member_id: Uuid,
+    /// First day of the leave.
+    start_date: NaiveDate,
+    /// Last day of the leave.
+    end_date: NaiveDate,
+    reason: Option<String>,
+    return_announced: bool,
+    created_at: NaiveDateTime,
+}
+
+impl Leave {
+    pub fn insert(
+        new_member_id: Uuid,
+        start: NaiveDate,
+        end: NaiveDate,
+        new_reason: Option<String>,
+    ) -> Result<Self, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        if end < start {
+            return Err(Error::InvalidLeavePeriod);
+        }
+
+        Ok(diesel::insert_into(member_leave)
+            .values((
+                member_id.eq(new_member_id),
+                start_date.eq(start),
+                end_date.eq(end),
+                reason.eq(new_reason),
+            ))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn delete(&self) -> Result<usize, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        Ok(diesel::delete(member_leave.filter(id.eq(self.id))).execute(&mut PG_POOL.get()?)?)
+    }
+
+    /// Finds the member's leave starting on the day.
+    pub fn find(find_member_id: Uuid, start: NaiveDate) -> Result<Option<Self>, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        Ok(member_leave
+            .filter(member_id.eq(find_member_id))
+            .filter(start_date.eq(start))
+            .get_result(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
+    /// Returns the member's leave covering the day, if any.
+    pub fn on(find_member_id: Uuid, day: NaiveDate) -> Result<Option<Self>, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        Ok(member_leave
+            .filter(member_id.eq(find_member_id))
+            .filter(start_date.le(day))
+            .filter(end_date.ge(day))
+            .order(end_date.desc())
+            .first(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
+    /// Returns the leaves, latest first, optionally only the member's.
+    pub fn list(
+        page: i64,
+        per_page: Option<i64>,
+        find_member_id: Option<Uuid>,
+    ) -> Result<(Vec<Self>, i64), Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        let mut query = member_leave.order(start_date.desc()).into_boxed();
+
+        if let Some(find_member_id) = find_member_id {
+            query = query.filter(member_id.eq(find_member_id));
+        }
+
+        let mut query = query.paginate(page);
+
+        if let Some(per_page) = per_page {
+            query = query.per_page(per_page);
+        }
+
+        Ok(query.load_and_count_pages(&mut PG_POOL.get().unwrap())?)
+    }
+
+    /// Returns the first and last days of the member's leaves.
+    pub fn periods(find_member_id: Uuid) -> Result<Vec<(NaiveDate, NaiveDate)>, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        Ok(member_leave
+            .select((start_date, end_date))
+            .filter(member_id.eq(find_member_id))
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns the first and last days of every member's leaves.
+    pub fn periods_by_member() -> Result<HashMap<Uuid, Vec<(NaiveDate, NaiveDate)>>, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        let mut periods: HashMap<Uuid, Vec<(NaiveDate, NaiveDate)>> = HashMap::new();
+
+        for (leave_member_id, start, end) in member_leave
+            .select((member_id, start_date, end_date))
+            .load::<(Uuid, NaiveDate, NaiveDate)>(&mut PG_POOL.get()?)?
+        {
+            periods
+                .entry(leave_member_id)
+                .or_default()
+                .push((start, end));
+        }
+
+        Ok(periods)
+    }
+
+    /// Returns the leaves that ended before the day and whose return was not
+    /// announced yet.
+    pub fn returns_to_announce(day: NaiveDate) -> Result<Vec<Self>, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        Ok(member_leave
+            .filter(end_date.lt(day))
+            .filter(return_announced.eq(false))
+            .order(end_date.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn set_return_announced(&mut self) -> Result<Self, Error> {
+        use crate::database::schema::member_leave::dsl::*;
+
+        *self = diesel::update(member_leave.filter(id.eq(self.id)))
+            .set(return_announced.eq(true))
+            .get_result(&mut PG_POOL.get()?)?;
+
+        Ok(self.clone())
+    }
+
+    pub fn member_id(&self) -> Uuid {
+        self.member_id
+    }
+
+    pub fn start_date(&self) -> NaiveDate {
+        self.start_date
+    }
+
+    pub fn end_date(&self) -> NaiveDate {
+        self.end_date
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Whether the member's return from the leave was announced.
+    pub fn return_announced(&self) -> bool {
+        self.return_announced
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+}
@@ -5,7 +5,10 @@ use std::{
 
 use chrono::NaiveDateTime;
 use cron::Schedule;
-use diesel::{dsl::exists, query_dsl::SaveChangesDsl, select, BoolExpressionMethods, QueryDsl};
+use diesel::{
+    dsl::exists, query_dsl::SaveChangesDsl, select, BoolExpressionMethods, OptionalExtension,
+    QueryDsl,
+};
 use poise::{
     serenity_prelude::{self as serenity, CommandInteraction, Context, CreateCommandOption},
     SlashArgument,
@@ -15,7 +18,7 @@ use uuid::Uuid;
 
 use crate::{
     database::{
-        models::{member::Member, summary::Summary},
+        models::{activity_event::ActivityKind, member::Member, summary::Summary},
         pagination::Paginate,
         schema::{meeting, meeting_members},
         PG_POOL,
@@ -193,27 +196,15 @@ impl Meeting {
     }
 
     /// Removes member from the database and from the meeting.
-    /// Returns the formatted string with the result.
-    pub(crate) fn remove_member(&self, member: &mut Member) -> Result<String, Error> {
+    /// Returns `false` if the member was not in the meeting.
+    pub(crate) fn remove_member(&self, member: &mut Member) -> Result<bool, Error> {
         let rows = self._remove_member(member.id())?;
 
-        let mut output = String::new();
-
         if rows > 0 {
-            if let Some(end_date) = self.end_date {
-                member.update_activity(end_date.date())?;
-            }
-
-            output.push_str("Removed member <@");
-            output.push_str(member.discord_id().unwrap());
-            output.push('>');
-        } else {
-            output.push_str("Member <@");
-            output.push_str(member.discord_id().unwrap());
-            output.push_str("> is not in the meeting");
+            member.forget_activity(ActivityKind::Meeting, self.id)?;
         }
 
-        Ok(output)
+        Ok(rows > 0)
     }
 
     fn _remove_member(&self, user_id: Uuid) -> Result<usize, Error> {
@@ -228,10 +219,7 @@ impl Meeting {
     }
 
     /// Adds member from the database and from the meeting.
-    /// Returns the formatted string with the result.
-    pub(crate) fn add_member(&self, member: &mut Member) -> Result<String, Error> {
-        let mut output = String::new();
-
+    pub(crate) fn add_member(&self, member: &mut Member) -> Result<(), Error> {
         if MeetingMembers::is_user_in_meeting(self.id(), member.id())? {
             Err(Error::UserAlreadyInMeeting {
                 user_id: member.id(),
@@ -242,14 +230,10 @@ impl Meeting {
         self._add_member(member.id())?;
 
         if let Some(end_date) = self.end_date {
-            member.update_activity(end_date.date())?;
+            member.record_activity(ActivityKind::Meeting, self.id, end_date)?;
         }
 
-        output.push_str("Added member <@");
-        output.push_str(&member.discord_id().unwrap().to_string());
-        output.push('>');
-
-        Ok(output)
+        Ok(())
     }
 
     fn _add_member(&self, user_id: Uuid) -> Result<MeetingMembers, Error> {
@@ -289,6 +273,44 @@ impl Meeting {
         Ok(result)
     }
 
+    /// Counts the meetings the member attended that started since the given
+    /// moment.
+    pub(crate) fn count_attended_since(member: Uuid, since: NaiveDateTime) -> Result<i64, Error> {
+        use crate::database::schema::{meeting::dsl, meeting_members};
+
+        Ok(dsl::meeting
+            .inner_join(meeting_members::table)
+            .filter(meeting_members::member_id.eq(member))
+            .filter(dsl::start_date.ge(since))
+            .count()
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns the meetings the member attended, newest first.
+    pub(crate) fn list_attended(
+        member: Uuid,
+        page: i64,
+        page_size: Option<i64>,
+    ) -> Result<(Vec<Self>, i64), Error> {
+        use crate::database::schema::{meeting::dsl, meeting_members};
+
+        let mut query = dsl::meeting
+            .inner_join(meeting_members::table)
+            .filter(meeting_members::member_id.eq(member))
+            .select(crate::database::schema::meeting::all_columns)
+            .order_by(dsl::start_date.desc())
+            .into_boxed()
+            .paginate(page);
+
+        if let Some(page_size) = page_size {
+            query = query.per_page(page_size);
+        }
+
+        let result = query.load_and_count_pages::<Self>(&mut PG_POOL.get().unwrap())?;
+
+        Ok(result)
+    }
+
     pub(crate) fn members(&self) -> Result<Vec<Member>, Error> {
         use crate::database::schema::meeting_members::dsl::*;
 
@@ -301,6 +323,29 @@ impl Meeting {
         Ok(members)
     }
 
+    /// Returns the latest moment a report can be written and still count
+    /// towards this meeting's summary. `None` while the meeting has not ended.
+    pub(crate) fn report_cutoff(&self) -> Option<NaiveDateTime> {
+        self.end_date
+            .map(|end_date| report_cutoff(end_date, SETTINGS.meeting.report_cutoff_hours))
+    }
+
+    /// Finds the ended meeting a report written at the given time belongs
+    /// to: the earliest meeting whose report cut-off is not before it.
+    /// Returns `None` if the report belongs to a meeting that has not ended
+    /// yet.
+    pub(crate) fn find_by_report_date(date: NaiveDateTime) -> Result<Option<Self>, Error> {
+        use crate::database::schema::meeting::dsl::*;
+
+        let earliest_end = earliest_end(date, SETTINGS.meeting.report_cutoff_hours);
+
+        Ok(meeting
+            .filter(end_date.ge(earliest_end))
+            .order(end_date.asc())
+            .first(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
     pub(crate) fn find_by_summary_id(find_id: Uuid) -> Result<Self, Error> {
         use crate::database::schema::meeting::dsl::*;
 
@@ -310,6 +355,18 @@ impl Meeting {
     }
 }
 
+/// Returns the latest moment a report can be written and still count towards
+/// a meeting which ended at `end_date`.
+fn report_cutoff(end_date: NaiveDateTime, cutoff_hours: i64) -> NaiveDateTime {
+    end_date + chrono::Duration::hours(cutoff_hours)
+}
+
+/// Returns the earliest end of a meeting a report written at `date` still
+/// counts towards. Meetings which ended before have passed their cut-off.
+fn earliest_end(date: NaiveDateTime, cutoff_hours: i64) -> NaiveDateTime {
+    date - chrono::Duration::hours(cutoff_hours)
+}
+
 impl MeetingMembers {
     pub(crate) fn new(member_id: impl Into<Uuid>, meeting_id: impl Into<Uuid>) -> MeetingMembers {
         MeetingMembers {
@@ -432,3 +489,58 @@ impl SlashArgument for Meeting {
         builder.kind(poise::serenity_prelude::CommandOptionType::String)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+    use super::{earliest_end, report_cutoff};
+
+    const CUTOFF_HOURS: i64 = 24;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    /// The meeting a report belongs to, the way `find_by_report_date` picks
+    /// it: the earliest one whose cut-off has not passed.
+    fn meeting_of(report: NaiveDateTime, ends: &[NaiveDateTime]) -> Option<NaiveDateTime> {
+        ends.iter()
+            .copied()
+            .filter(|end| *end >= earliest_end(report, CUTOFF_HOURS))
+            .min()
+    }
+
+    #[test]
+    fn reports_count_until_the_cutoff() {
+        let end = at(6, 20);
+        let cutoff = report_cutoff(end, CUTOFF_HOURS);
+
+        assert_eq!(cutoff, at(7, 20));
+
+        for report in [at(6, 19), cutoff - Duration::seconds(1), cutoff] {
+            assert!(report <= cutoff);
+            assert_eq!(meeting_of(report, &[end]), Some(end));
+        }
+
+        let late = cutoff + Duration::seconds(1);
+
+        assert!(late > cutoff);
+        assert_eq!(meeting_of(late, &[end]), None);
+    }
+
+    #[test]
+    fn reports_between_meetings_belong_to_the_next_one() {
+        let first = at(6, 20);
+        let second = at(13, 20);
+        let ends = [second, first];
+
+        assert_eq!(meeting_of(at(7, 12), &ends), Some(first));
+        assert_eq!(meeting_of(at(7, 21), &ends), Some(second));
+        assert_eq!(meeting_of(at(10, 12), &ends), Some(second));
+        assert_eq!(meeting_of(at(14, 21), &ends), None);
+    }
+}
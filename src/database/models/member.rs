@@ -1,31 +1,40 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
 use diesel::{
-    backend::Backend,
-    deserialize::FromSql,
-    query_dsl::SaveChangesDsl,
-    serialize::{Output, ToSql},
-    sql_types::Integer,
-    BoolExpressionMethods, QueryDsl, Table,
+    query_dsl::SaveChangesDsl, OptionalExtension, PgArrayExpressionMethods, QueryDsl, Table,
 };
 use poise::{
-    serenity_prelude::{
-        self as serenity, CommandInteraction, Context, CreateCommandOption, ResolvedValue,
-    },
+    serenity_prelude::{CommandInteraction, Context, CreateCommandOption, ResolvedValue},
     SlashArgument,
 };
-use serenity::{http::CacheHttp, model::prelude::RoleId};
 use tracing::{error, warn};
 use uuid::Uuid;
 
+use super::{
+    activity_event::{ActivityEvent, ActivityKind},
+    leave::Leave,
+    profile_field::{self, ProfileField},
+    role::Role,
+    team::Team,
+};
 use crate::{
-    database::{pagination::Paginate, schema::member, PG_POOL},
+    activity::{self, sources::SOURCES},
+    database::{
+        pagination::{paginate_vec, Paginate},
+        schema::member,
+        PG_POOL,
+    },
     diesel::{ExpressionMethods, RunQueryDsl},
     error::Error,
+    i18n::t,
+    wiki::{
+        profile::{self, ProfileChange, WikiProfile},
+        WIKI,
+    },
     SETTINGS,
 };
 
-#[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug, Eq)]
+#[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug, Clone, Eq)]
 #[diesel(table_name = member)]
 pub struct Member {
     id: Uuid,
@@ -33,24 +42,36 @@ pub struct Member {
     discord_id: Option<String>,
     trello_id: Option<String>,
     trello_report_card_id: Option<String>,
-    role: MemberRole,
+    role: i32,
     wiki_id: Option<i64>,
     last_activity: Option<chrono::NaiveDate>,
+    /// When the member left the Discord server without being offboarded,
+    /// flagging them for review by an admin.
+    left_server_at: Option<chrono::NaiveDateTime>,
+    /// Values of the admin-defined [`ProfileField`]s by field name.
+    custom_fields: serde_json::Value,
+    /// Lowercase skills or tags, such as `art` or `audio`.
+    skills: Vec<String>,
+    /// Member guiding this one, usually an apprentice.
+    mentor_id: Option<Uuid>,
+    /// When the member got their current role.
+    role_since: chrono::NaiveDateTime,
+    /// When leads were last told the member is ready to be promoted.
+    promotion_notified_at: Option<chrono::NaiveDateTime>,
 }
 
-#[derive(
-    Copy, Clone, Default, Debug, FromSqlRow, PartialEq, Eq, AsExpression, poise::ChoiceParameter,
-)]
-#[diesel(sql_type = diesel::sql_types::Integer)]
-pub enum MemberRole {
-    #[name = "Ex-Member"]
-    ExMember = -1,
-    #[name = "Member"]
-    #[default]
-    Member = 0,
-    #[name = "Apprentice"]
-    Apprentice = 1, /* if you add more roles, make sure to update the FromSql and ToSql
-                     * implementation below */
+/// Splits a comma-separated list of skills, lowercasing them and dropping
+/// empty entries and duplicates.
+pub fn parse_skills(input: &str) -> Vec<String> {
+    let mut skills = Vec::new();
+
+    for skill in input.split(',').map(|s| s.trim().to_lowercase()) {
+        if !skill.is_empty() && !skills.contains(&skill) {
+            skills.push(skill);
+        }
+    }
+
+    skills
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, poise::ChoiceParameter)]
@@ -59,87 +80,21 @@ pub enum Activity {
     Inactive,
 }
 
-impl Display for Activity {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Activity {
+    /// Returns the lowercase name of the activity in the given locale.
+    pub fn localized(&self, locale: &str) -> String {
         match self {
-            Activity::Active => write!(f, "Active"),
-            Activity::Inactive => write!(f, "Inactive"),
+            Activity::Active => t!(locale, "activity.active"),
+            Activity::Inactive => t!(locale, "activity.inactive"),
         }
     }
 }
 
-impl MemberRole {
-    pub fn discord_role(&self) -> Option<RoleId> {
-        match self {
-            MemberRole::Member => Some(SETTINGS.discord.member_role),
-            MemberRole::Apprentice => Some(SETTINGS.discord.apprentice_role),
-            MemberRole::ExMember => None,
-        }
-    }
-
-    pub async fn add_role(&self, cache_http: &impl CacheHttp, member_id: u64) -> Result<(), Error> {
-        if let Some(role_id) = self.discord_role() {
-            cache_http
-                .http()
-                .add_member_role(SETTINGS.discord.server_id, member_id.into(), role_id, None)
-                .await?;
-        }
-
-        Ok(())
-    }
-
-    pub async fn remove_role(
-        &self,
-        cache_http: &impl CacheHttp,
-        member_id: u64,
-    ) -> Result<(), Error> {
-        if let Some(role_id) = self.discord_role() {
-            cache_http
-                .http()
-                .remove_member_role(SETTINGS.discord.server_id, member_id.into(), role_id, None)
-                .await?;
-        }
-
-        Ok(())
-    }
-
-    pub async fn swap_roles(
-        add_role: MemberRole,
-        remove_role: MemberRole,
-        cache_http: &impl CacheHttp,
-        member_id: u64,
-    ) -> Result<(), Error> {
-        add_role.add_role(cache_http, member_id).await?;
-        remove_role.remove_role(cache_http, member_id).await?;
-        Ok(())
-    }
-}
-
-impl<DB> FromSql<Integer, DB> for MemberRole
-where
-    DB: Backend,
-    i32: FromSql<Integer, DB>,
-{
-    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
-        match i32::from_sql(bytes)? {
-            -1 => Ok(MemberRole::ExMember),
-            0 => Ok(MemberRole::Member),
-            1 => Ok(MemberRole::Apprentice),
-            x => Err(format!("Unrecognized member role: {}", x).into()),
-        }
-    }
-}
-
-impl<DB> ToSql<Integer, DB> for MemberRole
-where
-    DB: Backend,
-    i32: ToSql<Integer, DB>,
-{
-    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> diesel::serialize::Result {
+impl Display for Activity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            MemberRole::ExMember => (-1).to_sql(out),
-            MemberRole::Member => 0.to_sql(out),
-            MemberRole::Apprentice => 1.to_sql(out),
+            Activity::Active => write!(f, "Active"),
+            Activity::Inactive => write!(f, "Inactive"),
         }
     }
 }
@@ -150,7 +105,7 @@ impl Member {
         discord_id: Option<String>,
         trello_id: Option<String>,
         trello_report_card_id: Option<String>,
-        role: MemberRole,
+        role: i32,
         wiki_id: Option<i64>,
     ) -> Member {
         Member {
@@ -162,6 +117,12 @@ impl Member {
             role,
             wiki_id,
             last_activity: None,
+            left_server_at: None,
+            custom_fields: serde_json::Value::Object(serde_json::Map::new()),
+            skills: Vec::new(),
+            mentor_id: None,
+            role_since: chrono::Local::now().naive_local(),
+            promotion_notified_at: None,
         }
     }
 
@@ -187,35 +148,45 @@ impl Member {
 
         Ok(diesel::update(member.filter(id.eq(self.id)))
             .set((
-                role.eq(MemberRole::ExMember),
+                role.eq(Role::EX_MEMBER),
                 discord_id.eq(None::<String>),
                 trello_id.eq(None::<String>),
                 trello_report_card_id.eq(None::<String>),
+                left_server_at.eq(None::<chrono::NaiveDateTime>),
             ))
             .execute(&mut PG_POOL.get()?)?)
     }
 
+    /// Flags the member as having left the Discord server, or clears the flag.
+    pub fn set_left_server(&mut self, left: bool) -> Result<Self, Error> {
+        use crate::database::schema::member::dsl;
+
+        self.left_server_at = left.then(|| chrono::Local::now().naive_local());
+
+        // `update` skips `None` fields, so the cleared flag is saved
+        // explicitly
+        Ok(diesel::update(dsl::member.filter(dsl::id.eq(self.id)))
+            .set(dsl::left_server_at.eq(self.left_server_at))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
     pub async fn unassign_wiki_group(&self, group_id: i64) -> Result<(), Error> {
-        crate::wiki::unassign_user_group(crate::wiki::unassign_user_group::Variables {
-            user_id: self.wiki_id.expect("User must have a wiki id"),
-            group_id,
-        })
-        .await
+        let wiki_id = self.wiki_id.ok_or(Error::NoWikiAccount)?;
+
+        Ok(WIKI.unassign_user_group(wiki_id, group_id).await?)
     }
 
     pub async fn assign_wiki_group(&self, group_id: i64) -> Result<(), Error> {
-        crate::wiki::assign_user_group(crate::wiki::assign_user_group::Variables {
-            user_id: self.wiki_id.expect("User must have a wiki id"),
-            group_id,
-        })
-        .await
+        let wiki_id = self.wiki_id.ok_or(Error::NoWikiAccount)?;
+
+        Ok(WIKI.assign_user_group(wiki_id, group_id).await?)
     }
 
     /// Assigns member appropriate group on wiki. This should be used when
     /// assigning a member role to a user. This function will also remove
     /// the guest group if the user had one.
     pub async fn assign_wiki_group_by_role(&self) -> Result<(), Error> {
-        let group_id = self.wiki_group();
+        let group_id = self.wiki_group()?;
 
         self.assign_wiki_group(group_id).await?;
 
@@ -226,31 +197,58 @@ impl Member {
     /// unassigning a member role to a user. This function will also remove
     /// the guest group if the user had one.
     pub async fn unassign_wiki_group_by_role(&self) -> Result<(), Error> {
-        let group_id = self.wiki_group();
+        let group_id = self.wiki_group()?;
 
         self.unassign_wiki_group(group_id).await?;
 
         Ok(())
     }
 
-    /// Returns wiki group id based on member role
-    pub fn wiki_group(&self) -> i64 {
-        match self.role {
-            MemberRole::Member => SETTINGS.wiki.member_group_id,
-            MemberRole::Apprentice => SETTINGS.wiki.member_group_id,
-            MemberRole::ExMember => SETTINGS.wiki.guest_group_id,
+    /// Returns the profile the member's wiki account should have. Accounts of
+    /// ex-members are inactive.
+    pub(crate) fn wiki_profile(&self) -> WikiProfile {
+        WikiProfile {
+            name: self.display_name.clone(),
+            is_active: self.role != Role::EX_MEMBER,
+        }
+    }
+
+    /// Pushes the member's profile to their wiki account. Returns the applied
+    /// changes, none if the member has no wiki account.
+    pub(crate) async fn sync_wiki_profile(&self) -> Result<Vec<ProfileChange>, Error> {
+        match self.wiki_id {
+            Some(wiki_id) => profile::sync_profile(wiki_id, &self.wiki_profile()).await,
+            None => Ok(Vec::new()),
         }
     }
 
+    /// Returns wiki group id based on member role
+    pub fn wiki_group(&self) -> Result<i64, Error> {
+        Ok(Member::wiki_group_of(&self.role()?))
+    }
+
+    /// Returns wiki group id of members with the given role. Roles without a
+    /// wiki group, such as ex-members, get the guest group.
+    pub fn wiki_group_of(role: &Role) -> i64 {
+        role.wiki_group_id().unwrap_or(SETTINGS.wiki.guest_group_id)
+    }
+
+    /// Lists the members matching the filters, ordered by name. Members are
+    /// inactive after their role's activity threshold, leaving out their
+    /// leaves; `inactive_after` keeps only the members who became inactive
+    /// after the day, e.g. since the previous meeting.
+    #[allow(clippy::too_many_arguments)]
     pub fn list(
         page: i64,
         per_page: Option<i64>,
-        role: Option<MemberRole>,
-        ignore_role: Option<MemberRole>,
+        role: Option<i32>,
+        ignore_role: Option<i32>,
         activity: Option<Activity>,
-        active_after: Option<chrono::NaiveDate>,
+        inactive_after: Option<chrono::NaiveDate>,
+        team: Option<i32>,
+        skill: Option<&str>,
     ) -> Result<(Vec<Self>, i64), Error> {
-        use crate::database::schema::member::dsl;
+        use crate::database::schema::{member::dsl, team_members};
 
         let mut query = dsl::member
             .select(dsl::member::all_columns())
@@ -265,29 +263,63 @@ impl Member {
             query = query.filter(dsl::role.ne(ignore_role));
         }
 
-        if let Some(activity) = activity {
-            match activity {
-                Activity::Active => {
-                    query = query.filter(
-                        dsl::last_activity.gt((chrono::Utc::now().naive_utc()
-                            - chrono::Duration::days(SETTINGS.activity_threshold_days))
-                        .date()),
-                    );
-                }
-                Activity::Inactive => {
-                    query = query.filter(
-                        dsl::last_activity
-                            .le((chrono::Utc::now().naive_utc()
-                                - chrono::Duration::days(SETTINGS.activity_threshold_days))
-                            .date())
-                            .or(dsl::last_activity.is_null()),
-                    );
-                }
-            }
+        if let Some(team) = team {
+            query = query.filter(
+                dsl::id.eq_any(
+                    team_members::table
+                        .filter(team_members::team_id.eq(team))
+                        .select(team_members::member_id),
+                ),
+            );
         }
 
-        if let Some(active_after) = active_after {
-            query = query.filter(dsl::last_activity.gt(active_after));
+        if let Some(skill) = skill {
+            query = query.filter(dsl::skills.contains(vec![skill.trim().to_lowercase()]));
+        }
+
+        // thresholds differ between roles and leaves are left out of the
+        // inactivity, so it is counted here
+        if activity.is_some() || inactive_after.is_some() {
+            let today = chrono::Local::now().date_naive();
+            let leaves = Leave::periods_by_member()?;
+            let thresholds = Role::all()?
+                .into_iter()
+                .map(|role| (role.id(), role.threshold_days()))
+                .collect::<HashMap<_, _>>();
+
+            let members = query
+                .load::<Member>(&mut PG_POOL.get()?)?
+                .into_iter()
+                .filter(|member| {
+                    let threshold_days = thresholds
+                        .get(&member.role)
+                        .copied()
+                        .unwrap_or(SETTINGS.activity_threshold_days);
+
+                    let inactive = activity::is_inactive(
+                        member.last_activity,
+                        today,
+                        leaves
+                            .get(&member.id)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default(),
+                        threshold_days,
+                    );
+
+                    let matches_activity = activity
+                        .is_none_or(|activity| inactive == (activity == Activity::Inactive));
+
+                    let matches_inactive_after = inactive_after.is_none_or(|day| {
+                        member.last_activity.is_some_and(|last_activity| {
+                            last_activity + chrono::Duration::days(threshold_days) > day
+                        })
+                    });
+
+                    matches_activity && matches_inactive_after
+                })
+                .collect();
+
+            return Ok(paginate_vec(members, page, per_page));
         }
 
         let mut query = query.paginate(page);
@@ -300,6 +332,57 @@ impl Member {
         Ok((vec, total_pages))
     }
 
+    /// Returns all members with the role, ordered by name.
+    pub fn list_with_role(find_role: i32) -> Result<Vec<Self>, Error> {
+        use crate::database::schema::member::dsl::*;
+
+        Ok(member
+            .filter(role.eq(find_role))
+            .order(display_name.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns all members except ex-members, ordered by name.
+    pub fn list_current() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::member::dsl::*;
+
+        Ok(member
+            .filter(role.ne(Role::EX_MEMBER))
+            .order(display_name.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns all members, including ex-members, that have a wiki account
+    pub fn list_with_wiki_account() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::member::dsl::*;
+
+        Ok(member
+            .filter(wiki_id.is_not_null())
+            .order(display_name.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns all members, including ex-members, that have a Discord account
+    pub fn list_with_discord_account() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::member::dsl::*;
+
+        Ok(member
+            .filter(discord_id.is_not_null())
+            .order(display_name.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns all members, including ex-members, that have a Trello report
+    /// card
+    pub fn list_with_report_card() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::member::dsl::*;
+
+        Ok(member
+            .filter(trello_report_card_id.is_not_null())
+            .order(display_name.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
     pub fn find_by_id(find_id: impl Into<Uuid>) -> Result<Self, Error> {
         use crate::database::schema::member::dsl::*;
 
@@ -318,11 +401,33 @@ impl Member {
             .get_result(&mut PG_POOL.get()?)?)
     }
 
-    /// Refreshes every member's activity by checking their last report or attended meeting
-    /// and updating their last activity date
+    pub fn find_by_wiki_id(find_id: i64) -> Result<Option<Self>, Error> {
+        use crate::database::schema::member::dsl::*;
+
+        Ok(member
+            .filter(wiki_id.eq(find_id))
+            .get_result(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
+    /// Fails if a member other than `except` already holds the wiki account.
+    pub fn check_wiki_id_free(find_id: i64, except: Option<Uuid>) -> Result<(), Error> {
+        match Member::find_by_wiki_id(find_id)? {
+            Some(holder) if Some(holder.id) != except => Err(Error::WikiAccountTaken {
+                wiki_id: find_id,
+                member: holder.name(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the missing activity events of every enabled activity source,
+    /// then refreshes every member's last activity date from their events
     pub fn refresh_all_activities() -> Result<(), Error> {
         use crate::database::schema::member::dsl;
 
+        SOURCES.backfill()?;
+
         let members = dsl::member
             .select(dsl::member::all_columns())
             .load::<Member>(&mut PG_POOL.get()?)?;
@@ -334,37 +439,69 @@ impl Member {
         Ok(())
     }
 
+    /// Sets the last activity date to the latest day the member was active
+    /// according to the enabled activity sources and their role's required
+    /// activity, clearing it if there is none.
     pub fn refresh_activity(&mut self) -> Result<(), Error> {
-        use crate::database::schema::{
-            meeting::dsl as meeting_dsl, meeting_members::dsl as meeting_members_dsl,
-            report::dsl as report_dsl,
-        };
+        use crate::database::schema::member::dsl;
 
-        let report_date = report_dsl::report
-            .select(diesel::dsl::max(report_dsl::create_date))
-            .filter(report_dsl::member_id.eq(self.id))
-            .get_result::<Option<chrono::NaiveDate>>(&mut PG_POOL.get()?)?;
-
-        let meeting_date = meeting_dsl::meeting
-            .select(diesel::dsl::max(meeting_dsl::end_date))
-            .inner_join(meeting_members_dsl::meeting_members)
-            .filter(meeting_members_dsl::member_id.eq(self.id))
-            .get_result::<Option<chrono::NaiveDateTime>>(&mut PG_POOL.get()?)?
-            .map(|dt| dt.date());
-
-        let last_activity = match (report_date, meeting_date) {
-            (Some(report_date), Some(meeting_date)) => report_date.max(meeting_date),
-            (Some(report_date), None) => report_date,
-            (None, Some(meeting_date)) => meeting_date,
-            (None, None) => {
-                warn!("Member {} has no report or meeting", self.id);
-                return Ok(());
-            }
-        };
+        let required = self.role()?.required_activity();
 
-        self.last_activity = Some(last_activity);
+        let mut events = ActivityEvent::occurred_since(self.id, None)?;
+        events.retain(|(kind, _)| required.counts(*kind));
+        let last_activity = activity::last_active_day(&SOURCES.weigh(&events));
 
-        self.update()?;
+        if last_activity.is_none() {
+            warn!("Member {} has no activity", self.id);
+        }
+
+        // save_changes skips `None`, so the date is cleared explicitly
+        *self = diesel::update(dsl::member.find(self.id))
+            .set(dsl::last_activity.eq(last_activity))
+            .get_result(&mut PG_POOL.get()?)?;
+
+        Ok(())
+    }
+
+    /// Records the member's activity and updates their last activity date if
+    /// it made the day active and is newer. Activity their role does not
+    /// require is only recorded.
+    pub fn record_activity(
+        &mut self,
+        kind: ActivityKind,
+        source_id: impl ToString,
+        at: chrono::NaiveDateTime,
+    ) -> Result<(), Error> {
+        ActivityEvent::record(self.id, kind, source_id, at)?;
+
+        let required = self.role()?.required_activity();
+
+        if SOURCES.weight(kind).is_none() || !required.counts(kind) {
+            return Ok(());
+        }
+
+        let day = ActivityEvent::occurred_since(self.id, Some(at.date().into()))?
+            .into_iter()
+            .filter(|(kind, occurred_at)| required.counts(*kind) && occurred_at.date() == at.date())
+            .collect::<Vec<_>>();
+
+        if let Some(date) = activity::last_active_day(&SOURCES.weigh(&day)) {
+            self.update_activity(date)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the member's activity, e.g. of a removed report, and derives
+    /// their last activity date again.
+    pub fn forget_activity(
+        &mut self,
+        kind: ActivityKind,
+        source_id: impl ToString,
+    ) -> Result<(), Error> {
+        if ActivityEvent::remove(self.id, kind, source_id)? {
+            self.refresh_activity()?;
+        }
 
         Ok(())
     }
@@ -415,6 +552,14 @@ impl Member {
         self.discord_id = Some(new_id);
     }
 
+    pub fn trello_id(&self) -> Option<&str> {
+        self.trello_id.as_deref()
+    }
+
+    pub fn trello_report_card_id(&self) -> Option<&str> {
+        self.trello_report_card_id.as_deref()
+    }
+
     pub fn set_trello_id(&mut self, new_id: String) {
         self.trello_id = Some(new_id);
     }
@@ -423,8 +568,46 @@ impl Member {
         self.trello_report_card_id = Some(new_id);
     }
 
-    pub fn set_role(&mut self, new_role: MemberRole) {
-        self.role = new_role;
+    pub fn set_role(&mut self, new_role: &Role) {
+        if self.role != new_role.id() {
+            self.role_since = chrono::Local::now().naive_local();
+        }
+
+        self.role = new_role.id();
+    }
+
+    pub fn role_since(&self) -> chrono::NaiveDateTime {
+        self.role_since
+    }
+
+    pub fn mentor_id(&self) -> Option<Uuid> {
+        self.mentor_id
+    }
+
+    /// Assigns the member's mentor, or removes it when `None`.
+    pub fn set_mentor(&mut self, new_mentor: Option<Uuid>) -> Result<Self, Error> {
+        use crate::database::schema::member::dsl;
+
+        self.mentor_id = new_mentor;
+
+        // `update` skips `None` fields, so a removed mentor is saved
+        // explicitly
+        Ok(diesel::update(dsl::member.filter(dsl::id.eq(self.id)))
+            .set(dsl::mentor_id.eq(self.mentor_id))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    /// Whether leads were told the member is ready to be promoted since they
+    /// got their current role.
+    pub fn promotion_notified(&self) -> bool {
+        self.promotion_notified_at
+            .is_some_and(|notified_at| notified_at >= self.role_since)
+    }
+
+    pub fn set_promotion_notified(&mut self) -> Result<Self, Error> {
+        self.promotion_notified_at = Some(chrono::Local::now().naive_local());
+
+        self.update()
     }
 
     pub fn set_wiki_id(&mut self, new_id: i64) {
@@ -435,7 +618,54 @@ impl Member {
         self.last_activity = Some(new_activity);
     }
 
-    pub fn role(&self) -> MemberRole {
+    pub fn skills(&self) -> &[String] {
+        &self.skills
+    }
+
+    pub fn set_skills(&mut self, new_skills: Vec<String>) {
+        self.skills = new_skills;
+    }
+
+    /// Returns the values of the member's profile fields, ordered by field
+    /// name, formatted in the locale.
+    pub fn custom_fields(&self, locale: &str) -> Vec<(String, String)> {
+        let mut fields = self
+            .custom_fields
+            .as_object()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|(name, value)| {
+                        (name.clone(), profile_field::display_value(value, locale))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        fields.sort();
+
+        fields
+    }
+
+    /// Sets the value of the profile field, or clears it when `None`.
+    pub fn set_custom_field(&mut self, field: &ProfileField, value: Option<serde_json::Value>) {
+        if !self.custom_fields.is_object() {
+            self.custom_fields = serde_json::Value::Object(serde_json::Map::new());
+        }
+
+        if let Some(fields) = self.custom_fields.as_object_mut() {
+            match value {
+                Some(value) => fields.insert(field.name().to_string(), value),
+                None => fields.remove(field.name()),
+            };
+        }
+    }
+
+    pub fn role(&self) -> Result<Role, Error> {
+        Role::find(self.role)
+    }
+
+    pub fn role_id(&self) -> i32 {
         self.role
     }
 
@@ -447,7 +677,18 @@ impl Member {
         self.last_activity
     }
 
-    pub fn display_activity(&self) -> String {
+    pub fn left_server_at(&self) -> Option<chrono::NaiveDateTime> {
+        self.left_server_at
+    }
+
+    /// Formats the member's last activity and the end of their current leave,
+    /// with the leaves loaded beforehand for a whole list with
+    /// `Leave::periods_by_member`.
+    pub fn display_activity(
+        &self,
+        locale: &str,
+        leaves: &HashMap<Uuid, Vec<(chrono::NaiveDate, chrono::NaiveDate)>>,
+    ) -> String {
         let user_name = self
             .discord_id()
             .map(|id| format!("<@{}>", id))
@@ -456,19 +697,30 @@ impl Member {
         let last_activity = self
             .last_activity()
             .map(|a| a.to_string())
-            .unwrap_or_else(|| "Never".to_owned());
-
-        format!("{} Last active: {}", user_name, last_activity)
-    }
-}
-
-impl Display for MemberRole {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            MemberRole::ExMember => write!(f, "Ex-Member"),
-            MemberRole::Member => write!(f, "Member"),
-            MemberRole::Apprentice => write!(f, "Apprentice"),
+            .unwrap_or_else(|| t!(locale, "activity.never"));
+
+        let mut output = t!(
+            locale,
+            "activity.last_active",
+            member = user_name,
+            date = last_activity
+        );
+
+        let today = chrono::Local::now().date_naive();
+        let on_leave_until = leaves
+            .get(&self.id)
+            .into_iter()
+            .flatten()
+            .filter(|(start, end)| *start <= today && *end >= today)
+            .map(|(_, end)| *end)
+            .max();
+
+        if let Some(until) = on_leave_until {
+            output.push(' ');
+            output.push_str(&t!(locale, "activity.on_leave", until = until));
         }
+
+        output
     }
 }
 
@@ -505,17 +757,39 @@ impl Display for Member {
             "Never".to_string()
         };
 
+        let role = Role::cached_name(self.role).unwrap_or_else(|| self.role.to_string());
+
         write!(
             f,
             "{} <@{}> ({}) Last active: {}, Trello ID: {}, Trello Report Card ID: {}, Wiki ID: {}",
-            self.role,
+            role,
             discord_id,
             self.id.simple(),
             activity,
             trello_id,
             trello_report_card_id,
             wiki_id
-        )
+        )?;
+
+        if let Some(left_server_at) = self.left_server_at {
+            write!(
+                f,
+                ", Left the server: {}",
+                left_server_at.format("%Y-%m-%d")
+            )?;
+        }
+
+        let teams = Team::cached_names(self.id);
+
+        if !teams.is_empty() {
+            write!(f, ", Teams: {}", teams.join(", "))?;
+        }
+
+        if !self.skills.is_empty() {
+            write!(f, ", Skills: {}", self.skills.join(", "))?;
+        }
+
+        Ok(())
     }
 }
 
@@ -567,3 +841,21 @@ impl SlashArgument for Member {
         builder.kind(poise::serenity_prelude::CommandOptionType::User)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_skills;
+
+    #[test]
+    fn skills_are_lowercased_and_deduplicated() {
+        assert_eq!(
+            parse_skills("Art, code,, ART , Audio"),
+            vec!["art", "code", "audio"]
+        );
+    }
+
+    #[test]
+    fn empty_skill_list_has_no_skills() {
+        assert!(parse_skills(" , ").is_empty());
+    }
+}
@@ -1,4 +1,13 @@
+pub mod activity_event;
+pub mod inactivity_escalation;
+pub mod invite;
+pub mod leave;
 pub mod meeting;
 pub mod member;
+pub mod offboarding;
+pub mod onboarding;
+pub mod profile_field;
 pub mod report;
+pub mod role;
 pub mod summary;
+pub mod team;
@@ -0,0 +1,139 @@
+use chrono::NaiveDateTime;
+use diesel::{query_dsl::SaveChangesDsl, QueryDsl};
+use uuid::Uuid;
+
+use super::{member::Member, role::Role};
+use crate::{
+    database::{schema::offboarding, PG_POOL},
+    diesel::{ExpressionMethods, OptionalExtension, RunQueryDsl},
+    error::Error,
+};
+
+/// Archived membership of an ex-member: why and when they left, and the
+/// external accounts they had, which are cleared from the member. The archive
+/// outlives the member if they are deleted from the database.
+#[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = offboarding)]
+pub struct Offboarding {
+    id: Uuid,
+    /// `None` once the member was deleted.
+    member_id: Option<Uuid>,
+    reason: String,
+    offboarded_at: NaiveDateTime,
+    previous_role: i32,
+    discord_id: Option<String>,
+    trello_id: Option<String>,
+    trello_report_card_id: Option<String>,
+    wiki_id: Option<i64>,
+    /// Integration steps that failed, as `integration: error`.
+    failed_steps: Vec<String>,
+    reinstated_at: Option<NaiveDateTime>,
+}
+
+impl Offboarding {
+    /// Archives the member's role and external accounts.
+    pub fn archive(
+        member: &Member,
+        reason: String,
+        failed_steps: Vec<String>,
+    ) -> Result<Self, Error> {
+        let new_offboarding = Offboarding {
+            id: Uuid::new_v4(),
+            member_id: Some(member.id()),
+            reason,
+            offboarded_at: chrono::Local::now().naive_local(),
+            previous_role: member.role_id(),
+            discord_id: member.discord_id().cloned(),
+            trello_id: member.trello_id().map(str::to_string),
+            trello_report_card_id: member.trello_report_card_id().map(str::to_string),
+            wiki_id: member.wiki_id(),
+            failed_steps,
+            reinstated_at: None,
+        };
+
+        Ok(diesel::insert_into(offboarding::table)
+            .values(&new_offboarding)
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn update(&self) -> Result<Self, Error> {
+        Ok(self.save_changes(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn find_by_id(find_id: Uuid) -> Result<Option<Self>, Error> {
+        use crate::database::schema::offboarding::dsl::*;
+
+        Ok(offboarding
+            .find(find_id)
+            .get_result(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
+    /// Returns the latest offboarding of the Discord account that was not
+    /// reinstated yet and whose member still exists.
+    pub fn find_by_discord_id(find_id: impl Into<String>) -> Result<Option<Self>, Error> {
+        use crate::database::schema::offboarding::dsl::*;
+
+        Ok(offboarding
+            .filter(discord_id.eq(find_id.into()))
+            .filter(reinstated_at.is_null())
+            .filter(member_id.is_not_null())
+            .order(offboarded_at.desc())
+            .first(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
+    pub fn set_reinstated(&mut self) -> Result<Self, Error> {
+        self.reinstated_at = Some(chrono::Local::now().naive_local());
+
+        self.update()
+    }
+
+    /// Restores the member's role and external accounts from the archive.
+    pub fn restore(&self, member: &mut Member) -> Result<(), Error> {
+        member.set_role(&self.previous_role()?);
+
+        if let Some(discord_id) = &self.discord_id {
+            member.set_discord_id(discord_id.clone());
+        }
+
+        if let Some(trello_id) = &self.trello_id {
+            member.set_trello_id(trello_id.clone());
+        }
+
+        if let Some(trello_report_card_id) = &self.trello_report_card_id {
+            member.set_trello_report_card_id(trello_report_card_id.clone());
+        }
+
+        if let Some(wiki_id) = self.wiki_id {
+            Member::check_wiki_id_free(wiki_id, Some(member.id()))?;
+            member.set_wiki_id(wiki_id);
+        }
+
+        Ok(())
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn reinstated_at(&self) -> Option<NaiveDateTime> {
+        self.reinstated_at
+    }
+
+    pub fn member_id(&self) -> Option<Uuid> {
+        self.member_id
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    pub fn offboarded_at(&self) -> NaiveDateTime {
+        self.offboarded_at
+    }
+
+    pub fn previous_role(&self) -> Result<Role, Error> {
+        Role::find(self.previous_role)
+    }
+}
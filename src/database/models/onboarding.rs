@@ -0,0 +1,246 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    query_dsl::SaveChangesDsl,
+    serialize::{Output, ToSql},
+    sql_types::Integer,
+    QueryDsl,
+};
+use uuid::Uuid;
+
+use crate::{
+    database::{schema::onboarding, PG_POOL},
+    diesel::{ExpressionMethods, OptionalExtension, RunQueryDsl},
+    error::Error,
+    i18n::t,
+};
+
+/// Progress of a new member's onboarding, persisted so it survives restarts.
+#[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = onboarding)]
+pub struct Onboarding {
+    id: Uuid,
+    member_id: Uuid,
+    step: OnboardingStep,
+    dm_channel_id: Option<String>,
+    dm_message_id: Option<String>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+    reminders_sent: i32,
+    reminded_at: Option<NaiveDateTime>,
+    last_error: Option<String>,
+}
+
+/// Steps of the onboarding, in order.
+#[derive(Copy, Clone, Debug, FromSqlRow, PartialEq, Eq, PartialOrd, Ord, AsExpression)]
+#[diesel(sql_type = diesel::sql_types::Integer)]
+pub enum OnboardingStep {
+    /// The member was added, the welcome DM was not sent yet.
+    Invited = 0,
+    /// The welcome DM was sent, waiting for the member's wiki email.
+    AwaitingWikiEmail = 1,
+    /// The member's wiki account is linked, the roles were not assigned yet.
+    WikiLinked = 2,
+    /// The integrations assigned the member's roles and groups.
+    RolesAssigned = 3,
+    Done = 4, /* if you add more steps, make sure to update the FromSql and ToSql
+               * implementation below */
+}
+
+impl OnboardingStep {
+    /// Whether the step waits for the new member instead of the bot.
+    pub fn awaits_member(&self) -> bool {
+        *self == OnboardingStep::AwaitingWikiEmail
+    }
+
+    pub fn localized(&self, locale: &str) -> String {
+        match self {
+            OnboardingStep::Invited => t!(locale, "onboarding.step_invited"),
+            OnboardingStep::AwaitingWikiEmail => t!(locale, "onboarding.step_awaiting_wiki_email"),
+            OnboardingStep::WikiLinked => t!(locale, "onboarding.step_wiki_linked"),
+            OnboardingStep::RolesAssigned => t!(locale, "onboarding.step_roles_assigned"),
+            OnboardingStep::Done => t!(locale, "onboarding.step_done"),
+        }
+    }
+}
+
+impl<DB> FromSql<Integer, DB> for OnboardingStep
+where
+    DB: Backend,
+    i32: FromSql<Integer, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match i32::from_sql(bytes)? {
+            0 => Ok(OnboardingStep::Invited),
+            1 => Ok(OnboardingStep::AwaitingWikiEmail),
+            2 => Ok(OnboardingStep::WikiLinked),
+            3 => Ok(OnboardingStep::RolesAssigned),
+            4 => Ok(OnboardingStep::Done),
+            x => Err(format!("Unrecognized onboarding step: {}", x).into()),
+        }
+    }
+}
+
+impl<DB> ToSql<Integer, DB> for OnboardingStep
+where
+    DB: Backend,
+    i32: ToSql<Integer, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> diesel::serialize::Result {
+        match self {
+            OnboardingStep::Invited => 0.to_sql(out),
+            OnboardingStep::AwaitingWikiEmail => 1.to_sql(out),
+            OnboardingStep::WikiLinked => 2.to_sql(out),
+            OnboardingStep::RolesAssigned => 3.to_sql(out),
+            OnboardingStep::Done => 4.to_sql(out),
+        }
+    }
+}
+
+impl Onboarding {
+    /// Starts the onboarding of the member, replacing a previous one.
+    pub fn start(member_id: Uuid) -> Result<Self, Error> {
+        let now = chrono::Local::now().naive_local();
+        let new_onboarding = Onboarding {
+            id: Uuid::new_v4(),
+            member_id,
+            step: OnboardingStep::Invited,
+            dm_channel_id: None,
+            dm_message_id: None,
+            created_at: now,
+            updated_at: now,
+            reminders_sent: 0,
+            reminded_at: None,
+            last_error: None,
+        };
+
+        Self::delete_for_member(member_id)?;
+
+        Ok(diesel::insert_into(onboarding::table)
+            .values(&new_onboarding)
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn update(&self) -> Result<Self, Error> {
+        Ok(self.save_changes(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn delete_for_member(member: Uuid) -> Result<usize, Error> {
+        use crate::database::schema::onboarding::dsl::*;
+
+        Ok(diesel::delete(onboarding.filter(member_id.eq(member))).execute(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn find_by_id(find_id: Uuid) -> Result<Option<Self>, Error> {
+        use crate::database::schema::onboarding::dsl::*;
+
+        Ok(onboarding
+            .find(find_id)
+            .get_result(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
+    pub fn find_by_member_id(member: Uuid) -> Result<Option<Self>, Error> {
+        use crate::database::schema::onboarding::dsl::*;
+
+        Ok(onboarding
+            .filter(member_id.eq(member))
+            .get_result(&mut PG_POOL.get()?)
+            .optional()?)
+    }
+
+    /// Returns all onboardings that are not done, oldest first.
+    pub fn list_pending() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::onboarding::dsl::*;
+
+        Ok(onboarding
+            .filter(step.ne(OnboardingStep::Done))
+            .order(created_at.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Moves the onboarding to the step and clears the last error.
+    pub fn set_step(&mut self, new_step: OnboardingStep) -> Result<Self, Error> {
+        self.step = new_step;
+        self.updated_at = chrono::Local::now().naive_local();
+        self.reminders_sent = 0;
+        self.reminded_at = None;
+        self.last_error = None;
+
+        // `update` skips `None` fields
+        self.save_all()
+    }
+
+    pub fn set_dm(&mut self, channel_id: String, message_id: String) -> Result<Self, Error> {
+        self.dm_channel_id = Some(channel_id);
+        self.dm_message_id = Some(message_id);
+
+        self.update()
+    }
+
+    pub fn set_last_error(&mut self, error: &impl std::fmt::Display) -> Result<Self, Error> {
+        self.last_error = Some(error.to_string());
+
+        self.update()
+    }
+
+    pub fn record_reminder(&mut self) -> Result<Self, Error> {
+        self.reminders_sent += 1;
+        self.reminded_at = Some(chrono::Local::now().naive_local());
+
+        self.update()
+    }
+
+    fn save_all(&self) -> Result<Self, Error> {
+        use crate::database::schema::onboarding::dsl::*;
+
+        Ok(diesel::update(onboarding.filter(id.eq(self.id)))
+            .set((
+                step.eq(self.step),
+                updated_at.eq(self.updated_at),
+                reminders_sent.eq(self.reminders_sent),
+                reminded_at.eq(self.reminded_at),
+                last_error.eq(&self.last_error),
+            ))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn member_id(&self) -> Uuid {
+        self.member_id
+    }
+
+    pub fn step(&self) -> OnboardingStep {
+        self.step
+    }
+
+    /// Returns the channel and ID of the message with the wiki email button.
+    pub fn dm(&self) -> Option<(&str, &str)> {
+        Some((
+            self.dm_channel_id.as_deref()?,
+            self.dm_message_id.as_deref()?,
+        ))
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    pub fn reminders_sent(&self) -> i32 {
+        self.reminders_sent
+    }
+
+    /// Time of the last reminder, or of reaching the current step if no
+    /// reminder was sent.
+    pub fn last_nudge(&self) -> NaiveDateTime {
+        self.reminded_at.unwrap_or(self.updated_at)
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
@@ -0,0 +1,203 @@
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    serialize::{Output, ToSql},
+    sql_types::Integer,
+    Connection, PgJsonbExpressionMethods, QueryDsl,
+};
+use poise::ChoiceParameter;
+use serde_json::Value;
+
+use crate::{
+    database::{schema::profile_field, PG_POOL},
+    diesel::{ExpressionMethods, RunQueryDsl},
+    error::Error,
+    i18n::t,
+};
+
+/// Admin-defined field of the member profile. The values are stored by field
+/// name in the member's `custom_fields`.
+#[derive(Queryable, Identifiable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = profile_field, primary_key(name))]
+pub struct ProfileField {
+    name: String,
+    kind: FieldKind,
+}
+
+/// Type of the values of a profile field.
+#[derive(Copy, Clone, Debug, FromSqlRow, PartialEq, Eq, AsExpression, poise::ChoiceParameter)]
+#[diesel(sql_type = diesel::sql_types::Integer)]
+pub enum FieldKind {
+    Text = 0,
+    Number = 1,
+    #[name = "Yes/no"]
+    Boolean = 2,
+    /// Stored as `YYYY-MM-DD`.
+    Date = 3, /* if you add more kinds, make sure to update the FromSql and ToSql
+               * implementation below */
+}
+
+impl FieldKind {
+    /// Parses a value typed by an admin into a JSON value of this kind.
+    pub fn parse(&self, input: &str) -> Result<Value, Error> {
+        let input = input.trim();
+        let invalid = || Error::InvalidFieldValue {
+            value: input.to_string(),
+            kind: self.name().to_lowercase(),
+        };
+
+        match self {
+            FieldKind::Text if input.is_empty() => Err(invalid()),
+            FieldKind::Text => Ok(Value::from(input)),
+            FieldKind::Number => {
+                if let Ok(number) = input.parse::<i64>() {
+                    return Ok(Value::from(number));
+                }
+
+                input
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .ok_or_else(invalid)
+            }
+            FieldKind::Boolean => match input.to_lowercase().as_str() {
+                "true" | "yes" | "y" => Ok(Value::Bool(true)),
+                "false" | "no" | "n" => Ok(Value::Bool(false)),
+                _ => Err(invalid()),
+            },
+            FieldKind::Date => chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .map(|date| Value::from(date.format("%Y-%m-%d").to_string()))
+                .map_err(|_| invalid()),
+        }
+    }
+}
+
+impl<DB> FromSql<Integer, DB> for FieldKind
+where
+    DB: Backend,
+    i32: FromSql<Integer, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match i32::from_sql(bytes)? {
+            0 => Ok(FieldKind::Text),
+            1 => Ok(FieldKind::Number),
+            2 => Ok(FieldKind::Boolean),
+            3 => Ok(FieldKind::Date),
+            x => Err(format!("Unrecognized profile field kind: {}", x).into()),
+        }
+    }
+}
+
+impl<DB> ToSql<Integer, DB> for FieldKind
+where
+    DB: Backend,
+    i32: ToSql<Integer, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> diesel::serialize::Result {
+        match self {
+            FieldKind::Text => 0.to_sql(out),
+            FieldKind::Number => 1.to_sql(out),
+            FieldKind::Boolean => 2.to_sql(out),
+            FieldKind::Date => 3.to_sql(out),
+        }
+    }
+}
+
+/// Formats a stored value for display, without the quotes of JSON strings.
+pub fn display_value(value: &Value, locale: &str) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Bool(true) => t!(locale, "field.yes"),
+        Value::Bool(false) => t!(locale, "field.no"),
+        other => other.to_string(),
+    }
+}
+
+impl ProfileField {
+    pub fn new(name: String, kind: FieldKind) -> Self {
+        ProfileField { name, kind }
+    }
+
+    pub fn insert(&self) -> Result<Self, Error> {
+        Ok(diesel::insert_into(profile_field::table)
+            .values(self)
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    /// Removes the field and its values from every member, in one
+    /// transaction so the values are only gone with the field.
+    pub fn delete(&self) -> Result<usize, Error> {
+        use crate::database::schema::{member, profile_field::dsl::*};
+
+        PG_POOL.get()?.transaction(|conn| {
+            diesel::update(member::table)
+                .set(member::custom_fields.eq(member::custom_fields.remove(self.name.as_str())))
+                .execute(conn)?;
+
+            Ok(diesel::delete(profile_field.filter(name.eq(&self.name))).execute(conn)?)
+        })
+    }
+
+    /// Returns all fields, ordered by name.
+    pub fn all() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::profile_field::dsl::*;
+
+        Ok(profile_field.order(name.asc()).load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Finds the field by name, ignoring case.
+    pub fn find(find_name: &str) -> Result<Self, Error> {
+        Self::all()?
+            .into_iter()
+            .find(|field| field.name.eq_ignore_ascii_case(find_name))
+            .ok_or_else(|| Error::ProfileFieldNotFound(find_name.to_string()))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> FieldKind {
+        self.kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{display_value, FieldKind};
+
+    #[test]
+    fn values_are_parsed_by_kind() {
+        assert_eq!(
+            FieldKind::Text.parse(" pixel art ").unwrap(),
+            Value::from("pixel art")
+        );
+        assert_eq!(FieldKind::Number.parse("3").unwrap(), Value::from(3));
+        assert_eq!(FieldKind::Number.parse("2.5").unwrap(), Value::from(2.5));
+        assert_eq!(FieldKind::Boolean.parse("Yes").unwrap(), Value::Bool(true));
+        assert_eq!(
+            FieldKind::Date.parse("2026-10-18").unwrap(),
+            Value::from("2026-10-18")
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert!(FieldKind::Text.parse("  ").is_err());
+        assert!(FieldKind::Number.parse("three").is_err());
+        assert!(FieldKind::Number.parse("NaN").is_err());
+        assert!(FieldKind::Boolean.parse("maybe").is_err());
+        assert!(FieldKind::Date.parse("18.10.2026").is_err());
+    }
+
+    #[test]
+    fn values_are_displayed_without_json_quotes() {
+        assert_eq!(display_value(&Value::from("Blender"), "en"), "Blender");
+        assert_eq!(display_value(&Value::Bool(false), "en"), "no");
+        assert_eq!(display_value(&Value::Bool(true), "pl"), "tak");
+        assert_eq!(display_value(&Value::from(4), "en"), "4");
+    }
+}
@@ -1,7 +1,10 @@
-use std::fmt::{Display, Formatter, Write};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter, Write},
+};
 
-use chrono::NaiveDate;
-use diesel::{query_dsl::SaveChangesDsl, QueryDsl, RunQueryDsl};
+use chrono::NaiveDateTime;
+use diesel::{query_dsl::SaveChangesDsl, Connection, QueryDsl, RunQueryDsl};
 use poise::{
     serenity_prelude::{
         self as serenity, CommandInteraction, Context, CreateCommandOption, ResolvedValue,
@@ -11,7 +14,7 @@ use poise::{
 use tracing::error;
 use uuid::Uuid;
 
-use super::summary::Summary;
+use super::{meeting::Meeting, summary::Summary};
 use crate::{
     database::{
         models::member::Member,
@@ -21,6 +24,7 @@ use crate::{
     },
     diesel::ExpressionMethods,
     error::Error,
+    trello::import::ImportedReport,
 };
 
 #[derive(Associations, Queryable, Identifiable, Insertable, AsChangeset, Debug)]
@@ -30,9 +34,12 @@ pub struct Report {
     id: Uuid,
     pub member_id: Uuid,
     pub content: String,
-    pub create_date: NaiveDate,
+    pub create_date: NaiveDateTime,
     published: bool,
     summary_id: Option<Uuid>,
+    trello_card_id: Option<String>,
+    trello_comment_id: Option<String>,
+    imported: bool,
 }
 
 #[derive(Insertable, Debug)]
@@ -40,6 +47,7 @@ pub struct Report {
 struct NewReport {
     member_id: Uuid,
     content: String,
+    create_date: NaiveDateTime,
 }
 
 impl Report {
@@ -48,20 +56,65 @@ impl Report {
             id: Uuid::new_v4(),
             member_id,
             content,
-            create_date: chrono::Local::now().naive_local().date(),
+            create_date: chrono::Local::now().naive_local(),
             published: false,
             summary_id: None,
+            trello_card_id: None,
+            trello_comment_id: None,
+            imported: false,
         }
     }
 
     pub fn insert(member_id: Uuid, content: String) -> Result<Self, Error> {
-        let new_report = NewReport { member_id, content };
+        let new_report = NewReport {
+            member_id,
+            content,
+            create_date: chrono::Local::now().naive_local(),
+        };
 
         Ok(diesel::insert_into(report::table)
             .values(&new_report)
             .get_result(&mut PG_POOL.get()?)?)
     }
 
+    /// Inserts reports imported from Trello comments, as already published,
+    /// in one transaction so a failure imports none of them. They keep the
+    /// comment only to skip it on the next import, and are never mirrored.
+    pub(crate) fn import(imported: &[&ImportedReport]) -> Result<usize, Error> {
+        PG_POOL.get()?.transaction(|conn| {
+            for imported in imported {
+                let new_report = Report {
+                    id: Uuid::new_v4(),
+                    member_id: imported.member_id,
+                    content: imported.content.clone(),
+                    create_date: imported.date,
+                    published: true,
+                    summary_id: None,
+                    trello_card_id: Some(imported.card_id.clone()),
+                    trello_comment_id: Some(imported.comment_id.clone()),
+                    imported: true,
+                };
+
+                diesel::insert_into(report::table)
+                    .values(&new_report)
+                    .execute(conn)?;
+            }
+
+            Ok(imported.len())
+        })
+    }
+
+    /// Returns the IDs of all Trello comments mirroring reports.
+    pub(crate) fn trello_comment_ids() -> Result<HashSet<String>, Error> {
+        Ok(dsl::report
+            .select(dsl::trello_comment_id)
+            .filter(dsl::trello_comment_id.is_not_null())
+            .load::<Option<String>>(&mut PG_POOL.get()?)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
     pub fn update(&self) -> Result<Self, Error> {
         Ok(self.save_changes(&mut PG_POOL.get()?)?)
     }
@@ -103,11 +156,37 @@ impl Report {
         Ok((reports, total_pages))
     }
 
-    /// Returns all unpublished reports
-    pub fn get_unpublished_reports() -> Result<Vec<Self>, Error> {
-        Ok(dsl::report
+    /// Counts the member's reports written since the given moment.
+    pub fn count_since(find_member_id: Uuid, since: NaiveDateTime) -> Result<i64, Error> {
+        Ok(report::table
+            .filter(dsl::member_id.eq(find_member_id))
+            .filter(dsl::create_date.ge(since))
+            .count()
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns unpublished reports that are not attached to any summary. If
+    /// `until` is set, only reports written up to that moment are returned.
+    pub fn get_unpublished_reports(until: Option<NaiveDateTime>) -> Result<Vec<Self>, Error> {
+        let mut query = dsl::report
             .filter(dsl::published.eq(false))
-            .load(&mut PG_POOL.get()?)?)
+            .filter(dsl::summary_id.is_null())
+            .into_boxed();
+
+        if let Some(until) = until {
+            query = query.filter(dsl::create_date.le(until));
+        }
+
+        Ok(query.load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Returns the summary of an already ended meeting this report still
+    /// belongs to, i.e. the report was written before that meeting's cut-off.
+    pub(crate) fn late_summary(&self) -> Result<Option<Summary>, Error> {
+        match Meeting::find_by_report_date(self.create_date)? {
+            Some(meeting) => Ok(Some(Summary::find_by_id(meeting.summary_id())?)),
+            None => Ok(None),
+        }
     }
 
     pub fn set_publish(&mut self) -> Result<Self, Error> {
@@ -116,6 +195,40 @@ impl Report {
         self.update()
     }
 
+    /// Whether the report was imported from a Trello comment, which is then
+    /// left as is.
+    pub(crate) fn imported(&self) -> bool {
+        self.imported
+    }
+
+    /// Returns the card and ID of the Trello comment mirroring the report.
+    pub(crate) fn trello_comment(&self) -> Option<(&str, &str)> {
+        Some((
+            self.trello_card_id.as_deref()?,
+            self.trello_comment_id.as_deref()?,
+        ))
+    }
+
+    pub(crate) fn set_trello_comment(
+        &mut self,
+        comment: Option<(String, String)>,
+    ) -> Result<Self, Error> {
+        (self.trello_card_id, self.trello_comment_id) = comment.unzip();
+
+        // `update` skips `None` fields, so an unmirrored report is saved
+        // explicitly
+        Ok(diesel::update(dsl::report.filter(dsl::id.eq(self.id)))
+            .set((
+                dsl::trello_card_id.eq(&self.trello_card_id),
+                dsl::trello_comment_id.eq(&self.trello_comment_id),
+            ))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
     pub(crate) fn summary_id(&self) -> Option<Uuid> {
         self.summary_id
     }
@@ -126,6 +239,15 @@ impl Report {
         self.update()
     }
 
+    /// Returns `true` if the report is attached to an already published
+    /// summary.
+    pub(crate) fn is_locked(&self) -> Result<bool, Error> {
+        match self.summary_id {
+            Some(summary_id) => Ok(Summary::find_by_id(summary_id)?.is_published()),
+            None => Ok(false),
+        }
+    }
+
     pub fn find_by_id(find_id: impl Into<Uuid>) -> Result<Self, Error> {
         use crate::database::schema::report::dsl::*;
 
@@ -134,46 +256,46 @@ impl Report {
         Ok(report.find(uuid).get_result(&mut PG_POOL.get()?)?)
     }
 
-    /// Returns formatted list of reports since last summary.
+    /// Returns reports since last summary, grouped by member. Each entry is
+    /// the member's name and their reports joined together.
+    ///
+    /// If the summary's meeting has already ended, only unattached reports
+    /// written before its report cut-off are included.
     ///
     /// If the summary is Some and publish is true, it will set the reports as
     /// published and set the summary id.
     pub(crate) async fn report_summary(
         summary: Option<Summary>,
         publish: bool,
-    ) -> Result<String, Error> {
-        let mut reports = Report::get_unpublished_reports()?;
+    ) -> Result<Vec<(String, String)>, Error> {
+        let cutoff = match &summary {
+            Some(summary) => Meeting::find_by_summary_id(summary.id())?.report_cutoff(),
+            None => None,
+        };
+
+        let mut reports = Report::get_unpublished_reports(cutoff)?;
 
         // get reports associated with summary
         if let Some(summary) = &summary {
-            let summary_reports = Report::get_by_summary_id(summary.id())?;
-
-            for report in summary_reports {
-                reports.push(report);
-            }
-
-            // delete the same reports
-            reports.dedup_by(|a, b| a.id == b.id);
+            reports.extend(Report::get_by_summary_id(summary.id())?);
         }
 
-        let mut output = String::new();
-        reports.sort_by_key(|r| r.member_id);
-        let mut previous_report: Option<Report> = None;
+        let mut output: Vec<(String, String)> = Vec::new();
+        reports.sort_by_key(|r| (r.member_id, r.create_date));
+        let mut previous_member_id: Option<Uuid> = None;
         for mut report in reports {
-            let member = Member::find_by_id(report.member_id)?;
-
-            // if report is from the same member as the previous report, don't print the
-            // member's name
-            if previous_report.is_some()
-                && previous_report.as_ref().unwrap().member_id == report.member_id
-            {
-                write!(&mut output, " {}", report.content)?;
-            } else {
-                if previous_report.is_some() {
-                    writeln!(&mut output)?;
+            // if report is from the same member as the previous report, append it to
+            // the member's entry
+            match output.last_mut() {
+                Some((_, content)) if previous_member_id == Some(report.member_id) => {
+                    write!(content, " {}", report.content)?;
+                }
+                _ => {
+                    let member = Member::find_by_id(report.member_id)?;
+                    output.push((member.name(), report.content.clone()));
                 }
-                write!(&mut output, "**{}:** {}", member.name(), report.content)?;
             }
+
             if publish {
                 if let Some(summary) = &summary {
                     report.set_publish()?;
@@ -181,7 +303,7 @@ impl Report {
                 }
             }
 
-            previous_report = Some(report);
+            previous_member_id = Some(report.member_id);
         }
         Ok(output)
     }
@@ -203,7 +325,7 @@ impl Display for Report {
             "Report {} by {} on {}",
             self.id.as_simple(),
             self.member_id.as_simple(),
-            self.create_date
+            self.create_date.format("%Y-%m-%d %H:%M")
         )?;
 
         if self.published {
@@ -0,0 +1,511 @@
+use std::{fmt::Display, sync::RwLock};
+
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    serialize::{Output, ToSql},
+    sql_types::Integer,
+    QueryDsl,
+};
+use lazy_static::lazy_static;
+use poise::{
+    serenity_prelude::{
+        self as serenity, CommandInteraction, Context, CreateCommandOption, ResolvedValue,
+    },
+    SlashArgument,
+};
+use serenity::{http::CacheHttp, model::prelude::RoleId};
+
+use crate::{
+    database::{models::activity_event::ActivityKind, schema::role, PG_POOL},
+    diesel::{ExpressionMethods, RunQueryDsl},
+    error::Error,
+    i18n::t,
+    SETTINGS,
+};
+
+lazy_static! {
+    /// Roles are read on every member lookup, so they are kept in memory and
+    /// reloaded whenever one of them changes.
+    static ref ROLES: RwLock<Vec<Role>> = RwLock::new(Vec::new());
+}
+
+/// Role of a member in the organization, from the roles table.
+#[derive(Queryable, Identifiable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = role)]
+pub struct Role {
+    id: i32,
+    name: String,
+    discord_role_id: Option<String>,
+    wiki_group_id: Option<i64>,
+    activity_threshold_days: Option<i32>,
+    permissions: Vec<String>,
+    required_activity: RequiredActivity,
+}
+
+/// Activity that keeps members with a role active.
+#[derive(Copy, Clone, Debug, FromSqlRow, PartialEq, Eq, AsExpression, poise::ChoiceParameter)]
+#[diesel(sql_type = diesel::sql_types::Integer)]
+pub enum RequiredActivity {
+    /// Any activity of the enabled activity sources.
+    #[name = "Any"]
+    Any = 0,
+    #[name = "Reports"]
+    Reports = 1,
+    #[name = "Meetings"]
+    Meetings = 2, /* if you add more activities, make sure to update the FromSql and ToSql
+                   * implementation below */
+}
+
+impl RequiredActivity {
+    /// Whether the kind of activity counts towards the activity of members
+    /// with the role.
+    pub fn counts(&self, kind: ActivityKind) -> bool {
+        match self {
+            RequiredActivity::Any => true,
+            RequiredActivity::Reports => kind == ActivityKind::Report,
+            RequiredActivity::Meetings => kind == ActivityKind::Meeting,
+        }
+    }
+
+    pub fn localized(&self, locale: &str) -> String {
+        match self {
+            RequiredActivity::Any => t!(locale, "role.required_any"),
+            RequiredActivity::Reports => t!(locale, "role.required_reports"),
+            RequiredActivity::Meetings => t!(locale, "role.required_meetings"),
+        }
+    }
+}
+
+impl<DB> FromSql<Integer, DB> for RequiredActivity
+where
+    DB: Backend,
+    i32: FromSql<Integer, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match i32::from_sql(bytes)? {
+            0 => Ok(RequiredActivity::Any),
+            1 => Ok(RequiredActivity::Reports),
+            2 => Ok(RequiredActivity::Meetings),
+            x => Err(format!("Unrecognized required activity: {}", x).into()),
+        }
+    }
+}
+
+impl<DB> ToSql<Integer, DB> for RequiredActivity
+where
+    DB: Backend,
+    i32: ToSql<Integer, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> diesel::serialize::Result {
+        match self {
+            RequiredActivity::Any => 0.to_sql(out),
+            RequiredActivity::Reports => 1.to_sql(out),
+            RequiredActivity::Meetings => 2.to_sql(out),
+        }
+    }
+}
+
+/// What members with a role may do besides managing their own data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum Permission {
+    /// Manage other members' reports, including locked ones.
+    #[name = "Manage reports"]
+    ManageReports,
+    /// Assign mentors and promote apprentices.
+    #[name = "Manage promotions"]
+    ManagePromotions,
+}
+
+impl Permission {
+    /// Returns the name the permission is stored under.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Permission::ManageReports => "manage_reports",
+            Permission::ManagePromotions => "manage_promotions",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "manage_reports" => Some(Permission::ManageReports),
+            "manage_promotions" => Some(Permission::ManagePromotions),
+            _ => None,
+        }
+    }
+}
+
+impl Role {
+    /// Role of former members. Members are moved to it when offboarded.
+    pub const EX_MEMBER: i32 = -1;
+    /// Role of new members.
+    pub const MEMBER: i32 = 0;
+    pub const APPRENTICE: i32 = 1;
+
+    /// Whether the role is one of the roles the bot relies on, which cannot
+    /// be removed.
+    pub fn is_built_in(&self) -> bool {
+        [Self::EX_MEMBER, Self::MEMBER, Self::APPRENTICE].contains(&self.id)
+    }
+
+    /// Adds a role with the given name and no Discord role, wiki group or
+    /// permissions.
+    pub fn insert(new_name: String) -> Result<Self, Error> {
+        use crate::database::schema::role::dsl::*;
+
+        let new_role = diesel::insert_into(role)
+            .values(name.eq(new_name))
+            .get_result(&mut PG_POOL.get()?)?;
+
+        Self::reload()?;
+
+        Ok(new_role)
+    }
+
+    /// Saves every field of the role, including cleared ones.
+    pub fn update(&self) -> Result<Self, Error> {
+        use crate::database::schema::role::dsl::*;
+
+        let updated = diesel::update(role.filter(id.eq(self.id)))
+            .set((
+                name.eq(&self.name),
+                discord_role_id.eq(&self.discord_role_id),
+                wiki_group_id.eq(self.wiki_group_id),
+                activity_threshold_days.eq(self.activity_threshold_days),
+                permissions.eq(&self.permissions),
+                required_activity.eq(self.required_activity),
+            ))
+            .get_result(&mut PG_POOL.get()?)?;
+
+        Self::reload()?;
+
+        Ok(updated)
+    }
+
+    /// Removes the role. Built-in roles and roles that members hold, or held
+    /// before being offboarded, cannot be removed.
+    pub fn delete(&self) -> Result<usize, Error> {
+        use crate::database::schema::{member, offboarding, role::dsl::*};
+
+        if self.is_built_in() {
+            return Err(Error::BuiltInRole(self.name.clone()));
+        }
+
+        let members = member::table
+            .filter(member::role.eq(self.id))
+            .count()
+            .get_result::<i64>(&mut PG_POOL.get()?)?;
+        let offboardings = offboarding::table
+            .filter(offboarding::previous_role.eq(self.id))
+            .count()
+            .get_result::<i64>(&mut PG_POOL.get()?)?;
+
+        if members + offboardings > 0 {
+            return Err(Error::RoleInUse(self.name.clone()));
+        }
+
+        let deleted = diesel::delete(role.filter(id.eq(self.id))).execute(&mut PG_POOL.get()?)?;
+
+        Self::reload()?;
+
+        Ok(deleted)
+    }
+
+    /// Loads the roles from the database into memory.
+    pub fn reload() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::role::dsl::*;
+
+        let roles: Vec<Self> = role.order(id.asc()).load(&mut PG_POOL.get()?)?;
+
+        *ROLES.write().unwrap() = roles.clone();
+
+        Ok(roles)
+    }
+
+    /// Returns all roles, ordered by id.
+    pub fn all() -> Result<Vec<Self>, Error> {
+        let roles = ROLES.read().unwrap().clone();
+
+        if roles.is_empty() {
+            return Self::reload();
+        }
+
+        Ok(roles)
+    }
+
+    fn find_cached(predicate: impl Fn(&Role) -> bool) -> Result<Option<Self>, Error> {
+        if let Some(role) = ROLES.read().unwrap().iter().find(|r| predicate(r)) {
+            return Ok(Some(role.clone()));
+        }
+
+        // the role may have been added since the roles were loaded
+        Ok(Self::reload()?.into_iter().find(predicate))
+    }
+
+    pub fn find(find_id: i32) -> Result<Self, Error> {
+        Self::find_cached(|r| r.id == find_id)?
+            .ok_or_else(|| Error::RoleNotFound(find_id.to_string()))
+    }
+
+    /// Finds the role by name, ignoring case.
+    pub fn find_by_name(find_name: &str) -> Result<Self, Error> {
+        Self::find_cached(|r| r.name.eq_ignore_ascii_case(find_name))?
+            .ok_or_else(|| Error::RoleNotFound(find_name.to_string()))
+    }
+
+    /// Returns the name of the role from memory, without touching the
+    /// database.
+    pub fn cached_name(find_id: i32) -> Option<String> {
+        ROLES
+            .read()
+            .unwrap()
+            .iter()
+            .find(|r| r.id == find_id)
+            .map(|r| r.name.clone())
+    }
+
+    /// Fills in the Discord roles and wiki groups of the built-in roles from
+    /// the deprecated `discord.member_role`, `discord.apprentice_role` and
+    /// `wiki.member_group_id` config options, unless they are already set.
+    pub fn seed_from_settings() -> Result<(), Error> {
+        let seeds = [
+            (Self::MEMBER, SETTINGS.discord.member_role),
+            (Self::APPRENTICE, SETTINGS.discord.apprentice_role),
+        ];
+
+        for (role_id, discord_role) in seeds {
+            let mut role = Self::find(role_id)?;
+            let mut changed = false;
+
+            if role.discord_role_id.is_none() {
+                if let Some(discord_role) = discord_role {
+                    role.discord_role_id = Some(discord_role.to_string());
+                    changed = true;
+                }
+            }
+
+            if role.wiki_group_id.is_none() && SETTINGS.wiki.member_group_id.is_some() {
+                role.wiki_group_id = SETTINGS.wiki.member_group_id;
+                changed = true;
+            }
+
+            if changed {
+                role.update()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn discord_role(&self) -> Option<RoleId> {
+        self.discord_role_id
+            .as_deref()?
+            .parse::<u64>()
+            .ok()
+            .filter(|id| *id != 0)
+            .map(RoleId::new)
+    }
+
+    pub async fn add_role(&self, cache_http: &impl CacheHttp, member_id: u64) -> Result<(), Error> {
+        if let Some(role_id) = self.discord_role() {
+            cache_http
+                .http()
+                .add_member_role(SETTINGS.discord.server_id, member_id.into(), role_id, None)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove_role(
+        &self,
+        cache_http: &impl CacheHttp,
+        member_id: u64,
+    ) -> Result<(), Error> {
+        if let Some(role_id) = self.discord_role() {
+            cache_http
+                .http()
+                .remove_member_role(SETTINGS.discord.server_id, member_id.into(), role_id, None)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn swap_roles(
+        add_role: &Role,
+        remove_role: &Role,
+        cache_http: &impl CacheHttp,
+        member_id: u64,
+    ) -> Result<(), Error> {
+        if add_role.discord_role() == remove_role.discord_role() {
+            return Ok(());
+        }
+
+        add_role.add_role(cache_http, member_id).await?;
+        remove_role.remove_role(cache_http, member_id).await?;
+        Ok(())
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.iter().any(|p| p == permission.key())
+    }
+
+    /// Returns the permissions the role grants, skipping unknown ones.
+    pub fn permissions(&self) -> Vec<Permission> {
+        self.permissions
+            .iter()
+            .filter_map(|p| Permission::from_key(p))
+            .collect()
+    }
+
+    pub fn grant(&mut self, permission: Permission) {
+        if !self.has_permission(permission) {
+            self.permissions.push(permission.key().to_string());
+        }
+    }
+
+    pub fn revoke(&mut self, permission: Permission) {
+        self.permissions.retain(|p| p != permission.key());
+    }
+
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn wiki_group_id(&self) -> Option<i64> {
+        self.wiki_group_id
+    }
+
+    pub fn activity_threshold_days(&self) -> Option<i32> {
+        self.activity_threshold_days
+    }
+
+    /// Days without activity after which members with the role are
+    /// inactive: the role's threshold, or `activity_threshold_days` if it has
+    /// none.
+    pub fn threshold_days(&self) -> i64 {
+        self.activity_threshold_days
+            .map_or(SETTINGS.activity_threshold_days, i64::from)
+    }
+
+    pub fn required_activity(&self) -> RequiredActivity {
+        self.required_activity
+    }
+
+    pub fn set_name(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    pub fn set_discord_role(&mut self, new_role: Option<RoleId>) {
+        self.discord_role_id = new_role.map(|r| r.to_string());
+    }
+
+    pub fn set_wiki_group_id(&mut self, new_group: Option<i64>) {
+        self.wiki_group_id = new_group;
+    }
+
+    pub fn set_activity_threshold_days(&mut self, new_threshold: Option<i32>) {
+        self.activity_threshold_days = new_threshold;
+    }
+
+    pub fn set_required_activity(&mut self, new_activity: RequiredActivity) {
+        self.required_activity = new_activity;
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[async_trait::async_trait]
+impl SlashArgument for Role {
+    async fn extract(
+        _ctx: &Context,
+        _interaction: &CommandInteraction,
+        value: &ResolvedValue<'_>,
+    ) -> Result<Self, poise::SlashArgError> {
+        let ResolvedValue::String(name) = value else {
+            return Err(poise::SlashArgError::new_command_structure_mismatch(
+                "Expected string",
+            ));
+        };
+
+        Role::find_by_name(name).map_err(|why| {
+            tracing::error!("Could not find role in database: {}", why);
+
+            poise::SlashArgError::new_command_structure_mismatch("Could not find role in database")
+        })
+    }
+
+    fn create(builder: CreateCommandOption) -> CreateCommandOption {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Permission, RequiredActivity, Role};
+    use crate::database::models::activity_event::ActivityKind;
+
+    fn role(discord_role_id: Option<&str>, permissions: &[&str]) -> Role {
+        Role {
+            id: 2,
+            name: "Lead".to_string(),
+            discord_role_id: discord_role_id.map(str::to_string),
+            wiki_group_id: None,
+            activity_threshold_days: None,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            required_activity: RequiredActivity::Any,
+        }
+    }
+
+    #[test]
+    fn permissions_are_granted_once_and_revoked() {
+        let mut role = role(None, &[]);
+
+        role.grant(Permission::ManageReports);
+        role.grant(Permission::ManageReports);
+
+        assert_eq!(role.permissions, vec!["manage_reports"]);
+        assert!(role.has_permission(Permission::ManageReports));
+
+        role.revoke(Permission::ManageReports);
+
+        assert!(!role.has_permission(Permission::ManageReports));
+    }
+
+    #[test]
+    fn unknown_permissions_are_skipped() {
+        let role = role(None, &["manage_reports", "fly", "manage_promotions"]);
+
+        assert_eq!(
+            role.permissions(),
+            vec![Permission::ManageReports, Permission::ManagePromotions]
+        );
+    }
+
+    #[test]
+    fn only_required_activity_counts() {
+        assert!(RequiredActivity::Any.counts(ActivityKind::Message));
+        assert!(RequiredActivity::Reports.counts(ActivityKind::Report));
+        assert!(!RequiredActivity::Reports.counts(ActivityKind::Meeting));
+        assert!(RequiredActivity::Meetings.counts(ActivityKind::Meeting));
+        assert!(!RequiredActivity::Meetings.counts(ActivityKind::Voice));
+    }
+
+    #[test]
+    fn invalid_discord_role_is_ignored() {
+        assert_eq!(role(Some("not a role"), &[]).discord_role(), None);
+        assert_eq!(
+            role(Some("42"), &[]).discord_role().map(|r| r.get()),
+            Some(42)
+        );
+    }
+}
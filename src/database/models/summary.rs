@@ -1,23 +1,37 @@
-use std::fmt::{Display, Formatter, Write};
+use std::fmt::{Display, Formatter};
 
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use diesel::{query_dsl::SaveChangesDsl, Table};
 use poise::{
-    serenity_prelude::{self as serenity, CommandInteraction, CreateCommandOption, ResolvedValue},
+    serenity_prelude::{
+        self as serenity, CommandInteraction, CreateCommandOption, CreateMessage, EditMessage,
+        ResolvedValue,
+    },
     SlashArgument,
 };
-use tracing::{error, info};
+use tracing::{error, warn};
 use uuid::Uuid;
 
 use super::{meeting::Meeting, report::Report};
 use crate::{
     database::{pagination::Paginate, schema::summary, PG_POOL},
     diesel::{ExpressionMethods, QueryDsl, RunQueryDsl},
-    discord::{split_message, Context},
+    discord::{
+        embed::{self, EmbedPage, EmbedSection, BLANK_FIELD_NAME},
+        Context,
+    },
     error::Error,
+    i18n::{self, t},
+    wiki::{
+        pages::{self, PageStore, WikiPage},
+        WIKI,
+    },
     SETTINGS,
 };
 
+const WIKI_DATE_FORMAT: &str = "%Y-%m-%d";
+const WIKI_TAG: &str = "meeting";
+
 #[derive(Queryable, Identifiable, Insertable, AsChangeset, Debug)]
 #[diesel(table_name = summary)]
 pub struct Summary {
@@ -25,6 +39,7 @@ pub struct Summary {
     note: String,
     create_date: NaiveDate,
     messages_id: Option<Vec<String>>,
+    wiki_path: Option<String>,
 }
 
 impl Summary {
@@ -34,6 +49,7 @@ impl Summary {
             note: content,
             create_date,
             messages_id: None,
+            wiki_path: None,
         }
     }
 
@@ -90,103 +106,212 @@ impl Summary {
         self.update()
     }
 
-    /// Generate summary for the meeting. Return the summary of reports and the
-    /// list of members that were present.
+    /// Generate summary for the meeting. Returns the summary's sections: the
+    /// list of members that were present, their reports and the note split
+    /// into agenda items.
     pub(crate) async fn generate_summary(
         &self,
         mut note: String,
         publish: bool,
-    ) -> Result<String, Error> {
-        let mut summary = String::new();
-
+    ) -> Result<Vec<EmbedSection>, Error> {
         if note.is_empty() {
             note = self.note().to_string();
         }
 
         let meeting = Meeting::find_by_summary_id(self.id)?;
 
+        // summaries are posted for the whole guild
+        let locale = i18n::guild_locale();
+
         let date_format = "%d.%m.%Y";
-        write!(
-            summary,
-            "**Raport ze spotkania {}**\n\n",
-            meeting.start_date().format(date_format)
-        )?;
-
-        let members = meeting.members()?;
-
-        summary.push_str("**Na spotkaniu pojawili się:** ");
-        for member in &members {
-            summary.push_str(&member.name());
-            // print comma if not last element
-            if member != members.last().unwrap() {
-                summary.push_str(", ");
-            }
-        }
+        let attendees = meeting
+            .members()?
+            .iter()
+            .map(|member| member.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let overview = EmbedSection::new(t!(
+            locale,
+            "summary.title",
+            date = meeting.start_date().format(date_format)
+        ))
+        .field(t!(locale, "summary.attendees"), attendees);
 
-        summary.push_str("\n\n**Raporty z tego tygodnia:**\n");
         let save_summary = Summary::find_by_id(meeting.summary_id())?;
 
-        summary.push_str(&Report::report_summary(Some(save_summary), publish).await?);
+        let reports = Report::report_summary(Some(save_summary), publish)
+            .await?
+            .into_iter()
+            .fold(
+                EmbedSection::new(t!(locale, "summary.reports")),
+                |section, (member, content)| section.field(member, content),
+            );
 
-        summary.push_str("\n**Notatka ze spotkania:**\n");
-        summary.push_str(&note);
+        let note = agenda_items(&note).into_iter().fold(
+            EmbedSection::new(t!(locale, "summary.note")),
+            |section, (item, content)| section.field(item, content),
+        );
 
-        Ok(summary)
+        Ok(vec![overview, reports, note])
     }
 
     /// Generates the summary of the meeting and sends it to the summary
-    /// channel. If set to resend. It will resend the summary to the summary
-    /// channel. If there are no previous summaries messages to resend to or
-    /// new summary is too long, it will return an error.
+    /// channel as embeds. If set to resend, it will edit the previously sent
+    /// messages instead, sending or deleting trailing messages when the
+    /// summary grew or shrank. If there are no previous summary messages to
+    /// resend to, it will return an error.
     pub(crate) async fn send_summary(
         mut self,
         ctx: Context<'_>,
         resend: bool,
     ) -> Result<String, Error> {
-        let summary = self.generate_summary(self.note().to_string(), true).await?;
-
-        if summary.is_empty() {
-            info!("Generated empty summary");
-            return Ok("Summary is empty. Nothing was sent".to_string());
-        }
+        let sections = self.generate_summary(self.note().to_string(), true).await?;
 
-        let messages = split_message(summary)?;
+        let messages = embed::paginate(sections.clone());
         let channel_id = SETTINGS.discord.summary_channel;
 
-        if resend {
-            // edit old messages only if there are the same number of messages
-            if let Some(messages_id) = self.messages_id() {
-                if messages_id.len() == messages.len() {
-                    for (message_id, message) in messages_id.iter().zip(messages.iter()) {
-                        channel_id
-                            .edit_message(
-                                ctx,
-                                message_id.parse::<u64>().unwrap(),
-                                serenity::builder::EditMessage::new().content(message),
-                            )
-                            .await?;
-                    }
-                } else {
-                    return Err(Error::SummaryTooLong);
+        let old_messages_id = if resend {
+            self.messages_id().ok_or(Error::NoSummaryMessages)?
+        } else {
+            Vec::new()
+        };
+
+        let mut messages_id = Vec::new();
+        for (index, pages) in messages.iter().enumerate() {
+            let embeds = pages.iter().map(EmbedPage::to_embed).collect::<Vec<_>>();
+
+            match old_messages_id.get(index) {
+                Some(message_id) => {
+                    channel_id
+                        .edit_message(
+                            ctx,
+                            message_id.parse::<u64>()?,
+                            EditMessage::new().content("").embeds(embeds),
+                        )
+                        .await?;
+
+                    messages_id.push(message_id.clone());
+                }
+                None => {
+                    let message_id = channel_id
+                        .send_message(ctx, CreateMessage::new().embeds(embeds))
+                        .await?
+                        .id
+                        .get();
+
+                    messages_id.push(message_id.to_string());
                 }
-            } else {
-                return Err(Error::NoSummaryMessages);
             }
-        } else {
-            let mut messages_id = Vec::new();
-            for message in messages {
-                let message_id = channel_id.say(ctx, message).await?.id.get();
+        }
+
+        // the summary got shorter, remove messages that are no longer needed
+        for message_id in old_messages_id.iter().skip(messages.len()) {
+            channel_id
+                .delete_message(ctx, message_id.parse::<u64>()?)
+                .await?;
+        }
+
+        self.set_messages_id(messages_id)?;
+
+        let locale = i18n::user_locale(ctx);
+        let mut output = t!(locale, "summary.sent", channel = channel_id.get());
+
+        output.push('\n');
 
-                messages_id.push(message_id.to_string());
+        // the summary is already on Discord, a wiki outage should not fail it
+        match self.publish_to_wiki(*WIKI, &sections).await {
+            Ok(()) => output.push_str(&t!(
+                locale,
+                "summary.wiki_published",
+                url = format!(
+                    "{}/{}",
+                    SETTINGS.wiki.url.trim_end_matches('/'),
+                    self.wiki_path().unwrap_or_default()
+                )
+            )),
+            Err(why) => {
+                warn!("Failed to publish summary to the wiki: {why}");
+
+                output.push_str(&t!(locale, "summary.wiki_failed", error = why));
             }
+        }
 
-            self.set_messages_id(messages_id)?;
+        Ok(output)
+    }
+
+    /// Writes the summary to its wiki page, creating or updating it, and
+    /// refreshes the index page of the summary's year.
+    pub(crate) async fn publish_to_wiki(
+        &mut self,
+        store: &dyn PageStore,
+        sections: &[EmbedSection],
+    ) -> Result<(), Error> {
+        let locale = i18n::guild_locale();
+        let prefix = &SETTINGS.wiki.summaries_path;
+
+        let page = WikiPage {
+            path: summary_page_path(prefix, self.create_date),
+            title: sections
+                .first()
+                .map(|section| section.title.clone())
+                .unwrap_or_default(),
+            description: t!(
+                locale,
+                "wiki.summary_description",
+                date = self.create_date.format(WIKI_DATE_FORMAT)
+            ),
+            content: summary_markdown(sections),
+            tags: vec![WIKI_TAG.to_string()],
+        };
+
+        pages::publish_page(store, &page).await?;
+
+        if self.wiki_path.as_ref() != Some(&page.path) {
+            self.wiki_path = Some(page.path);
+            self.update()?;
         }
 
-        Ok(format!(
-            "Summary was generated and sent to the <#{channel_id}>",
-            channel_id = channel_id.get()
-        ))
+        let year = self.create_date.year();
+        let entries = Summary::list_on_wiki(year)?
+            .into_iter()
+            .filter_map(|summary| Some((summary.create_date, summary.wiki_path?)))
+            .collect::<Vec<_>>();
+
+        let index = WikiPage {
+            path: index_page_path(prefix, year),
+            title: t!(locale, "wiki.index_title", year = year),
+            description: t!(locale, "wiki.index_description", year = year),
+            content: index_markdown(locale, &entries),
+            tags: vec![WIKI_TAG.to_string()],
+        };
+
+        pages::publish_page(store, &index).await?;
+
+        Ok(())
+    }
+
+    /// Summaries from the given year that were published on the wiki, newest
+    /// first.
+    fn list_on_wiki(year: i32) -> Result<Vec<Self>, Error> {
+        use crate::database::schema::summary::dsl::*;
+
+        let (Some(first_day), Some(last_day)) = (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year, 12, 31),
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        Ok(summary
+            .filter(create_date.between(first_day, last_day))
+            .filter(wiki_path.is_not_null())
+            .order(create_date.desc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    pub(crate) fn wiki_path(&self) -> Option<&str> {
+        self.wiki_path.as_deref()
     }
 
     pub(crate) fn messages_id(&self) -> Option<Vec<String>> {
@@ -208,6 +333,97 @@ impl Summary {
     }
 }
 
+fn summary_page_path(prefix: &str, date: NaiveDate) -> String {
+    format!("{prefix}/{}", date.format(WIKI_DATE_FORMAT))
+}
+
+fn index_page_path(prefix: &str, year: i32) -> String {
+    format!("{prefix}/{year}")
+}
+
+/// Renders the summary's sections as a markdown page. Fields without a name
+/// are written as plain paragraphs.
+fn summary_markdown(sections: &[EmbedSection]) -> String {
+    let mut content = String::new();
+
+    for section in sections {
+        content.push_str(&format!("# {}\n\n", section.title));
+
+        for (name, value) in &section.fields {
+            if name != BLANK_FIELD_NAME {
+                content.push_str(&format!("## {name}\n\n"));
+            }
+
+            content.push_str(&format!("{}\n\n", value.trim()));
+        }
+    }
+
+    content.trim_end().to_string()
+}
+
+/// Renders the index page listing the year's summaries, given as the
+/// summary's date and wiki path.
+fn index_markdown(locale: &str, entries: &[(NaiveDate, String)]) -> String {
+    entries
+        .iter()
+        .map(|(date, path)| {
+            format!(
+                "- [{}](/{path})",
+                t!(locale, "summary.title", date = date.format("%d.%m.%Y"))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Splits the note into agenda items. A line consisting only of a bold text
+/// (`**Item**`) or a markdown heading (`# Item`) starts a new item; text before
+/// the first item is returned under a blank name.
+fn agenda_items(note: &str) -> Vec<(String, String)> {
+    let mut items = vec![(BLANK_FIELD_NAME.to_string(), String::new())];
+
+    for line in note.lines() {
+        let trimmed = line.trim();
+
+        let heading = trimmed
+            .strip_prefix("**")
+            .and_then(|heading| heading.strip_suffix("**"))
+            .filter(|heading| !heading.contains("**"))
+            .or_else(|| {
+                trimmed
+                    .strip_prefix('#')
+                    .map(|heading| heading.trim_start_matches('#'))
+            })
+            .map(str::trim)
+            .filter(|heading| !heading.is_empty());
+
+        match heading {
+            Some(heading) => items.push((heading.to_string(), String::new())),
+            None => {
+                let (_, content) = items.last_mut().expect("items are never empty");
+                content.push_str(line);
+                content.push('\n');
+            }
+        }
+    }
+
+    items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, (item, content))| {
+            let content = content.trim();
+
+            match (index, content.is_empty()) {
+                // nothing before the first agenda item
+                (0, true) => None,
+                // agenda items without any content are still listed
+                (_, true) => Some((item, "-".to_string())),
+                (_, false) => Some((item, content.to_string())),
+            }
+        })
+        .collect()
+}
+
 impl Display for Summary {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -280,3 +496,110 @@ impl SlashArgument for Summary {
         builder.kind(poise::serenity_prelude::CommandOptionType::String)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{
+        agenda_items, index_markdown, index_page_path, summary_markdown, summary_page_path,
+    };
+    use crate::discord::embed::{EmbedSection, BLANK_FIELD_NAME};
+
+    #[test]
+    fn wiki_paths_use_date_and_year() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 7).unwrap();
+
+        assert_eq!(summary_page_path("meetings", date), "meetings/2024-05-07");
+        assert_eq!(index_page_path("meetings", 2024), "meetings/2024");
+    }
+
+    #[test]
+    fn summary_is_rendered_as_markdown() {
+        let sections = vec![
+            EmbedSection::new("Report").field("Present", "Alice, Bob"),
+            EmbedSection::new("Note").field(BLANK_FIELD_NAME, "Intro"),
+        ];
+
+        assert_eq!(
+            summary_markdown(&sections),
+            "# Report\n\n## Present\n\nAlice, Bob\n\n# Note\n\nIntro"
+        );
+    }
+
+    #[test]
+    fn index_links_to_summaries() {
+        let entries = vec![
+            (
+                NaiveDate::from_ymd_opt(2024, 5, 14).unwrap(),
+                "meetings/2024-05-14".to_string(),
+            ),
+            (
+                NaiveDate::from_ymd_opt(2024, 5, 7).unwrap(),
+                "meetings/2024-05-07".to_string(),
+            ),
+        ];
+
+        let index = index_markdown("en", &entries);
+
+        assert_eq!(
+            index.lines().collect::<Vec<_>>(),
+            vec![
+                "- [Meeting report 14.05.2024](/meetings/2024-05-14)",
+                "- [Meeting report 07.05.2024](/meetings/2024-05-07)",
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_note_is_a_single_item() {
+        let items = agenda_items("Discussed the roadmap.\nSecond line.");
+
+        assert_eq!(
+            items,
+            vec![(
+                BLANK_FIELD_NAME.to_string(),
+                "Discussed the roadmap.\nSecond line.".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn bold_lines_and_headings_start_items() {
+        let items = agenda_items("Intro\n**Art**\nNew sprites\n## Code\nFixed bugs");
+
+        assert_eq!(
+            items,
+            vec![
+                (BLANK_FIELD_NAME.to_string(), "Intro".to_string()),
+                ("Art".to_string(), "New sprites".to_string()),
+                ("Code".to_string(), "Fixed bugs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bold_text_inside_a_line_is_not_an_item() {
+        let items = agenda_items("**Art** and **Code** went well");
+
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn empty_items_are_kept() {
+        let items = agenda_items("**Art**\n\n**Code**\nFixed bugs");
+
+        assert_eq!(
+            items,
+            vec![
+                ("Art".to_string(), "-".to_string()),
+                ("Code".to_string(), "Fixed bugs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_note_has_no_items() {
+        assert!(agenda_items("").is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use std::{collections::HashMap, fmt::Display, sync::RwLock};
+
+use diesel::QueryDsl;
+use lazy_static::lazy_static;
+use poise::{
+    serenity_prelude::{
+        self as serenity, CommandInteraction, Context, CreateCommandOption, ResolvedValue,
+    },
+    SlashArgument,
+};
+use uuid::Uuid;
+
+use super::member::Member;
+use crate::{
+    database::{
+        schema::{team, team_members},
+        PG_POOL,
+    },
+    diesel::{ExpressionMethods, RunQueryDsl},
+    error::Error,
+};
+
+lazy_static! {
+    /// Team names by member, ordered by name. Members are displayed in long
+    /// lists, so the names are kept in memory and reloaded whenever a
+    /// membership changes.
+    static ref MEMBER_TEAMS: RwLock<HashMap<Uuid, Vec<String>>> = RwLock::new(HashMap::new());
+}
+
+/// Project team members can belong to, independent of their role.
+#[derive(Queryable, Identifiable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = team)]
+pub struct Team {
+    id: i32,
+    name: String,
+}
+
+impl Team {
+    pub fn insert(new_name: String) -> Result<Self, Error> {
+        use crate::database::schema::team::dsl::*;
+
+        Ok(diesel::insert_into(team)
+            .values(name.eq(new_name))
+            .get_result(&mut PG_POOL.get()?)?)
+    }
+
+    /// Removes the team. Its members stay, only their membership is removed.
+    pub fn delete(&self) -> Result<usize, Error> {
+        use crate::database::schema::team::dsl::*;
+
+        let deleted = diesel::delete(team.filter(id.eq(self.id))).execute(&mut PG_POOL.get()?)?;
+
+        Self::reload_member_teams()?;
+
+        Ok(deleted)
+    }
+
+    /// Returns all teams, ordered by name.
+    pub fn all() -> Result<Vec<Self>, Error> {
+        use crate::database::schema::team::dsl::*;
+
+        Ok(team.order(name.asc()).load(&mut PG_POOL.get()?)?)
+    }
+
+    /// Finds the team by name, ignoring case.
+    pub fn find_by_name(find_name: &str) -> Result<Self, Error> {
+        Self::all()?
+            .into_iter()
+            .find(|t| t.name.eq_ignore_ascii_case(find_name))
+            .ok_or_else(|| Error::TeamNotFound(find_name.to_string()))
+    }
+
+    /// Loads the names of every member's teams from the database into
+    /// memory, in one query.
+    pub fn reload_member_teams() -> Result<(), Error> {
+        use crate::database::schema::team::dsl::*;
+
+        let mut names: HashMap<Uuid, Vec<String>> = HashMap::new();
+
+        for (member_id, team_name) in team
+            .inner_join(team_members::table)
+            .select((team_members::member_id, name))
+            .order(name.asc())
+            .load::<(Uuid, String)>(&mut PG_POOL.get()?)?
+        {
+            names.entry(member_id).or_default().push(team_name);
+        }
+
+        *MEMBER_TEAMS.write().unwrap() = names;
+
+        Ok(())
+    }
+
+    /// Returns the names of the member's teams from memory, without touching
+    /// the database.
+    pub fn cached_names(find_member_id: Uuid) -> Vec<String> {
+        MEMBER_TEAMS
+            .read()
+            .unwrap()
+            .get(&find_member_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Adds the member to the team. Returns `false` if they already were in
+    /// it.
+    pub fn add_member(&self, new_member_id: Uuid) -> Result<bool, Error> {
+        use crate::database::schema::team_members::dsl::*;
+
+        let inserted = diesel::insert_into(team_members)
+            .values((team_id.eq(self.id), member_id.eq(new_member_id)))
+            .on_conflict_do_nothing()
+            .execute(&mut PG_POOL.get()?)?;
+
+        if inserted > 0 {
+            Self::reload_member_teams()?;
+        }
+
+        Ok(inserted > 0)
+    }
+
+    /// Removes the member from the team. Returns `false` if they were not in
+    /// it.
+    pub fn remove_member(&self, old_member_id: Uuid) -> Result<bool, Error> {
+        use crate::database::schema::team_members::dsl::*;
+
+        let deleted = diesel::delete(
+            team_members
+                .filter(team_id.eq(self.id))
+                .filter(member_id.eq(old_member_id)),
+        )
+        .execute(&mut PG_POOL.get()?)?;
+
+        if deleted > 0 {
+            Self::reload_member_teams()?;
+        }
+
+        Ok(deleted > 0)
+    }
+
+    pub fn members(&self) -> Result<Vec<Member>, Error> {
+        use crate::database::schema::member;
+
+        Ok(team_members::table
+            .filter(team_members::team_id.eq(self.id))
+            .inner_join(member::table)
+            .select(member::all_columns)
+            .order(member::display_name.asc())
+            .load(&mut PG_POOL.get()?)?)
+    }
+
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for Team {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[async_trait::async_trait]
+impl SlashArgument for Team {
+    async fn extract(
+        _ctx: &Context,
+        _interaction: &CommandInteraction,
+        value: &ResolvedValue<'_>,
+    ) -> Result<Self, poise::SlashArgError> {
+        let ResolvedValue::String(name) = value else {
+            return Err(poise::SlashArgError::new_command_structure_mismatch(
+                "Expected string",
+            ));
+        };
+
+        Team::find_by_name(name).map_err(|why| {
+            tracing::error!("Could not find team in database: {}", why);
+
+            poise::SlashArgError::new_command_structure_mismatch("Could not find team in database")
+        })
+    }
+
+    fn create(builder: CreateCommandOption) -> CreateCommandOption {
+        builder.kind(serenity::CommandOptionType::String)
+    }
+}
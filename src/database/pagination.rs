@@ -73,3 +73,38 @@ where
         Ok(())
     }
 }
+
+/// Returns the page of the items, counted from 1, and the number of pages,
+/// like `load_and_count_pages` does for queries, for lists built in memory.
+pub fn paginate_vec<T>(items: Vec<T>, page: i64, per_page: Option<i64>) -> (Vec<T>, i64) {
+    let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE).max(1);
+    let total_pages = (items.len() as f64 / per_page as f64).ceil() as i64;
+
+    let records = items
+        .into_iter()
+        .skip(((page - 1) * per_page).max(0) as usize)
+        .take(per_page as usize)
+        .collect();
+
+    (records, total_pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::paginate_vec;
+
+    #[test]
+    fn items_are_split_into_pages() {
+        let items = (1..=25).collect::<Vec<_>>();
+
+        assert_eq!(
+            paginate_vec(items.clone(), 1, None),
+            ((1..=10).collect(), 3)
+        );
+        assert_eq!(
+            paginate_vec(items.clone(), 3, None),
+            ((21..=25).collect(), 3)
+        );
+        assert_eq!(paginate_vec(items, 2, Some(20)), ((21..=25).collect(), 2));
+    }
+}
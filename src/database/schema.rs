@@ -1,5 +1,37 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    activity_event (id) {
+        id -> Uuid,
+        member_id -> Uuid,
+        kind -> Int4,
+        source_id -> Text,
+        occurred_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    inactivity_escalation (id) {
+        id -> Uuid,
+        member_id -> Uuid,
+        step -> Int4,
+        inactive_since -> Date,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    invite (code) {
+        code -> Text,
+        role -> Int4,
+        created_by -> Text,
+        created_at -> Timestamp,
+        expires_at -> Nullable<Timestamp>,
+        max_uses -> Int4,
+        uses -> Int4,
+    }
+}
+
 diesel::table! {
     meeting (id) {
         id -> Uuid,
@@ -29,6 +61,62 @@ diesel::table! {
         role -> Int4,
         wiki_id -> Nullable<Int8>,
         last_activity -> Nullable<Date>,
+        left_server_at -> Nullable<Timestamp>,
+        custom_fields -> Jsonb,
+        skills -> Array<Text>,
+        mentor_id -> Nullable<Uuid>,
+        role_since -> Timestamp,
+        promotion_notified_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    member_leave (id) {
+        id -> Uuid,
+        member_id -> Uuid,
+        start_date -> Date,
+        end_date -> Date,
+        reason -> Nullable<Text>,
+        return_announced -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    offboarding (id) {
+        id -> Uuid,
+        member_id -> Nullable<Uuid>,
+        reason -> Text,
+        offboarded_at -> Timestamp,
+        previous_role -> Int4,
+        discord_id -> Nullable<Text>,
+        trello_id -> Nullable<Text>,
+        trello_report_card_id -> Nullable<Text>,
+        wiki_id -> Nullable<Int8>,
+        failed_steps -> Array<Text>,
+        reinstated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    onboarding (id) {
+        id -> Uuid,
+        member_id -> Uuid,
+        step -> Int4,
+        dm_channel_id -> Nullable<Text>,
+        dm_message_id -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        reminders_sent -> Int4,
+        reminded_at -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    profile_field (name) {
+        name -> Text,
+        kind -> Int4,
     }
 }
 
@@ -37,9 +125,24 @@ diesel::table! {
         id -> Uuid,
         member_id -> Uuid,
         content -> Text,
-        create_date -> Date,
+        create_date -> Timestamp,
         published -> Bool,
         summary_id -> Nullable<Uuid>,
+        trello_card_id -> Nullable<Text>,
+        trello_comment_id -> Nullable<Text>,
+        imported -> Bool,
+    }
+}
+
+diesel::table! {
+    role (id) {
+        id -> Int4,
+        name -> Text,
+        discord_role_id -> Nullable<Text>,
+        wiki_group_id -> Nullable<Int8>,
+        activity_threshold_days -> Nullable<Int4>,
+        permissions -> Array<Text>,
+        required_activity -> Int4,
     }
 }
 
@@ -49,13 +152,54 @@ diesel::table! {
         note -> Text,
         create_date -> Date,
         messages_id -> Nullable<Array<Text>>,
+        wiki_path -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    team (id) {
+        id -> Int4,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    team_members (team_id, member_id) {
+        team_id -> Int4,
+        member_id -> Uuid,
     }
 }
 
+diesel::joinable!(activity_event -> member (member_id));
+diesel::joinable!(inactivity_escalation -> member (member_id));
+diesel::joinable!(invite -> role (role));
 diesel::joinable!(meeting -> summary (summary_id));
 diesel::joinable!(meeting_members -> meeting (meeting_id));
 diesel::joinable!(meeting_members -> member (member_id));
+diesel::joinable!(member -> role (role));
+diesel::joinable!(member_leave -> member (member_id));
+diesel::joinable!(offboarding -> role (previous_role));
+diesel::joinable!(offboarding -> member (member_id));
+diesel::joinable!(onboarding -> member (member_id));
 diesel::joinable!(report -> member (member_id));
 diesel::joinable!(report -> summary (summary_id));
+diesel::joinable!(team_members -> member (member_id));
+diesel::joinable!(team_members -> team (team_id));
 
-diesel::allow_tables_to_appear_in_same_query!(meeting, meeting_members, member, report, summary,);
+diesel::allow_tables_to_appear_in_same_query!(
+    activity_event,
+    inactivity_escalation,
+    invite,
+    meeting,
+    meeting_members,
+    member,
+    member_leave,
+    offboarding,
+    onboarding,
+    profile_field,
+    report,
+    role,
+    summary,
+    team,
+    team_members,
+);
@@ -1,18 +1,33 @@
 use std::fmt::Write;
 
-use poise::ChoiceParameter;
-
 use crate::{
-    database::models::member::{Activity, Member, MemberRole},
+    activity,
+    database::{
+        models::{
+            activity_event::ActivityEvent,
+            inactivity_escalation::InactivityEscalation,
+            leave::Leave,
+            member::{Activity, Member},
+            role::Role,
+        },
+        pagination::paginate_vec,
+    },
     discord::Context,
     error::Error,
+    i18n::{self, t},
 };
 
+/// Number of weeks checked when only the minimum number of active weeks is given.
+const DEFAULT_WEEKS: u32 = 4;
+
+/// Minimum number of active weeks when only the number of weeks is given.
+const DEFAULT_MIN_WEEKS: u32 = 3;
+
 #[poise::command(slash_command, rename = "refresh")]
 pub(crate) async fn refresh(ctx: Context<'_>) -> Result<(), Error> {
     Member::refresh_all_activities()?;
 
-    crate::discord::respond(ctx, "Refreshed".to_owned()).await
+    crate::discord::respond(ctx, t!(i18n::user_locale(ctx), "activity.refreshed")).await
 }
 
 #[poise::command(slash_command, rename = "list")]
@@ -21,35 +36,212 @@ pub(crate) async fn list(
     #[description = "Page to list"] page: Option<i64>,
     #[description = "Page size"] page_size: Option<i64>,
     #[description = "Member's activity"] activity: Option<Activity>,
+    #[description = "Active in at least this many of the last weeks, 3 by default"]
+    #[min = 1]
+    #[max = 52]
+    min_weeks: Option<u32>,
+    #[description = "Number of last weeks to check, 4 by default"]
+    #[min = 1]
+    #[max = 52]
+    weeks: Option<u32>,
 ) -> Result<(), Error> {
     let page = page.unwrap_or(1);
 
     let activity = activity.unwrap_or(Activity::Inactive);
 
-    let (members, total_pages) = Member::list(
-        page,
-        page_size,
-        None,
-        Some(MemberRole::ExMember),
-        Some(activity),
-        None,
+    let locale = i18n::user_locale(ctx);
+    let mut output = String::new();
+
+    if min_weeks.is_none() && weeks.is_none() {
+        let (members, total_pages) = Member::list(
+            page,
+            page_size,
+            None,
+            Some(Role::EX_MEMBER),
+            Some(activity),
+            None,
+            None,
+            None,
+        )?;
+
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "activity.list_header",
+                activity = activity.localized(locale)
+            )
+        )?;
+
+        let leaves = Leave::periods_by_member()?;
+
+        for member in members {
+            writeln!(&mut output, "{}", member.display_activity(locale, &leaves))?;
+        }
+        write!(
+            &mut output,
+            "{}",
+            t!(locale, "common.page", page = page, total = total_pages)
+        )?;
+
+        return crate::discord::respond(ctx, output).await;
+    }
+
+    let weeks = weeks.unwrap_or(DEFAULT_WEEKS);
+    let min_weeks = min_weeks.unwrap_or(DEFAULT_MIN_WEEKS).min(weeks);
+
+    let members = activity::weekly_activity(weeks)?
+        .into_iter()
+        .map(|(member, scores)| (member, activity::active_weeks(&scores)))
+        .filter(|(_, active)| (*active >= min_weeks as usize) == (activity == Activity::Active))
+        .collect::<Vec<_>>();
+
+    let (members, total_pages) = paginate_vec(members, page, page_size);
+    let leaves = Leave::periods_by_member()?;
+
+    writeln!(
+        &mut output,
+        "{}",
+        t!(
+            locale,
+            "activity.list_header_weeks",
+            activity = activity.localized(locale),
+            min_weeks = min_weeks,
+            weeks = weeks
+        )
     )?;
 
+    for (member, active) in members {
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "activity.active_weeks",
+                member = member.display_activity(locale, &leaves),
+                active = active,
+                weeks = weeks
+            )
+        )?;
+    }
+    write!(
+        &mut output,
+        "{}",
+        t!(locale, "common.page", page = page, total = total_pages)
+    )?;
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Shows a member's activity per week and their activity history
+#[poise::command(slash_command, rename = "timeline")]
+pub(crate) async fn timeline(
+    ctx: Context<'_>,
+    #[description = "Member of the organization"] member: Member,
+    #[description = "Number of last weeks to count, 4 by default"]
+    #[min = 1]
+    #[max = 52]
+    weeks: Option<u32>,
+    #[description = "Page to list"] page: Option<i64>,
+    #[description = "Page size"] page_size: Option<i64>,
+) -> Result<(), Error> {
+    let page = page.unwrap_or(1);
+    let weeks = weeks.unwrap_or(DEFAULT_WEEKS);
+
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
+    let scores = activity::member_weekly_activity(&member, weeks)?;
+
+    writeln!(
+        &mut output,
+        "{}",
+        t!(locale, "activity.timeline_header", member = member.name())
+    )?;
     writeln!(
         &mut output,
-        "## List of {} members",
-        activity
-            .localized_name(ctx.locale().unwrap())
-            .unwrap_or(&activity.to_string())
-            .to_lowercase()
+        "{}",
+        t!(
+            locale,
+            "activity.weekly",
+            weeks = weeks,
+            scores = scores
+                .iter()
+                .map(|score| activity::display_score(*score))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    )?;
+
+    let (events, total_pages) = ActivityEvent::timeline(member.id(), page, page_size)?;
+
+    if events.is_empty() {
+        writeln!(&mut output, "{}", t!(locale, "activity.no_events"))?;
+    }
+
+    for event in events {
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "activity.event",
+                date = event.occurred_at().format("%Y-%m-%d %H:%M"),
+                kind = event.kind().localized(locale)
+            )
+        )?;
+    }
+    write!(
+        &mut output,
+        "{}",
+        t!(locale, "common.page", page = page, total = total_pages)
     )?;
 
-    for member in members {
-        writeln!(&mut output, "{}", member.display_activity())?;
+    crate::discord::respond(ctx, output).await
+}
+
+/// Lists the steps taken because of members' inactivity, newest first
+#[poise::command(slash_command, rename = "escalations")]
+pub(crate) async fn escalations(
+    ctx: Context<'_>,
+    #[description = "Member of the organization"] member: Option<Member>,
+    #[description = "Page to list"] page: Option<i64>,
+    #[description = "Page size"] page_size: Option<i64>,
+) -> Result<(), Error> {
+    let page = page.unwrap_or(1);
+
+    let locale = i18n::user_locale(ctx);
+    let mut output = String::new();
+
+    let (escalations, total_pages) =
+        InactivityEscalation::list(page, page_size, member.as_ref().map(Member::id))?;
+
+    if escalations.is_empty() {
+        writeln!(&mut output, "{}", t!(locale, "inactivity.none"))?;
     }
-    write!(&mut output, "Page: {page}/{total_pages}")?;
+
+    for escalation in escalations {
+        let member = Member::find_by_id(escalation.member_id())?;
+
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "inactivity.entry",
+                date = escalation.created_at().format("%Y-%m-%d %H:%M"),
+                member = member.name(),
+                step = escalation.step().localized(locale),
+                since = escalation.inactive_since()
+            )
+        )?;
+    }
+    write!(
+        &mut output,
+        "{}",
+        t!(locale, "common.page", page = page, total = total_pages)
+    )?;
 
     crate::discord::respond(ctx, output).await
 }
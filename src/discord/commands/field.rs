@@ -0,0 +1,213 @@
+use std::fmt::Write;
+
+use poise::ChoiceParameter;
+use tracing::info;
+
+use super::Context;
+use crate::{
+    database::models::{
+        member::Member,
+        profile_field::{FieldKind, ProfileField},
+    },
+    error::Error,
+    i18n::{self, t},
+};
+
+/// Suggests the names of the profile fields matching what was typed so far.
+async fn autocomplete_field(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let partial = partial.to_lowercase();
+
+    ProfileField::all()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|field| field.name().to_string())
+        .filter(|name| name.to_lowercase().contains(&partial))
+        .collect()
+}
+
+/// Lists the profile fields, or a member's values of them
+#[poise::command(slash_command, rename = "list")]
+pub(crate) async fn list_fields(
+    ctx: Context<'_>,
+    #[description = "Member whose values to list"] member: Option<Member>,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let mut output = String::new();
+
+    match member {
+        Some(member) => {
+            let fields = member.custom_fields(locale);
+
+            if fields.is_empty() {
+                return crate::discord::respond(
+                    ctx,
+                    t!(locale, "field.member_none", member = member.name()),
+                )
+                .await;
+            }
+
+            for (name, value) in fields {
+                writeln!(
+                    &mut output,
+                    "{}",
+                    t!(locale, "field.value", field = name, value = value)
+                )?;
+            }
+        }
+        None => {
+            let fields = ProfileField::all()?;
+
+            if fields.is_empty() {
+                return crate::discord::respond(ctx, t!(locale, "field.none")).await;
+            }
+
+            for field in fields {
+                writeln!(
+                    &mut output,
+                    "{}",
+                    t!(
+                        locale,
+                        "field.entry",
+                        field = field.name(),
+                        kind = field.kind().name()
+                    )
+                )?;
+            }
+        }
+    }
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Adds a profile field members can have a value of
+#[poise::command(
+    slash_command,
+    rename = "add",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn add_field(
+    ctx: Context<'_>,
+    #[description = "Field name"] name: String,
+    #[description = "Type of the values"] kind: FieldKind,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    if ProfileField::find(&name).is_ok() {
+        return crate::discord::respond(ctx, t!(locale, "field.already_exists", field = name))
+            .await;
+    }
+
+    let field = ProfileField::new(name.trim().to_string(), kind).insert()?;
+
+    info!("Profile field added by {}: {:?}", ctx.author().name, field);
+
+    crate::discord::respond(
+        ctx,
+        t!(
+            locale,
+            "field.added",
+            field = field.name(),
+            kind = field.kind().name()
+        ),
+    )
+    .await
+}
+
+/// Removes a profile field and every member's value of it
+#[poise::command(
+    slash_command,
+    rename = "remove",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn remove_field(
+    ctx: Context<'_>,
+    #[description = "Field"]
+    #[autocomplete = "autocomplete_field"]
+    field: String,
+) -> Result<(), Error> {
+    let field = ProfileField::find(&field)?;
+
+    field.delete()?;
+
+    info!(
+        "Profile field removed by {}: {:?}",
+        ctx.author().name,
+        field
+    );
+
+    crate::discord::respond(
+        ctx,
+        t!(
+            i18n::user_locale(ctx),
+            "field.removed",
+            field = field.name()
+        ),
+    )
+    .await
+}
+
+/// Sets a member's value of a profile field, or clears it without a value
+#[poise::command(
+    slash_command,
+    rename = "set",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn set_field(
+    ctx: Context<'_>,
+    #[description = "Member of the organization"] mut member: Member,
+    #[description = "Field"]
+    #[autocomplete = "autocomplete_field"]
+    field: String,
+    #[description = "Value, e.g. 42, yes or 2026-10-18 depending on the field"] value: Option<
+        String,
+    >,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let field = ProfileField::find(&field)?;
+
+    let value = match value {
+        Some(value) => match field.kind().parse(&value) {
+            Ok(value) => Some(value),
+            Err(why) => {
+                return crate::discord::respond(
+                    ctx,
+                    t!(locale, "field.invalid_value", error = why),
+                )
+                .await;
+            }
+        },
+        None => None,
+    };
+
+    let cleared = value.is_none();
+    member.set_custom_field(&field, value);
+    let member = member.update()?;
+
+    info!(
+        "Profile field {} of {} set by {}",
+        field.name(),
+        member.name(),
+        ctx.author().name
+    );
+
+    let output = if cleared {
+        t!(
+            locale,
+            "field.cleared",
+            field = field.name(),
+            member = member.name()
+        )
+    } else {
+        t!(
+            locale,
+            "field.set",
+            field = field.name(),
+            member = member.name()
+        )
+    };
+
+    crate::discord::respond(ctx, output).await
+}
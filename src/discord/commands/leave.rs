@@ -0,0 +1,199 @@
+use std::fmt::Write;
+
+use tracing::info;
+
+use super::Context;
+use crate::{
+    database::models::{leave::Leave, member::Member},
+    discord::permissions,
+    error::Error,
+    i18n::{self, t},
+    leave::{self, parse_date},
+    SETTINGS,
+};
+
+/// Returns the member whose leaves to manage: the given member, which only
+/// admins may choose for others, or the command's author, replying if they
+/// are not a member.
+async fn leave_member(ctx: Context<'_>, member: Option<Member>) -> Result<Option<Member>, Error> {
+    let author = Member::find_by_discord_id(ctx.author().id.to_string()).ok();
+
+    match member {
+        Some(member) => {
+            let is_author = author.is_some_and(|author| author.id() == member.id());
+
+            if !is_author && !permissions::is_admin(ctx).await {
+                return Err(Error::NotLeaveOwner);
+            }
+
+            Ok(Some(member))
+        }
+        None => {
+            if author.is_none() {
+                crate::discord::respond(ctx, t!(i18n::user_locale(ctx), "me.not_member")).await?;
+            }
+
+            Ok(author)
+        }
+    }
+}
+
+/// Adds a leave, during which your inactivity is not counted
+#[poise::command(slash_command, rename = "add")]
+pub(crate) async fn add_leave(
+    ctx: Context<'_>,
+    #[description = "First day of the leave, e.g. 2026-10-18"] start: String,
+    #[description = "Last day of the leave, e.g. 2026-10-31"] end: String,
+    #[description = "Reason, e.g. exams"] reason: Option<String>,
+    #[description = "Member on leave, only admins can choose others"] member: Option<Member>,
+) -> Result<(), Error> {
+    let Some(member) = leave_member(ctx, member).await? else {
+        return Ok(());
+    };
+
+    let locale = i18n::user_locale(ctx);
+    let start = parse_date(&start)?;
+    let end = parse_date(&end)?;
+
+    if end < start {
+        return Err(Error::InvalidLeavePeriod);
+    }
+
+    if Leave::find(member.id(), start)?.is_some() {
+        return crate::discord::respond(
+            ctx,
+            t!(
+                locale,
+                "leave.already_exists",
+                member = member.name(),
+                start = start
+            ),
+        )
+        .await;
+    }
+
+    let leaves = Leave::periods(member.id())?;
+
+    if let Some((start, end)) = leave::overlapping(start, end, &leaves) {
+        return Err(Error::LeaveOverlaps { start, end });
+    }
+
+    // admins can backdate leaves and add long ones, members only plan theirs
+    if !permissions::is_admin(ctx).await {
+        if start < chrono::Local::now().date_naive() {
+            return Err(Error::LeaveInPast);
+        }
+
+        let max_days = SETTINGS.leave.max_days;
+
+        if max_days > 0 && leave::consecutive_days(start, end, &leaves) > max_days {
+            return Err(Error::LeaveTooLong(max_days));
+        }
+    }
+
+    let leave = Leave::insert(
+        member.id(),
+        start,
+        end,
+        reason.map(|reason| reason.trim().to_string()),
+    )?;
+
+    info!("Leave added by {}: {:?}", ctx.author().name, leave);
+
+    crate::discord::respond(
+        ctx,
+        t!(
+            locale,
+            "leave.added",
+            member = member.name(),
+            start = start,
+            end = end
+        ),
+    )
+    .await
+}
+
+/// Lists the leaves, latest first
+#[poise::command(slash_command, rename = "list")]
+pub(crate) async fn list_leaves(
+    ctx: Context<'_>,
+    #[description = "Member whose leaves to list"] member: Option<Member>,
+    #[description = "Page to list"] page: Option<i64>,
+    #[description = "Page size"] page_size: Option<i64>,
+) -> Result<(), Error> {
+    let page = page.unwrap_or(1);
+
+    let locale = i18n::user_locale(ctx);
+    let mut output = String::new();
+
+    let (leaves, total_pages) = Leave::list(page, page_size, member.as_ref().map(Member::id))?;
+
+    if leaves.is_empty() {
+        writeln!(&mut output, "{}", t!(locale, "leave.none"))?;
+    }
+
+    for leave in leaves {
+        let member = Member::find_by_id(leave.member_id())?;
+
+        write!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "leave.entry",
+                member = member.name(),
+                start = leave.start_date(),
+                end = leave.end_date(),
+                reason = leave.reason().unwrap_or("-"),
+                date = leave.created_at().format("%Y-%m-%d")
+            )
+        )?;
+
+        if leave.return_announced() {
+            write!(&mut output, " {}", t!(locale, "leave.welcomed_back"))?;
+        }
+
+        writeln!(&mut output)?;
+    }
+    write!(
+        &mut output,
+        "{}",
+        t!(locale, "common.page", page = page, total = total_pages)
+    )?;
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Removes a leave
+#[poise::command(slash_command, rename = "remove")]
+pub(crate) async fn remove_leave(
+    ctx: Context<'_>,
+    #[description = "First day of the leave, e.g. 2026-10-18"] start: String,
+    #[description = "Member on leave, only admins can choose others"] member: Option<Member>,
+) -> Result<(), Error> {
+    let Some(member) = leave_member(ctx, member).await? else {
+        return Ok(());
+    };
+
+    let start = parse_date(&start)?;
+
+    let leave = Leave::find(member.id(), start)?.ok_or_else(|| Error::LeaveNotFound {
+        member: member.name(),
+        start,
+    })?;
+
+    leave.delete()?;
+
+    info!("Leave removed by {}: {:?}", ctx.author().name, leave);
+
+    crate::discord::respond(
+        ctx,
+        t!(
+            i18n::user_locale(ctx),
+            "leave.removed",
+            member = member.name(),
+            start = start
+        ),
+    )
+    .await
+}
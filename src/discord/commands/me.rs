@@ -0,0 +1,257 @@
+use std::{collections::HashMap, fmt::Write, time::Duration};
+
+use chrono::NaiveDate;
+use poise::serenity_prelude::{ComponentInteractionCollector, CreateActionRow, CreateButton};
+use tracing::info;
+use uuid::Uuid;
+
+use super::Context;
+use crate::{
+    database::models::{
+        leave::Leave,
+        meeting::Meeting,
+        member::Member,
+        onboarding::{Onboarding, OnboardingStep},
+        report::Report,
+    },
+    error::Error,
+    i18n::{self, t},
+    integrations::INTEGRATIONS,
+    onboarding::{self, WikiEmailModal},
+};
+
+/// Returns the member of the command's author, or replies that they are not
+/// a member.
+async fn author_member(ctx: Context<'_>) -> Result<Option<Member>, Error> {
+    match Member::find_by_discord_id(ctx.author().id.to_string()) {
+        Ok(member) => Ok(Some(member)),
+        Err(_) => {
+            crate::discord::respond(ctx, t!(i18n::user_locale(ctx), "me.not_member")).await?;
+
+            Ok(None)
+        }
+    }
+}
+
+/// Returns the member's leaves, keyed by member like
+/// `Leave::periods_by_member`.
+fn member_leaves(member: &Member) -> Result<HashMap<Uuid, Vec<(NaiveDate, NaiveDate)>>, Error> {
+    Ok(HashMap::from([(member.id(), Leave::periods(member.id())?)]))
+}
+
+/// Shows your member profile
+#[poise::command(slash_command, rename = "profile")]
+pub(crate) async fn profile(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(member) = author_member(ctx).await? else {
+        return Ok(());
+    };
+
+    let locale = i18n::user_locale(ctx);
+    let none = t!(locale, "role.none");
+
+    let mut output = t!(
+        locale,
+        "me.profile",
+        name = member.name(),
+        role = member.role()?,
+        discord = format!("<@{}>", ctx.author().id),
+        wiki = member
+            .wiki_id()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| none.clone()),
+        trello = member.trello_id().unwrap_or(&none),
+        activity = member.display_activity(locale, &member_leaves(&member)?)
+    );
+
+    if let Some(onboarding) = Onboarding::find_by_member_id(member.id())? {
+        if onboarding.step() != OnboardingStep::Done {
+            output.push('\n');
+            output.push_str(&t!(
+                locale,
+                "me.profile_onboarding",
+                step = onboarding.step().localized(locale)
+            ));
+        }
+    }
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Shows when you were last active
+#[poise::command(slash_command, rename = "activity")]
+pub(crate) async fn activity(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(member) = author_member(ctx).await? else {
+        return Ok(());
+    };
+
+    let activity = member.display_activity(i18n::user_locale(ctx), &member_leaves(&member)?);
+
+    crate::discord::respond(ctx, activity).await
+}
+
+/// Lists your reports
+#[poise::command(slash_command, rename = "reports")]
+pub(crate) async fn reports(
+    ctx: Context<'_>,
+    #[description = "Page number"] page: Option<i64>,
+    #[description = "Page size"] page_size: Option<i64>,
+) -> Result<(), Error> {
+    let Some(member) = author_member(ctx).await? else {
+        return Ok(());
+    };
+
+    let page = page.unwrap_or(1);
+    let (reports, total_pages) = Report::list(page, page_size, Some(member.id()), None, None)?;
+
+    let mut output = String::new();
+
+    for report in reports {
+        writeln!(&mut output, "{}\n", report)?;
+    }
+
+    write!(
+        &mut output,
+        "{}",
+        t!(
+            i18n::user_locale(ctx),
+            "common.page",
+            page = page,
+            total = total_pages
+        )
+    )?;
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Lists the meetings you attended
+#[poise::command(slash_command, rename = "meetings")]
+pub(crate) async fn meetings(
+    ctx: Context<'_>,
+    #[description = "Page number"] page: Option<i64>,
+    #[description = "Page size"] page_size: Option<i64>,
+) -> Result<(), Error> {
+    let Some(member) = author_member(ctx).await? else {
+        return Ok(());
+    };
+
+    let page = page.unwrap_or(1);
+    let (meetings, total_pages) = Meeting::list_attended(member.id(), page, page_size)?;
+
+    let mut output = String::new();
+
+    for meeting in meetings {
+        writeln!(&mut output, "{}\n", meeting)?;
+    }
+
+    write!(
+        &mut output,
+        "{}",
+        t!(
+            i18n::user_locale(ctx),
+            "common.page",
+            page = page,
+            total = total_pages
+        )
+    )?;
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Changes your display name
+#[poise::command(slash_command, rename = "rename")]
+pub(crate) async fn rename(
+    ctx: Context<'_>,
+    #[description = "New display name"]
+    #[min_length = 1]
+    #[max_length = 64]
+    name: String,
+) -> Result<(), Error> {
+    let Some(mut member) = author_member(ctx).await? else {
+        return Ok(());
+    };
+
+    let locale = i18n::user_locale(ctx);
+    let old = member.clone();
+
+    member.set_name(name.trim().to_string())?;
+
+    info!("Member renamed themselves: {:?}", member);
+
+    let errors = INTEGRATIONS
+        .profile_updated(ctx.http(), &old, &member)
+        .await;
+
+    let mut output = String::new();
+
+    for line in errors.localized(locale) {
+        writeln!(&mut output, "{line}")?;
+    }
+
+    output.push_str(&t!(locale, "me.renamed", name = member.name()));
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Creates your wiki account with your Discord email
+#[poise::command(slash_command, rename = "wiki")]
+pub(crate) async fn wiki(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(mut member) = author_member(ctx).await? else {
+        return Ok(());
+    };
+
+    let locale = i18n::user_locale(ctx);
+
+    // commands are deferred, so the form can only be opened from a button
+    ctx.send(
+        poise::CreateReply::default()
+            .content(t!(locale, "me.wiki_prompt"))
+            .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                "me_wiki_button",
+            )
+            .label(t!(locale, "onboarding.email_button"))])]),
+    )
+    .await?;
+
+    let interaction = ComponentInteractionCollector::new(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .filter(|interaction| interaction.data.custom_id == "me_wiki_button")
+        .timeout(Duration::from_secs(600))
+        .await;
+
+    let Some(interaction) = interaction else {
+        return crate::discord::respond(ctx, t!(locale, "me.wiki_timed_out")).await;
+    };
+
+    let modal_data = poise::execute_modal_on_component_interaction::<WikiEmailModal>(
+        &ctx,
+        interaction,
+        None,
+        Some(Duration::from_secs(600)),
+    )
+    .await?;
+
+    let Some(modal_data) = modal_data else {
+        return crate::discord::respond(ctx, t!(locale, "me.wiki_timed_out")).await;
+    };
+
+    let mut errors =
+        onboarding::create_wiki_account(ctx.http(), &mut member, modal_data.wiki_email).await?;
+
+    // a member who has not answered the welcome DM yet is onboarded further
+    errors.extend(onboarding::resume_for_member(ctx.http(), &member).await?);
+
+    let mut output = String::new();
+
+    for line in errors.localized(locale) {
+        writeln!(&mut output, "{line}")?;
+    }
+
+    output.push_str(&t!(
+        locale,
+        "me.wiki_linked",
+        url = crate::SETTINGS.wiki.url
+    ));
+
+    crate::discord::respond(ctx, output).await
+}
@@ -5,14 +5,12 @@ use tracing::info;
 
 use crate::{
     database::models::{
-        meeting::Meeting,
-        member::{Member, MemberRole},
-        summary::Summary,
+        leave::Leave, meeting::Meeting, member::Member, role::Role, summary::Summary,
     },
     discord::Context,
     error::Error,
+    i18n::{self, t},
     meeting::MeetingStatus,
-    SETTINGS,
 };
 
 /// Ends the meeting. Returns the meeting summary which contains members'
@@ -30,6 +28,7 @@ pub(crate) async fn end_meeting(
         return Err(Error::NoMeetingOngoing);
     }
 
+    let locale = i18n::user_locale(ctx);
     let mut summary_result;
 
     {
@@ -46,31 +45,33 @@ pub(crate) async fn end_meeting(
 
     let previous_meeting = Meeting::get_previous_meeting()?;
 
-    info!(
-        "Active after: {:?}",
-        previous_meeting.start_date() - chrono::Duration::days(SETTINGS.activity_threshold_days)
-    );
+    // members are inactive after their role's threshold, so this lists the
+    // ones who became inactive since the previous meeting
+    let inactive_after = previous_meeting.start_date().date();
+
+    info!("Inactive after: {:?}", inactive_after);
 
     let (members, total_pages) = Member::list(
         page,
         None,
         None,
-        Some(MemberRole::ExMember),
+        Some(Role::EX_MEMBER),
         Some(crate::database::models::member::Activity::Inactive),
-        Some(
-            (previous_meeting.start_date()
-                - chrono::Duration::days(SETTINGS.activity_threshold_days))
-            .into(),
-        ),
+        Some(inactive_after),
+        None,
+        None,
     )?;
 
     if !members.is_empty() {
-        summary_result.push_str("\nInactive members from this week:");
+        summary_result.push('\n');
+        summary_result.push_str(&t!(locale, "meeting.inactive_members"));
     }
 
+    let leaves = Leave::periods_by_member()?;
+
     for member in members {
         summary_result.push('\n');
-        summary_result.push_str(&member.display_activity());
+        summary_result.push_str(&member.display_activity(locale, &leaves));
     }
 
     page += 1;
@@ -80,14 +81,16 @@ pub(crate) async fn end_meeting(
             page,
             None,
             None,
-            Some(MemberRole::ExMember),
+            Some(Role::EX_MEMBER),
             Some(crate::database::models::member::Activity::Inactive),
-            Some((chrono::Local::now().naive_local() - chrono::Duration::weeks(1)).into()),
+            Some(inactive_after),
+            None,
+            None,
         )?;
 
         for member in members {
             summary_result.push('\n');
-            summary_result.push_str(&member.display_activity());
+            summary_result.push_str(&member.display_activity(locale, &leaves));
         }
 
         page += 1;
@@ -101,6 +104,7 @@ pub(crate) async fn end_meeting(
 /// Return the current or future meeting's status.
 #[poise::command(slash_command, rename = "status")]
 pub(crate) async fn status_meeting(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
     {
@@ -108,32 +112,38 @@ pub(crate) async fn status_meeting(ctx: Context<'_>) -> Result<(), Error> {
         let meeting_status = rw_lock.read().await;
 
         if meeting_status.is_meeting_ongoing() {
-            output.push_str("Meeting is ongoing. ");
-            output.push_str(&meeting_status.meeting_id().simple().to_string());
+            output.push_str(&t!(
+                locale,
+                "meeting.ongoing",
+                id = meeting_status.meeting_id().simple()
+            ));
         } else {
-            output.push_str("Planned meeting on ");
-            output.push_str(
-                &meeting_status
+            output.push_str(&t!(
+                locale,
+                "meeting.planned",
+                date = meeting_status
                     .schedule()?
                     .upcoming(chrono::Local)
                     .next()
-                    .unwrap()
-                    .to_string(),
-            );
-            output.push_str(" with id ");
-            output.push_str(&meeting_status.meeting_id().simple().to_string());
+                    .unwrap(),
+                id = meeting_status.meeting_id().simple()
+            ));
         }
 
-        output.push_str("\nMembers:");
+        output.push('\n');
+        output.push_str(&t!(locale, "meeting.members"));
         for member in meeting_status.members() {
             output.push_str(" <@");
             output.push_str(&member.discord_id()?);
             output.push('>');
         }
 
-        output.push_str("\nMonitoring channel: <#");
-        output.push_str(meeting_status.channel());
-        output.push('>');
+        output.push('\n');
+        output.push_str(&t!(
+            locale,
+            "meeting.channel",
+            channel = meeting_status.channel()
+        ));
     }
 
     info!("Generated meeting status: \n{}", output);
@@ -152,6 +162,7 @@ pub(crate) async fn plan_meeting(
     #[channel_types("Voice")]
     channel: Option<poise::serenity_prelude::GuildChannel>,
 ) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
     let meeting_status = ctx.data().meeting_status.clone();
@@ -166,11 +177,12 @@ pub(crate) async fn plan_meeting(
 
         let next = schedule.upcoming(chrono::Local).next().unwrap();
 
-        output.push_str("New schedule set to ");
-        output.push_str(&schedule.to_string());
-        output.push_str(" (next meeting on ");
-        output.push_str(&next.to_string());
-        output.push(')');
+        output.push_str(&t!(
+            locale,
+            "meeting.schedule_changed",
+            schedule = schedule,
+            next = next
+        ));
     }
 
     if let Some(channel) = channel {
@@ -180,9 +192,8 @@ pub(crate) async fn plan_meeting(
 
         meeting_status.change_channel(channel_id.to_string())?;
 
-        output.push_str("\nMeeting channel changed to <#");
-        output.push_str(&channel_id.to_string());
-        output.push('>');
+        output.push('\n');
+        output.push_str(&t!(locale, "meeting.channel_changed", channel = channel_id));
     }
 
     crate::discord::respond(ctx, output).await
@@ -209,10 +220,7 @@ pub(crate) async fn set_note(
     #[rest]
     note: String,
 ) -> Result<(), Error> {
-    let mut output = String::new();
-
-    output.push_str("Meeting summary changed to ");
-    output.push_str(&note);
+    let output = t!(i18n::user_locale(ctx), "meeting.note_changed", note = note);
 
     let meeting = resolve_meeting(ctx, meeting).await?;
 
@@ -242,8 +250,8 @@ struct NoteModal {
     note: String,
 }
 
-fn compose_note_button() -> CreateButton {
-    CreateButton::new("compose_note_button").label("Compose note")
+fn compose_note_button(locale: &str) -> CreateButton {
+    CreateButton::new("compose_note_button").label(t!(locale, "meeting.compose_note_button"))
 }
 
 /// Formats the modal's title/note fields into the text stored on the
@@ -281,11 +289,14 @@ pub(crate) async fn compose_note(
     meeting: Option<Meeting>,
 ) -> Result<(), Error> {
     let meeting = resolve_meeting(ctx, meeting).await?;
+    let locale = i18n::user_locale(ctx);
 
     ctx.send(
         poise::CreateReply::default()
-            .content("Click the button below to open the note form.")
-            .components(vec![CreateActionRow::Buttons(vec![compose_note_button()])]),
+            .content(t!(locale, "meeting.compose_note_prompt"))
+            .components(vec![CreateActionRow::Buttons(vec![compose_note_button(
+                locale,
+            )])]),
     )
     .await?;
 
@@ -297,11 +308,7 @@ pub(crate) async fn compose_note(
         .await;
 
     let Some(interaction) = interaction else {
-        return crate::discord::respond(
-            ctx,
-            "Timed out waiting for the note form to be opened.".to_string(),
-        )
-        .await;
+        return crate::discord::respond(ctx, t!(locale, "meeting.compose_note_open_timeout")).await;
     };
 
     let modal_data = poise::execute_modal_on_component_interaction::<NoteModal>(
@@ -313,11 +320,8 @@ pub(crate) async fn compose_note(
     .await?;
 
     let Some(modal_data) = modal_data else {
-        return crate::discord::respond(
-            ctx,
-            "Timed out waiting for the note to be submitted.".to_string(),
-        )
-        .await;
+        return crate::discord::respond(ctx, t!(locale, "meeting.compose_note_submit_timeout"))
+            .await;
     };
 
     let note = format_note(modal_data.title, modal_data.note)?;
@@ -328,7 +332,14 @@ pub(crate) async fn compose_note(
 
     let result = summary.send_summary(ctx, true).await?;
 
-    crate::discord::respond(ctx, format!("Meeting summary changed to {note}\n{result}")).await
+    crate::discord::respond(
+        ctx,
+        format!(
+            "{}\n{result}",
+            t!(locale, "meeting.note_changed", note = note)
+        ),
+    )
+    .await
 }
 
 #[poise::command(slash_command, rename = "add-member")]
@@ -337,9 +348,7 @@ pub async fn add_member(
     #[description = "Member to add"] mut member: Member,
     #[description = "Meeting ID to add the member to"] meeting: Option<Meeting>,
 ) -> Result<(), Error> {
-    let mut output = String::new();
-
-    let result = match meeting {
+    match meeting {
         Some(meeting) => meeting.add_member(&mut member)?,
         None => {
             let mut meeting_status = ctx.data().meeting_status.write().await;
@@ -347,7 +356,11 @@ pub async fn add_member(
         }
     };
 
-    output.push_str(&result);
+    let output = t!(
+        i18n::user_locale(ctx),
+        "meeting.member_added",
+        member = member.discord_id().cloned().unwrap_or_default()
+    );
 
     crate::discord::respond(ctx, output).await
 }
@@ -358,9 +371,7 @@ pub async fn remove_member(
     #[description = "Member of the organization"] mut member: Member,
     #[description = "Meeting ID to add the member to"] meeting: Option<Meeting>,
 ) -> Result<(), Error> {
-    let mut output = String::new();
-
-    let result = match meeting {
+    let removed = match meeting {
         Some(meeting) => meeting.remove_member(&mut member)?,
         None => {
             let mut meeting_status = ctx.data().meeting_status.write().await;
@@ -368,7 +379,17 @@ pub async fn remove_member(
         }
     };
 
-    output.push_str(&result);
+    let key = if removed {
+        "meeting.member_removed"
+    } else {
+        "meeting.member_not_in_meeting"
+    };
+
+    let output = t!(
+        i18n::user_locale(ctx),
+        key,
+        member = member.discord_id().cloned().unwrap_or_default()
+    );
 
     crate::discord::respond(ctx, output).await
 }
@@ -388,7 +409,16 @@ pub(crate) async fn list_meetings(
     for meeting in meetings {
         writeln!(&mut output, "{}\n", meeting)?;
     }
-    write!(output, "Page {}/{}", page, total_pages)?;
+    write!(
+        output,
+        "{}",
+        t!(
+            i18n::user_locale(ctx),
+            "common.page",
+            page = page,
+            total = total_pages
+        )
+    )?;
 
     crate::discord::respond(ctx, output).await
 }
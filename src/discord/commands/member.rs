@@ -1,40 +1,27 @@
-use std::{fmt::Write, future::IntoFuture};
+use std::{fmt::Write, time::Duration};
 
-use poise::serenity_prelude::{
-    self as serenity, CreateActionRow, CreateButton, CreateMessage, EditMessage,
-};
-use tracing::{error, info, warn};
+use poise::serenity_prelude as serenity;
+use tracing::{error, info};
 
 use super::Context;
 use crate::{
-    database::models::member::{Activity, Member, MemberRole},
+    database::models::{
+        invite::Invite,
+        member::{self, Activity, Member},
+        offboarding::Offboarding,
+        onboarding::Onboarding,
+        role::Role,
+        team::Team,
+    },
+    discord::{permissions, roles},
     error::Error,
+    i18n::{self, t},
+    integrations::INTEGRATIONS,
+    offboarding, onboarding, promotion,
+    reconcile::FixStatus,
     SETTINGS,
 };
 
-/// Modal to get the Discord email of a user to connect or create a new wiki
-/// account
-#[derive(Debug, poise::Modal)]
-#[name = "Discord email for Wiki account"]
-struct WikiEmailModal {
-    #[name = "Discord email"]
-    #[placeholder = "example@domain.com"]
-    #[min_length = 1]
-    #[max_length = 254]
-    wiki_email: String,
-}
-
-fn discord_email_button() -> CreateButton {
-    CreateButton::new("discord_email_button").label("Discord email")
-}
-
-fn dm_wiki_details() -> CreateMessage {
-    CreateMessage::new().content("Welcome to Flying Octopus! In order to create your account on our wiki, please provide your Discord email address (the one you use to log into Discord).")
-        .components(
-            vec![CreateActionRow::Buttons(vec![discord_email_button()])]
-        )
-}
-
 #[poise::command(slash_command, rename = "add")]
 pub async fn add_member(
     ctx: Context<'_>,
@@ -43,7 +30,9 @@ pub async fn add_member(
     #[description = "Trello Report Card ID"] trello_report_card_id: Option<String>,
     #[description = "Wiki ID"] wiki_id: Option<i64>,
     #[description = "Display name"] name: Option<String>,
-    #[description = "Role"] role: Option<MemberRole>,
+    #[description = "Role"]
+    #[autocomplete = "super::role::autocomplete_role"]
+    role: Option<Role>,
 ) -> Result<(), Error> {
     let name = if let Some(name) = name {
         name
@@ -52,212 +41,240 @@ pub async fn add_member(
     };
 
     let discord_id = member.user.id;
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
     // check if member is already in the database
     if let Ok(member) = Member::find_by_discord_id(discord_id.to_string()) {
-        let error_msg = format!("Member already exists in the database: {:?}", member);
+        error!("Member already exists in the database: {:?}", member);
 
-        error!("{}", error_msg);
-
-        output.push_str(&(error_msg + "\n"));
+        output.push_str(&t!(locale, "member.already_exists", member = member));
+        output.push('\n');
 
         crate::discord::respond(ctx, output).await?;
 
         return Ok(());
     }
 
+    if let Some(wiki_id) = wiki_id {
+        Member::check_wiki_id_free(wiki_id, None)?;
+    }
+
     let member = Member::new(
         name,
         Some(discord_id.to_string()),
         trello_id,
         trello_report_card_id,
-        role.unwrap_or_default(),
+        role.map_or(Role::MEMBER, |role| role.id()),
         wiki_id,
     );
 
-    match member.role().add_role(&ctx, discord_id.get()).await {
-        Ok(_) => (),
-        Err(why) => {
-            let error_msg = format!("Failed to add role: {}", why);
-            error!("{}", error_msg);
-            output.push_str(&(error_msg + "\n"));
-        }
-    }
-
-    let ask_wiki_details = member.wiki_id().is_none();
-
-    if !ask_wiki_details {
-        member.assign_wiki_group_by_role().await?;
-    }
-
-    let mut member = member.insert()?;
+    let member = member.insert()?;
 
     info!("Member added: {:?}", member);
-    output.push_str(&format!("Added {}", member));
-
-    if ask_wiki_details {
-        output.push_str("\nInstructions to create wiki account sent via DM to the new member.");
-    }
-
-    crate::discord::respond(ctx, output).await?;
 
-    if ask_wiki_details {
-        // DM new member to get either their Discord email to create new wiki account or
-        // their wiki ID
-        let dm = discord_id.create_dm_channel(&ctx).await?;
-
-        let mut msg = dm.send_message(&ctx, dm_wiki_details()).await?;
-
-        let mut wiki_id = None;
-
-        let mut response =
-            poise::serenity_prelude::ComponentInteractionCollector::new(ctx.serenity_context())
-                .channel_id(dm.id)
-                .author_id(discord_id)
-                .message_id(msg.id)
-                .timeout(std::time::Duration::from_secs(3600))
-                .await;
-
-        while let Some(interaction) = response.clone() {
-            info!("User {} responded to wiki email modal", member.name());
-            let collector =
-                poise::serenity_prelude::ComponentInteractionCollector::new(ctx.serenity_context())
-                    .channel_id(dm.id)
-                    .author_id(discord_id)
-                    .message_id(msg.id)
-                    .timeout(std::time::Duration::from_secs(3600))
-                    .into_future();
-
-            let email = tokio::select! {
-                email = poise::execute_modal_on_component_interaction::<WikiEmailModal>(&ctx, interaction, None, Some(std::time::Duration::from_secs(3600))) => email?,
-                interaction =
-                collector
-                => {
-                    response = interaction;
-                    continue;
-                }
-            };
-
-            if let Some(email) = email {
-                let email = email.wiki_email;
-
-                let user_id = crate::wiki::find_or_create_user(email, member.name().clone())
-                    .await
-                    .unwrap();
-
-                wiki_id = Some(user_id);
-
-                let _ = msg
-                    .edit(
-                        &ctx,
-                        EditMessage::new()
-                            .content(format!(
-                                "Your wiki account has been created. You can now login at {}",
-                                SETTINGS.wiki.url
-                            ))
-                            .components(Vec::new()),
-                    )
-                    .await;
-
-                break;
-            } else {
-                // Timeout
-                warn!("User {} did not respond to wiki email modal", member.name());
-
-                let _ = msg
-                    .edit(&ctx,
-                EditMessage::new()
-                        .content("Timed out waiting for response. Please use `/member update` to update your wiki ID.")
-                        .components(Vec::new())
-                    )
-                    .await;
-
-                break;
+    match onboarding::start(ctx.http(), &member).await {
+        Ok(errors) => {
+            for line in errors.localized(locale) {
+                writeln!(&mut output, "{line}")?;
             }
         }
+        Err(why) => {
+            error!("Failed to onboard member {}: {}", member.name(), why);
 
-        if response.is_none() {
-            // Timeout
-            warn!("User {} did not respond to wiki email modal", member.name());
-
-            // Edit message to indicate timeout
-            let _ = msg
-                .edit(&ctx,
-            EditMessage::new()
-                    .content("Timed out waiting for response. Please use `/member update` to update your wiki ID.")
-                    // .components(|c| c)
-            )
-                .await;
-
-            return Ok(());
+            writeln!(
+                &mut output,
+                "{}",
+                t!(locale, "member.onboarding_failed", error = why)
+            )?;
         }
+    }
 
-        if let Some(wiki_id) = wiki_id {
-            // Get member again to make sure we have the latest version
-            member = Member::find_by_discord_id(discord_id.to_string())?;
-
-            member.set_wiki_id(wiki_id);
-
-            member
-                .unassign_wiki_group(SETTINGS.wiki.guest_group_id)
-                .await?;
-
-            member.assign_wiki_group_by_role().await?;
+    output.push_str(&t!(locale, "member.added", member = member));
 
-            member.update()?;
-        }
+    if member.wiki_id().is_none() {
+        output.push('\n');
+        output.push_str(&t!(locale, "member.wiki_instructions_sent"));
     }
 
-    Ok(())
+    crate::discord::respond(ctx, output).await
 }
 
 #[poise::command(slash_command, rename = "remove")]
 pub async fn remove_member(
     ctx: Context<'_>,
     #[description = "Member of the organization"] member: Member,
+    #[description = "Reason for leaving"] reason: String,
     #[description = "Hard delete member from the database"] hard_delete: Option<bool>,
 ) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
-    if let Some(user_id) = member.discord_id() {
-        let user_id = user_id.parse().unwrap();
-
-        match member.role().remove_role(&ctx, user_id).await {
-            Ok(_) => {}
-            Err(why) => {
-                let error_msg = format!("Failed to remove member's role: {}", why);
-                error!("{}", error_msg);
-                output.push_str(&error_msg);
-                output.push('\n');
-            }
+    if hard_delete.unwrap_or(false) {
+        for line in INTEGRATIONS
+            .member_removed(ctx.http(), &member)
+            .await
+            .localized(locale)
+        {
+            writeln!(&mut output, "{line}")?;
         }
-    }
 
-    if member.wiki_id().is_some() {
-        member.unassign_wiki_group_by_role().await?;
+        member.hard_delete()?;
 
-        // assign account to guest group
-        crate::wiki::assign_user_group(crate::wiki::assign_user_group::Variables {
-            user_id: member.wiki_id().unwrap(),
-            group_id: SETTINGS.wiki.guest_group_id,
-        })
-        .await?;
-    }
+        info!("Member removed: {:?}", member);
 
-    if hard_delete.unwrap_or(false) {
-        member.hard_delete()?;
+        output.push_str(&t!(locale, "member.removed", member = member));
     } else {
-        member.delete()?;
+        let steps = offboarding::offboard(ctx.http(), &member, reason.clone()).await?;
+
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "member.offboarded",
+                member = member,
+                reason = reason
+            )
+        )?;
+        output.push_str(&offboarding::checklist(
+            locale,
+            &steps,
+            &SETTINGS.offboarding.manual_steps,
+        ));
     }
 
-    info!("Member removed: {:?}", member);
+    crate::discord::respond(ctx, output).await
+}
+
+/// Restores an ex-member's previous role and accounts
+#[poise::command(slash_command, rename = "reinstate")]
+pub async fn reinstate_member(
+    ctx: Context<'_>,
+    #[description = "Discord account of the ex-member"] user: serenity::User,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    if let Ok(member) = Member::find_by_discord_id(user.id.to_string()) {
+        return crate::discord::respond(ctx, t!(locale, "member.already_exists", member = member))
+            .await;
+    }
+
+    let Some(archive) = Offboarding::find_by_discord_id(user.id.to_string())? else {
+        return crate::discord::respond(
+            ctx,
+            t!(locale, "member.reinstate_not_found", user = user.name),
+        )
+        .await;
+    };
+
+    let reason = archive.reason().to_string();
+    let offboarded_at = archive.offboarded_at();
+    let role = archive.previous_role()?;
+
+    let (member, steps) = offboarding::reinstate(ctx.http(), archive).await?;
+
+    let mut output = String::new();
+
+    for step in steps {
+        if let Some(why) = step.error {
+            writeln!(
+                &mut output,
+                "{}",
+                t!(
+                    locale,
+                    "member.integration_failed",
+                    integration = step.integration,
+                    error = why
+                )
+            )?;
+        }
+    }
 
-    output.push_str(&format!("Removed {}", member));
+    output.push_str(&t!(
+        locale,
+        "member.reinstated",
+        member = member,
+        role = role,
+        date = offboarded_at.format("%Y-%m-%d"),
+        reason = reason
+    ));
 
     crate::discord::respond(ctx, output).await
 }
 
+/// Creates an invite to the server; users joining through it become members
+/// with the given role
+#[poise::command(
+    slash_command,
+    rename = "invite",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn invite_member(
+    ctx: Context<'_>,
+    #[description = "Role of the new members"]
+    #[autocomplete = "super::role::autocomplete_role"]
+    role: Role,
+    #[description = "How many people can join through the invite (0 for unlimited, default 1)"]
+    #[max = 100]
+    max_uses: Option<u8>,
+    #[description = "Hours until the invite expires (0 for never, default 168)"]
+    #[max = 168]
+    expires_in_hours: Option<u32>,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    if role.id() == Role::EX_MEMBER {
+        return crate::discord::respond(ctx, t!(locale, "member.invite_ex_member")).await;
+    }
+
+    let max_uses = max_uses.unwrap_or(1);
+    let expires_in_hours = expires_in_hours.unwrap_or(168);
+    let channel_id = SETTINGS
+        .discord
+        .invite_channel
+        .unwrap_or_else(|| ctx.channel_id());
+
+    let discord_invite = channel_id
+        .create_invite(
+            ctx,
+            serenity::CreateInvite::new()
+                .max_uses(max_uses)
+                .max_age(expires_in_hours * 60 * 60)
+                .unique(true),
+        )
+        .await?;
+
+    let expires_at = (expires_in_hours > 0).then(|| {
+        chrono::Local::now().naive_local() + chrono::Duration::hours(expires_in_hours.into())
+    });
+
+    let invite = Invite::new(
+        discord_invite.code.clone(),
+        &role,
+        ctx.author().id.to_string(),
+        expires_at,
+        max_uses.into(),
+    )
+    .insert()?;
+
+    info!("Invite created by {}: {:?}", ctx.author().name, invite);
+
+    crate::discord::respond(
+        ctx,
+        t!(
+            locale,
+            "member.invite_created",
+            url = discord_invite.url(),
+            role = role,
+            uses = max_uses,
+            hours = expires_in_hours
+        ),
+    )
+    .await
+}
+
 #[poise::command(slash_command, rename = "update")]
 #[allow(clippy::too_many_arguments)]
 pub async fn update_member(
@@ -267,60 +284,30 @@ pub async fn update_member(
     #[description = "Member Discord"] discord_member: Option<serenity::Member>,
     #[description = "Member Trello ID"] trello_id: Option<String>,
     #[description = "Member Trello Report Card ID"] trello_report_card_id: Option<String>,
-    #[description = "Member role"] role: Option<MemberRole>,
+    #[description = "Member role"]
+    #[autocomplete = "super::role::autocomplete_role"]
+    role: Option<Role>,
     #[description = "Member wiki ID"] wiki_id: Option<i64>,
     #[description = "Refresh member's activity"] refresh_activity: Option<bool>,
+    #[description = "Comma-separated skills, replacing the current ones"] skills: Option<String>,
 ) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
+    let old = member.clone();
+
     if let Some(new_name) = name {
         member.set_name(new_name)?
     }
 
     if let Some(new_discord_member) = discord_member {
-        if let Some(old_dc_id) = member.discord_id() {
-            match member.role().remove_role(&ctx, old_dc_id.parse()?).await {
-                Ok(_) => {}
-                Err(why) => {
-                    let error_msg = format!("Failed to remove member role: {}", why);
-                    error!("{}", error_msg);
-                    output.push_str(&error_msg);
-                    output.push('\n');
-                }
-            }
-        }
-
         let dc_id = new_discord_member.user.id.get().to_string();
 
         member.set_discord_id(dc_id);
     }
 
     if let Some(new_role) = role {
-        let user_id = member.discord_id().unwrap().parse()?;
-
-        match member.role().remove_role(&ctx, user_id).await {
-            Ok(_) => {}
-            Err(why) => {
-                let error_msg = format!("Failed to remove member role: {}", why);
-                error!("{}", error_msg);
-                output.push_str(&error_msg);
-                output.push('\n');
-            }
-        }
-
-        // remove wiki account from old group
-        if member.wiki_id().is_some() {
-            member.unassign_wiki_group_by_role().await?;
-        }
-
-        member.set_role(new_role);
-
-        // assign wiki account to new group
-        member.assign_wiki_group_by_role().await?;
-    }
-
-    if let Some(dc_id) = member.discord_id() {
-        member.role().add_role(&ctx, dc_id.parse::<u64>()?).await?;
+        member.set_role(&new_role);
     }
 
     if let Some(new_trello_id) = trello_id {
@@ -331,47 +318,83 @@ pub async fn update_member(
         member.set_trello_report_card_id(new_trello_report_card_id)
     }
 
-    if let Some(refresh_activity) = refresh_activity {
-        if refresh_activity {
-            member.refresh_activity()?;
-        }
+    if let Some(new_wiki_id) = wiki_id {
+        Member::check_wiki_id_free(new_wiki_id, Some(member.id()))?;
+        member.set_wiki_id(new_wiki_id);
     }
 
-    if let Some(new_wiki_id) = wiki_id {
-        // remove old account from group - only if it's not guest group
-        let is_guest_group = member.wiki_group() != SETTINGS.wiki.guest_group_id;
-
-        if is_guest_group {
-            member.unassign_wiki_group_by_role().await?;
-
-            // assign old account to guest group
-            crate::wiki::assign_user_group(crate::wiki::assign_user_group::Variables {
-                user_id: member.wiki_id().unwrap(),
-                group_id: SETTINGS.wiki.guest_group_id,
-            })
-            .await?;
-
-            member.set_wiki_id(new_wiki_id);
-
-            // remove new account from guest group
-            crate::wiki::unassign_user_group(crate::wiki::unassign_user_group::Variables {
-                user_id: member.wiki_id().unwrap(),
-                group_id: SETTINGS.wiki.guest_group_id,
-            })
-            .await?;
-
-            // assign new account to group
-            member.assign_wiki_group_by_role().await?;
-        } else {
-            member.set_wiki_id(new_wiki_id);
-        }
+    if let Some(new_skills) = skills {
+        member.set_skills(member::parse_skills(&new_skills));
     }
 
     member.update()?;
 
+    // roles may require different activity
+    if refresh_activity == Some(true) || old.role_id() != member.role_id() {
+        member.refresh_activity()?;
+    }
+
     info!("Member updated: {}", member);
 
-    output.push_str(&format!("Updated {}", member));
+    let mut errors = INTEGRATIONS
+        .profile_updated(ctx.http(), &old, &member)
+        .await;
+
+    if old.role_id() != member.role_id() {
+        errors.extend(
+            INTEGRATIONS
+                .role_changed(ctx.http(), &member, &old.role()?)
+                .await,
+        );
+    }
+
+    // linking the wiki account by hand lets a stalled onboarding continue
+    if old.wiki_id() != member.wiki_id() {
+        errors.extend(onboarding::resume_for_member(ctx.http(), &member).await?);
+    }
+
+    for line in errors.localized(locale) {
+        writeln!(&mut output, "{line}")?;
+    }
+
+    output.push_str(&t!(locale, "member.updated", member = member));
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Pushes the member's name and status to their wiki account and lists the
+/// differences that were fixed
+#[poise::command(slash_command, rename = "sync")]
+pub async fn sync_member(
+    ctx: Context<'_>,
+    #[description = "Member of the organization"] member: Member,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    if member.wiki_id().is_none() {
+        return crate::discord::respond(ctx, t!(locale, "member.sync_no_wiki", member = member))
+            .await;
+    }
+
+    let changes = member.sync_wiki_profile().await?;
+
+    info!("Member synced with wiki: {} {:?}", member, changes);
+
+    let mut output = String::new();
+
+    if changes.is_empty() {
+        output.push_str(&t!(locale, "member.sync_up_to_date", member = member));
+    } else {
+        writeln!(
+            &mut output,
+            "{}",
+            t!(locale, "member.synced", member = member)
+        )?;
+
+        for change in changes {
+            writeln!(&mut output, "- {}", change.localized(locale))?;
+        }
+    }
 
     crate::discord::respond(ctx, output).await
 }
@@ -381,19 +404,367 @@ pub async fn list_members(
     ctx: Context<'_>,
     #[description = "Page number"] page: Option<i64>,
     #[description = "Page size"] page_size: Option<i64>,
-    #[description = "Member role"] role: Option<MemberRole>,
+    #[description = "Member role"]
+    #[autocomplete = "super::role::autocomplete_role"]
+    role: Option<Role>,
     #[description = "Member's activity"] activity: Option<Activity>,
+    #[description = "Team"]
+    #[autocomplete = "super::team::autocomplete_team"]
+    team: Option<Team>,
 ) -> Result<(), Error> {
     let page = page.unwrap_or(1);
 
-    let (members, total_pages) = Member::list(page, page_size, role, None, activity, None)?;
+    let (members, total_pages) = Member::list(
+        page,
+        page_size,
+        role.map(|role| role.id()),
+        None,
+        activity,
+        None,
+        team.map(|team| team.id()),
+        None,
+    )?;
 
     let mut output = String::new();
 
     for member in members {
         writeln!(&mut output, "{}\n", member)?;
     }
-    write!(&mut output, "Page: {page}/{total_pages}")?;
+    write!(
+        &mut output,
+        "{}",
+        t!(
+            i18n::user_locale(ctx),
+            "common.page",
+            page = page,
+            total = total_pages
+        )
+    )?;
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Finds the members with a skill, e.g. art, code, audio or design
+#[poise::command(slash_command, rename = "find")]
+pub async fn find_members(
+    ctx: Context<'_>,
+    #[description = "Skill"] skill: String,
+    #[description = "Team"]
+    #[autocomplete = "super::team::autocomplete_team"]
+    team: Option<Team>,
+    #[description = "Page number"] page: Option<i64>,
+    #[description = "Page size"] page_size: Option<i64>,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let page = page.unwrap_or(1);
+
+    let (members, total_pages) = Member::list(
+        page,
+        page_size,
+        None,
+        Some(Role::EX_MEMBER),
+        None,
+        None,
+        team.map(|team| team.id()),
+        Some(&skill),
+    )?;
+
+    if members.is_empty() {
+        return crate::discord::respond(ctx, t!(locale, "member.find_none", skill = skill)).await;
+    }
+
+    let mut output = String::new();
+
+    for member in members {
+        writeln!(&mut output, "{}\n", member)?;
+    }
+    write!(
+        &mut output,
+        "{}",
+        t!(locale, "common.page", page = page, total = total_pages)
+    )?;
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Assigns an apprentice's mentor, or removes it without a mentor
+#[poise::command(slash_command, rename = "mentor")]
+pub async fn mentor_member(
+    ctx: Context<'_>,
+    #[description = "Apprentice"] mut apprentice: Member,
+    #[description = "Mentor"] mentor: Option<Member>,
+) -> Result<(), Error> {
+    if !permissions::can_manage_promotions(ctx).await {
+        return Err(Error::NotLead);
+    }
+
+    let locale = i18n::user_locale(ctx);
+
+    apprentice.set_mentor(mentor.as_ref().map(Member::id))?;
+
+    info!(
+        "Mentor of {} set to {:?} by {}",
+        apprentice.name(),
+        mentor.as_ref().map(Member::name),
+        ctx.author().name
+    );
+
+    let output = match mentor {
+        Some(mentor) => t!(
+            locale,
+            "member.mentor_assigned",
+            member = apprentice.name(),
+            mentor = mentor.name()
+        ),
+        None => t!(locale, "member.mentor_removed", member = apprentice.name()),
+    };
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Shows how far apprentices are with the promotion checklist
+#[poise::command(slash_command, rename = "promotion-status")]
+pub async fn promotion_status(
+    ctx: Context<'_>,
+    #[description = "Apprentice (defaults to all apprentices)"] member: Option<Member>,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    let apprentices = match member {
+        Some(member) if member.role_id() != Role::APPRENTICE => {
+            return Err(Error::NotApprentice(member.name()));
+        }
+        Some(member) => vec![member],
+        None => Member::list_with_role(Role::APPRENTICE)?,
+    };
+
+    if apprentices.is_empty() {
+        return crate::discord::respond(ctx, t!(locale, "member.promotion_no_apprentices")).await;
+    }
+
+    let mut output = String::new();
+
+    for apprentice in apprentices {
+        let checks = promotion::evaluate(&apprentice)?;
+        let mentor = match apprentice.mentor_id() {
+            Some(mentor_id) => Member::find_by_id(mentor_id)?.name(),
+            None => t!(locale, "member.promotion_no_mentor"),
+        };
+        let key = if promotion::is_ready(&checks) {
+            "member.promotion_ready"
+        } else {
+            "member.promotion_not_ready"
+        };
+
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                key,
+                member = apprentice.name(),
+                since = apprentice.role_since().format("%Y-%m-%d"),
+                mentor = mentor
+            )
+        )?;
+
+        for check in checks {
+            writeln!(&mut output, "- {}", check.localized(locale))?;
+        }
+    }
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Promotes an apprentice who completed the promotion checklist to member
+#[poise::command(slash_command, rename = "promote")]
+pub async fn promote_member(
+    ctx: Context<'_>,
+    #[description = "Apprentice"] mut member: Member,
+    #[description = "Promote even if the checklist is not complete"] force: Option<bool>,
+) -> Result<(), Error> {
+    if !permissions::can_manage_promotions(ctx).await {
+        return Err(Error::NotLead);
+    }
+
+    let locale = i18n::user_locale(ctx);
+
+    if member.role_id() != Role::APPRENTICE {
+        return Err(Error::NotApprentice(member.name()));
+    }
+
+    if !force.unwrap_or(false) && !promotion::is_ready(&promotion::evaluate(&member)?) {
+        return crate::discord::respond(
+            ctx,
+            t!(
+                locale,
+                "member.promotion_incomplete",
+                member = member.name()
+            ),
+        )
+        .await;
+    }
+
+    let errors = promotion::promote(ctx.http(), &mut member).await?;
+
+    let mut output = String::new();
+
+    for line in errors.localized(locale) {
+        writeln!(&mut output, "{line}")?;
+    }
+
+    output.push_str(&t!(locale, "member.promoted", member = member.name()));
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Lists onboardings of new members that are not done yet
+#[poise::command(
+    slash_command,
+    rename = "onboarding",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn list_onboardings(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let pending = Onboarding::list_pending()?;
+
+    if pending.is_empty() {
+        return crate::discord::respond(ctx, t!(locale, "member.onboarding_none")).await;
+    }
+
+    let mut output = String::new();
+
+    for onboarding in pending {
+        let member = Member::find_by_id(onboarding.member_id())?;
+
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "member.onboarding_pending",
+                member = member.name(),
+                step = onboarding.step().localized(locale),
+                since = onboarding.updated_at().format("%Y-%m-%d %H:%M"),
+                reminders = onboarding.reminders_sent()
+            )
+        )?;
+
+        if let Some(error) = onboarding.last_error() {
+            writeln!(
+                &mut output,
+                "  {}",
+                t!(locale, "member.onboarding_last_error", error = error)
+            )?;
+        }
+    }
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Compares the guild members' Discord roles with the database and offers to
+/// fix the mismatches
+#[poise::command(
+    slash_command,
+    rename = "audit",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn audit_members(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let mismatches = roles::audit(ctx.http()).await?;
+
+    info!(
+        "Discord roles audited by {}: {} mismatches",
+        ctx.author().name,
+        mismatches.len()
+    );
+
+    if mismatches.is_empty() {
+        return crate::discord::respond(ctx, t!(locale, "member.audit_in_sync")).await;
+    }
+
+    let mut output = String::new();
+
+    for mismatch in &mismatches {
+        writeln!(&mut output, "- {}", mismatch.localized(locale))?;
+    }
+
+    crate::discord::respond(ctx, output).await?;
+
+    let fixable = mismatches.iter().filter(|m| m.is_fixable()).count();
+
+    if fixable == 0 {
+        return crate::discord::respond(ctx, t!(locale, "member.audit_nothing_to_fix")).await;
+    }
+
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .content(t!(locale, "member.audit_confirm", count = fixable))
+                .components(vec![serenity::CreateActionRow::Buttons(vec![
+                    serenity::CreateButton::new("audit_apply")
+                        .label(t!(locale, "member.audit_apply_button"))
+                        .style(serenity::ButtonStyle::Danger),
+                    serenity::CreateButton::new("audit_cancel")
+                        .label(t!(locale, "member.audit_cancel_button")),
+                ])]),
+        )
+        .await?;
+    let message_id = reply.message().await?.id;
+
+    let interaction = serenity::ComponentInteractionCollector::new(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .message_id(message_id)
+        .timeout(Duration::from_secs(600))
+        .await;
+
+    // the buttons are removed whatever the answer
+    reply
+        .edit(ctx, poise::CreateReply::default().components(vec![]))
+        .await?;
+
+    let Some(interaction) = interaction else {
+        return crate::discord::respond(ctx, t!(locale, "member.audit_timed_out")).await;
+    };
+
+    interaction
+        .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    if interaction.data.custom_id != "audit_apply" {
+        return crate::discord::respond(ctx, t!(locale, "member.audit_cancelled")).await;
+    }
+
+    let results = roles::fix(ctx.http(), mismatches).await;
+
+    info!(
+        "Discord role fixes applied by {}: {:?}",
+        ctx.author().name,
+        results
+    );
+
+    let mut output = String::new();
+    let mut fixed = 0;
+
+    for (mismatch, status) in &results {
+        if matches!(status, FixStatus::Fixed) {
+            fixed += 1;
+        }
+
+        if let Some(status) = status.localized(locale) {
+            writeln!(&mut output, "- {} ({status})", mismatch.localized(locale))?;
+        }
+    }
+
+    output.push('\n');
+    output.push_str(&t!(
+        locale,
+        "member.audit_applied",
+        fixed = fixed,
+        count = fixable
+    ));
 
     crate::discord::respond(ctx, output).await
 }
@@ -2,16 +2,27 @@ use super::Context;
 use crate::error::Error;
 
 mod activity;
+mod field;
+mod leave;
+mod me;
 mod meeting;
 mod member;
 mod report;
+pub(crate) mod role;
 mod silent;
 mod summary;
+mod team;
+mod wiki;
 
 #[poise::command(
     slash_command,
     category = "Activity",
-    subcommands("activity::refresh", "activity::list",)
+    subcommands(
+        "activity::refresh",
+        "activity::list",
+        "activity::timeline",
+        "activity::escalations"
+    )
 )]
 pub async fn activity(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
@@ -22,15 +33,51 @@ pub async fn activity(_ctx: Context<'_>) -> Result<(), Error> {
     category = "Member",
     subcommands(
         "member::add_member",
+        "member::invite_member",
         "member::remove_member",
+        "member::reinstate_member",
         "member::update_member",
-        "member::list_members"
+        "member::list_members",
+        "member::find_members",
+        "member::mentor_member",
+        "member::promotion_status",
+        "member::promote_member",
+        "member::sync_member",
+        "member::list_onboardings",
+        "member::audit_members"
     )
 )]
 pub async fn member(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Leaves of absence, which do not count towards inactivity.
+#[poise::command(
+    slash_command,
+    category = "Activity",
+    subcommands("leave::add_leave", "leave::list_leaves", "leave::remove_leave")
+)]
+pub async fn leave(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Self-service commands for members. Role changes stay with the admins.
+#[poise::command(
+    slash_command,
+    category = "Member",
+    subcommands(
+        "me::profile",
+        "me::activity",
+        "me::reports",
+        "me::meetings",
+        "me::rename",
+        "me::wiki"
+    )
+)]
+pub async fn me(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
 #[poise::command(
     slash_command,
     category = "Report",
@@ -71,6 +118,68 @@ pub async fn silent_mode(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Admin catalogue of member roles.
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands(
+        "role::list_roles",
+        "role::add_role",
+        "role::update_role",
+        "role::remove_role",
+        "role::grant_permission",
+        "role::revoke_permission",
+    ),
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn role(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Project teams members belong to.
+#[poise::command(
+    slash_command,
+    category = "Member",
+    subcommands(
+        "team::list_teams",
+        "team::add_team",
+        "team::remove_team",
+        "team::assign_team",
+        "team::unassign_team",
+    )
+)]
+pub async fn team(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Admin-defined fields of the member profile.
+#[poise::command(
+    slash_command,
+    category = "Member",
+    subcommands(
+        "field::list_fields",
+        "field::add_field",
+        "field::remove_field",
+        "field::set_field",
+    )
+)]
+pub async fn field(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Admin tools for the wiki integration.
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("wiki::groups"),
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn wiki(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
 #[poise::command(
     slash_command,
     category = "Meeting",
@@ -1,13 +1,25 @@
 use std::fmt::Write;
 
 use tracing::info;
+use uuid::Uuid;
 
 use crate::{
-    database::models::{member::Member, report::Report, summary::Summary},
-    discord::Context,
+    database::models::{
+        activity_event::ActivityKind, member::Member, report::Report, summary::Summary,
+    },
+    discord::{permissions, Context},
     error::Error,
+    i18n::{self, t},
+    trello::reports,
 };
 
+/// Returns the member id of the command's author, if they are a member.
+fn author_member_id(ctx: Context<'_>) -> Option<Uuid> {
+    Member::find_by_discord_id(ctx.author().id.to_string())
+        .ok()
+        .map(|member| member.id())
+}
+
 #[poise::command(slash_command, rename = "add")]
 pub(crate) async fn add_report(
     ctx: Context<'_>,
@@ -23,23 +35,47 @@ pub(crate) async fn add_report(
         }
     };
 
+    let locked = summary
+        .as_ref()
+        .is_some_and(|summary| summary.is_published());
+
+    permissions::check_report_access(
+        author_member_id(ctx),
+        member.id(),
+        locked,
+        permissions::is_lead(ctx).await,
+    )?;
+
     let mut report = Report::insert(member.id(), content)?;
 
+    // reports written before an ended meeting's cut-off still belong to its
+    // summary
+    let summary = match summary {
+        Some(summary) => Some(summary),
+        None => report.late_summary()?,
+    };
+
     if let Some(summary) = summary {
         report.set_summary_id(summary.id())?;
 
         if summary.is_published() {
             report.set_publish()?;
-        }
 
-        summary.send_summary(ctx, true).await?;
+            summary.send_summary(ctx, true).await?;
+        }
     }
 
-    member.update_activity(report.create_date)?;
+    member.record_activity(ActivityKind::Report, report.id(), report.create_date)?;
 
     info!("Report added: {:?}", report);
 
-    let output = format!("Added: {}", report);
+    let locale = i18n::user_locale(ctx);
+    let mut output = t!(locale, "report.added", report = report);
+
+    if let Err(why) = reports::mirror_report(&mut report).await {
+        output.push('\n');
+        output.push_str(&t!(locale, "report.trello_failed", error = why));
+    }
 
     crate::discord::respond(ctx, output).await
 }
@@ -49,16 +85,40 @@ pub(crate) async fn remove_report(
     ctx: Context<'_>,
     #[description = "Report's ID"] report: Report,
 ) -> Result<(), Error> {
+    permissions::check_report_access(
+        author_member_id(ctx),
+        report.member_id,
+        report.is_locked()?,
+        permissions::is_lead(ctx).await,
+    )?;
+
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
+    if let Err(why) = reports::unmirror_report(&report).await {
+        writeln!(
+            &mut output,
+            "{}",
+            t!(locale, "report.trello_failed", error = why)
+        )?;
+    }
+
     match report.delete() {
         Ok(rows) => {
             if rows == 1 {
                 info!("Report removed: {:?}", report);
-                writeln!(&mut output, "Removed: {}", report)?;
+                writeln!(
+                    &mut output,
+                    "{}",
+                    t!(locale, "report.removed", report = report)
+                )?;
             } else {
                 info!("Removed {rows} reports");
-                writeln!(&mut output, "Removed {rows} reports")?;
+                writeln!(
+                    &mut output,
+                    "{}",
+                    t!(locale, "report.removed_many", count = rows)
+                )?;
             }
         }
         Err(err) => return Err(err),
@@ -66,13 +126,7 @@ pub(crate) async fn remove_report(
 
     let mut member = Member::find_by_id(report.member_id)?;
 
-    let last_activity = member.last_activity();
-
-    if let Some(last_activity) = last_activity {
-        if report.create_date >= last_activity {
-            member.refresh_activity()?;
-        }
-    }
+    member.forget_activity(ActivityKind::Report, report.id())?;
 
     crate::discord::respond(ctx, output).await
 }
@@ -98,7 +152,16 @@ pub(crate) async fn list_reports(
         writeln!(&mut output, "{}\n", report)?;
     }
 
-    write!(&mut output, "Page {} of {}", page, total_pages)?;
+    write!(
+        &mut output,
+        "{}",
+        t!(
+            i18n::user_locale(ctx),
+            "common.page",
+            page = page,
+            total = total_pages
+        )
+    )?;
 
     crate::discord::respond(ctx, output).await
 }
@@ -111,6 +174,26 @@ pub(crate) async fn update_report(
     #[description = "Member of the organization"] member: Option<Member>,
     #[description = "Summary's ID"] summary: Option<Summary>,
 ) -> Result<(), Error> {
+    let author = author_member_id(ctx);
+    let is_lead = permissions::is_lead(ctx).await;
+
+    permissions::check_report_access(author, report.member_id, report.is_locked()?, is_lead)?;
+
+    // moving the report to another member or summary needs the same rights
+    // over the destination
+    if let Some(member) = &member {
+        permissions::check_report_access(author, member.id(), false, is_lead)?;
+    }
+
+    if let Some(summary) = &summary {
+        permissions::check_report_access(
+            author,
+            report.member_id,
+            summary.is_published(),
+            is_lead,
+        )?;
+    }
+
     if let Some(content) = content {
         report.content = content;
     }
@@ -129,22 +212,17 @@ pub(crate) async fn update_report(
         report.set_summary_id(summary.id())?;
     }
 
-    let report = report.update()?;
+    let mut report = report.update()?;
 
     if let Some(mut member) = member {
-        member.update_activity(report.create_date)?;
+        member.record_activity(ActivityKind::Report, report.id(), report.create_date)?;
 
         if let Some(mut old_member) = old_member {
-            let old_last_activity = old_member.last_activity();
-
-            if let Some(old_last_activity) = old_last_activity {
-                if report.create_date >= old_last_activity {
-                    old_member.refresh_activity()?;
-                }
-            }
+            old_member.forget_activity(ActivityKind::Report, report.id())?;
         }
     }
 
+    let locale = i18n::user_locale(ctx);
     let mut output = String::new();
 
     if let Some(summary_id) = report.summary_id() {
@@ -152,14 +230,27 @@ pub(crate) async fn update_report(
         let summary = Summary::find_by_id(summary_id)?;
 
         match summary.send_summary(ctx, true).await {
-            Ok(_) => writeln!(&mut output, "Summary updated")?,
-            Err(why) => writeln!(&mut output, "Can't update summary: {}", why)?,
+            Ok(_) => writeln!(&mut output, "{}", t!(locale, "report.summary_updated"))?,
+            Err(why) => writeln!(
+                &mut output,
+                "{}",
+                t!(locale, "report.summary_update_failed", error = why)
+            )?,
         }
     }
 
+    if let Err(why) = reports::mirror_report(&mut report).await {
+        writeln!(
+            &mut output,
+            "{}",
+            t!(locale, "report.trello_failed", error = why)
+        )?;
+    }
+
     info!("Report updated: {:?}", report);
 
-    output.push_str(&format!("\nUpdated: {}", report));
+    output.push('\n');
+    output.push_str(&t!(locale, "report.updated", report = report));
 
     crate::discord::respond(ctx, output).await
 }
@@ -0,0 +1,310 @@
+use std::fmt::Write;
+
+use poise::{serenity_prelude as serenity, ChoiceParameter};
+use tracing::info;
+
+use super::Context;
+use crate::{
+    database::models::{
+        member::Member,
+        role::{Permission, RequiredActivity, Role},
+    },
+    error::Error,
+    i18n::{self, t},
+};
+
+/// Settings of a role that can be cleared.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum RoleField {
+    #[name = "Discord role"]
+    DiscordRole,
+    #[name = "Wiki group"]
+    WikiGroup,
+    #[name = "Activity threshold"]
+    ActivityThreshold,
+}
+
+/// Suggests the names of the roles matching what was typed so far.
+pub(crate) async fn autocomplete_role(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let partial = partial.to_lowercase();
+
+    Role::all()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|role| role.name().to_string())
+        .filter(|name| name.to_lowercase().contains(&partial))
+        .collect()
+}
+
+fn describe(locale: &str, role: &Role) -> String {
+    let none = t!(locale, "role.none");
+
+    let discord_role = role
+        .discord_role()
+        .map(|id| format!("<@&{}>", id))
+        .unwrap_or_else(|| none.clone());
+    let wiki_group = role
+        .wiki_group_id()
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| none.clone());
+    let threshold = role
+        .activity_threshold_days()
+        .map(|days| t!(locale, "role.threshold_days", days = days))
+        .unwrap_or_else(|| t!(locale, "role.default_threshold"));
+    let permissions = role
+        .permissions()
+        .iter()
+        .map(|p| p.name())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    t!(
+        locale,
+        "role.entry",
+        role = role.name(),
+        id = role.id(),
+        discord_role = discord_role,
+        wiki_group = wiki_group,
+        threshold = threshold,
+        required_activity = role.required_activity().localized(locale),
+        permissions = if permissions.is_empty() {
+            none
+        } else {
+            permissions
+        }
+    )
+}
+
+/// Lists the roles with their Discord role, wiki group, activity policy and
+/// permissions
+#[poise::command(
+    slash_command,
+    rename = "list",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn list_roles(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let mut output = String::new();
+
+    for role in Role::reload()? {
+        writeln!(&mut output, "{}", describe(locale, &role))?;
+    }
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Adds a role
+#[poise::command(
+    slash_command,
+    rename = "add",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn add_role(
+    ctx: Context<'_>,
+    #[description = "Role name"] name: String,
+    #[description = "Discord role given to members with the role"] discord_role: Option<
+        serenity::Role,
+    >,
+    #[description = "Wiki group of members with the role"] wiki_group_id: Option<i64>,
+    #[description = "Days without activity after which members with the role are inactive"]
+    #[min = 1]
+    activity_threshold_days: Option<i32>,
+    #[description = "Activity that keeps members with the role active, any by default"]
+    required_activity: Option<RequiredActivity>,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    if Role::find_by_name(&name).is_ok() {
+        return crate::discord::respond(ctx, t!(locale, "role.already_exists", role = name)).await;
+    }
+
+    let mut role = Role::insert(name)?;
+
+    role.set_discord_role(discord_role.map(|r| r.id));
+    role.set_wiki_group_id(wiki_group_id);
+    role.set_activity_threshold_days(activity_threshold_days);
+    role.set_required_activity(required_activity.unwrap_or(RequiredActivity::Any));
+
+    let role = role.update()?;
+
+    info!("Role added by {}: {:?}", ctx.author().name, role);
+
+    crate::discord::respond(
+        ctx,
+        t!(locale, "role.added", role = describe(locale, &role)),
+    )
+    .await
+}
+
+/// Changes a role's name, Discord role, wiki group or activity policy
+#[poise::command(
+    slash_command,
+    rename = "update",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn update_role(
+    ctx: Context<'_>,
+    #[description = "Role"]
+    #[autocomplete = "autocomplete_role"]
+    mut role: Role,
+    #[description = "New role name"] name: Option<String>,
+    #[description = "Discord role given to members with the role"] discord_role: Option<
+        serenity::Role,
+    >,
+    #[description = "Wiki group of members with the role"] wiki_group_id: Option<i64>,
+    #[description = "Days without activity after which members with the role are inactive"]
+    #[min = 1]
+    activity_threshold_days: Option<i32>,
+    #[description = "Activity that keeps members with the role active"] required_activity: Option<
+        RequiredActivity,
+    >,
+    #[description = "Setting to clear"] clear: Option<RoleField>,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let old_required_activity = role.required_activity();
+
+    if let Some(new_name) = name {
+        role.set_name(new_name);
+    }
+
+    if let Some(discord_role) = discord_role {
+        role.set_discord_role(Some(discord_role.id));
+    }
+
+    if let Some(wiki_group_id) = wiki_group_id {
+        role.set_wiki_group_id(Some(wiki_group_id));
+    }
+
+    if let Some(activity_threshold_days) = activity_threshold_days {
+        role.set_activity_threshold_days(Some(activity_threshold_days));
+    }
+
+    if let Some(required_activity) = required_activity {
+        role.set_required_activity(required_activity);
+    }
+
+    match clear {
+        Some(RoleField::DiscordRole) => role.set_discord_role(None),
+        Some(RoleField::WikiGroup) => role.set_wiki_group_id(None),
+        Some(RoleField::ActivityThreshold) => role.set_activity_threshold_days(None),
+        None => {}
+    }
+
+    let role = role.update()?;
+
+    info!("Role updated by {}: {:?}", ctx.author().name, role);
+
+    // the last activity of the role's members only counts the required
+    // activity, so it is derived again
+    if role.required_activity() != old_required_activity {
+        for mut member in Member::list_with_role(role.id())? {
+            member.refresh_activity()?;
+        }
+    }
+
+    crate::discord::respond(
+        ctx,
+        t!(locale, "role.updated", role = describe(locale, &role)),
+    )
+    .await
+}
+
+/// Removes a role no member holds
+#[poise::command(
+    slash_command,
+    rename = "remove",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn remove_role(
+    ctx: Context<'_>,
+    #[description = "Role"]
+    #[autocomplete = "autocomplete_role"]
+    role: Role,
+) -> Result<(), Error> {
+    role.delete()?;
+
+    info!("Role removed by {}: {:?}", ctx.author().name, role);
+
+    crate::discord::respond(
+        ctx,
+        t!(i18n::user_locale(ctx), "role.removed", role = role.name()),
+    )
+    .await
+}
+
+/// Grants a permission to members with the role
+#[poise::command(
+    slash_command,
+    rename = "grant",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn grant_permission(
+    ctx: Context<'_>,
+    #[description = "Role"]
+    #[autocomplete = "autocomplete_role"]
+    mut role: Role,
+    #[description = "Permission"] permission: Permission,
+) -> Result<(), Error> {
+    role.grant(permission);
+    let role = role.update()?;
+
+    info!(
+        "Permission {:?} granted to role {} by {}",
+        permission,
+        role,
+        ctx.author().name
+    );
+
+    crate::discord::respond(
+        ctx,
+        t!(
+            i18n::user_locale(ctx),
+            "role.granted",
+            role = role.name(),
+            permission = permission.name()
+        ),
+    )
+    .await
+}
+
+/// Revokes a permission from members with the role
+#[poise::command(
+    slash_command,
+    rename = "revoke",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn revoke_permission(
+    ctx: Context<'_>,
+    #[description = "Role"]
+    #[autocomplete = "autocomplete_role"]
+    mut role: Role,
+    #[description = "Permission"] permission: Permission,
+) -> Result<(), Error> {
+    role.revoke(permission);
+    let role = role.update()?;
+
+    info!(
+        "Permission {:?} revoked from role {} by {}",
+        permission,
+        role,
+        ctx.author().name
+    );
+
+    crate::discord::respond(
+        ctx,
+        t!(
+            i18n::user_locale(ctx),
+            "role.revoked",
+            role = role.name(),
+            permission = permission.name()
+        ),
+    )
+    .await
+}
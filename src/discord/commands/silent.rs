@@ -1,7 +1,11 @@
 use tracing::info;
 
 use super::Context;
-use crate::{error::Error, silent};
+use crate::{
+    error::Error,
+    i18n::{self, t},
+    silent,
+};
 
 /// Show whether silent mode is currently enabled.
 #[poise::command(
@@ -11,14 +15,15 @@ use crate::{error::Error, silent};
     default_member_permissions = "ADMINISTRATOR"
 )]
 pub(crate) async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
     let output = if silent::is_enabled() {
-        "Silent mode is enabled. The bot will not act on its own (scheduled meetings are not \
-         started). It still responds to commands."
+        t!(locale, "silent.status_enabled")
     } else {
-        "Silent mode is disabled. The bot acts normally (scheduled meetings are started)."
+        t!(locale, "silent.status_disabled")
     };
 
-    crate::discord::respond(ctx, output.to_string()).await
+    crate::discord::respond(ctx, output).await
 }
 
 /// Enable silent mode: the bot stops acting on its own.
@@ -33,13 +38,7 @@ pub(crate) async fn enable(ctx: Context<'_>) -> Result<(), Error> {
 
     info!("Silent mode enabled by {}", ctx.author().name);
 
-    crate::discord::respond(
-        ctx,
-        "Silent mode enabled. The bot will not start scheduled meetings or send anything on its \
-         own. It still responds to commands."
-            .to_string(),
-    )
-    .await
+    crate::discord::respond(ctx, t!(i18n::user_locale(ctx), "silent.enabled")).await
 }
 
 /// Disable silent mode: the bot resumes acting on its own.
@@ -54,10 +53,5 @@ pub(crate) async fn disable(ctx: Context<'_>) -> Result<(), Error> {
 
     info!("Silent mode disabled by {}", ctx.author().name);
 
-    crate::discord::respond(
-        ctx,
-        "Silent mode disabled. Scheduled meetings will resume at the next scheduled time."
-            .to_string(),
-    )
-    .await
+    crate::discord::respond(ctx, t!(i18n::user_locale(ctx), "silent.disabled")).await
 }
@@ -1,6 +1,11 @@
 use std::fmt::Write;
 
-use crate::{database::models::summary::Summary, discord::Context, error::Error};
+use crate::{
+    database::models::summary::Summary,
+    discord::{embed, Context},
+    error::Error,
+    i18n::{self, t},
+};
 
 #[poise::command(slash_command, rename = "preview")]
 pub(crate) async fn preview_summary(
@@ -18,9 +23,9 @@ pub(crate) async fn preview_summary(
         Summary::find_by_id(meeting_status.summary_id())?
     };
 
-    let summary = summary.generate_summary(note, false).await?;
+    let sections = summary.generate_summary(note, false).await?;
 
-    crate::discord::respond(ctx, summary).await
+    crate::discord::respond_embeds(ctx, embed::paginate(sections)).await
 }
 
 #[poise::command(slash_command, rename = "list")]
@@ -38,7 +43,16 @@ pub(crate) async fn list_summaries(
     for summary in summaries {
         writeln!(&mut output, "{}\n", summary)?;
     }
-    write!(output, "Page {}/{}", page, total_pages)?;
+    write!(
+        output,
+        "{}",
+        t!(
+            i18n::user_locale(ctx),
+            "common.page",
+            page = page,
+            total = total_pages
+        )
+    )?;
 
     crate::discord::respond(ctx, output).await
 }
@@ -0,0 +1,163 @@
+use std::fmt::Write;
+
+use tracing::info;
+
+use super::Context;
+use crate::{
+    database::models::{member::Member, team::Team},
+    error::Error,
+    i18n::{self, t},
+};
+
+/// Suggests the names of the teams matching what was typed so far.
+pub(crate) async fn autocomplete_team(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let partial = partial.to_lowercase();
+
+    Team::all()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|team| team.name().to_string())
+        .filter(|name| name.to_lowercase().contains(&partial))
+        .collect()
+}
+
+/// Lists the teams with their members
+#[poise::command(slash_command, rename = "list")]
+pub(crate) async fn list_teams(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let teams = Team::all()?;
+
+    if teams.is_empty() {
+        return crate::discord::respond(ctx, t!(locale, "team.none")).await;
+    }
+
+    let mut output = String::new();
+
+    for team in teams {
+        let members = team.members()?.iter().map(Member::name).collect::<Vec<_>>();
+
+        writeln!(
+            &mut output,
+            "{}",
+            t!(
+                locale,
+                "team.entry",
+                team = team.name(),
+                count = members.len(),
+                members = members.join(", ")
+            )
+        )?;
+    }
+
+    crate::discord::respond(ctx, output).await
+}
+
+/// Adds a team
+#[poise::command(
+    slash_command,
+    rename = "add",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn add_team(
+    ctx: Context<'_>,
+    #[description = "Team name"] name: String,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    if Team::find_by_name(&name).is_ok() {
+        return crate::discord::respond(ctx, t!(locale, "team.already_exists", team = name)).await;
+    }
+
+    let team = Team::insert(name)?;
+
+    info!("Team added by {}: {:?}", ctx.author().name, team);
+
+    crate::discord::respond(ctx, t!(locale, "team.added", team = team.name())).await
+}
+
+/// Removes a team; its members stay
+#[poise::command(
+    slash_command,
+    rename = "remove",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn remove_team(
+    ctx: Context<'_>,
+    #[description = "Team"]
+    #[autocomplete = "autocomplete_team"]
+    team: Team,
+) -> Result<(), Error> {
+    team.delete()?;
+
+    info!("Team removed by {}: {:?}", ctx.author().name, team);
+
+    crate::discord::respond(
+        ctx,
+        t!(i18n::user_locale(ctx), "team.removed", team = team.name()),
+    )
+    .await
+}
+
+/// Adds a member to a team
+#[poise::command(
+    slash_command,
+    rename = "assign",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn assign_team(
+    ctx: Context<'_>,
+    #[description = "Team"]
+    #[autocomplete = "autocomplete_team"]
+    team: Team,
+    #[description = "Member of the organization"] member: Member,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    let key = if team.add_member(member.id())? {
+        info!("Member {} assigned to team {}", member.name(), team);
+
+        "team.assigned"
+    } else {
+        "team.already_assigned"
+    };
+
+    crate::discord::respond(
+        ctx,
+        t!(locale, key, member = member.name(), team = team.name()),
+    )
+    .await
+}
+
+/// Removes a member from a team
+#[poise::command(
+    slash_command,
+    rename = "unassign",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn unassign_team(
+    ctx: Context<'_>,
+    #[description = "Team"]
+    #[autocomplete = "autocomplete_team"]
+    team: Team,
+    #[description = "Member of the organization"] member: Member,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+
+    let key = if team.remove_member(member.id())? {
+        info!("Member {} unassigned from team {}", member.name(), team);
+
+        "team.unassigned"
+    } else {
+        "team.not_assigned"
+    };
+
+    crate::discord::respond(
+        ctx,
+        t!(locale, key, member = member.name(), team = team.name()),
+    )
+    .await
+}
@@ -0,0 +1,67 @@
+use std::fmt::Write;
+
+use tracing::info;
+
+use super::Context;
+use crate::{
+    error::Error,
+    i18n::{self, t},
+    reconcile::FixStatus,
+    wiki::groups,
+};
+
+/// Compare wiki group membership with the database and optionally fix it.
+#[poise::command(
+    slash_command,
+    rename = "groups",
+    required_permissions = "ADMINISTRATOR",
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub(crate) async fn groups(
+    ctx: Context<'_>,
+    #[description = "Fix the mismatches instead of only listing them"] apply: Option<bool>,
+) -> Result<(), Error> {
+    let locale = i18n::user_locale(ctx);
+    let apply = apply.unwrap_or(false);
+
+    let results = groups::reconcile(apply).await?;
+
+    info!(
+        "Wiki groups reconciled by {} (apply: {apply}): {} mismatches",
+        ctx.author().name,
+        results.len()
+    );
+
+    if results.is_empty() {
+        return crate::discord::respond(ctx, t!(locale, "wiki.groups_in_sync")).await;
+    }
+
+    let mut output = String::new();
+
+    for (mismatch, status) in &results {
+        match status.localized(locale) {
+            Some(status) => writeln!(&mut output, "- {} ({status})", mismatch.localized(locale))?,
+            None => writeln!(&mut output, "- {}", mismatch.localized(locale))?,
+        }
+    }
+
+    output.push('\n');
+
+    if apply {
+        let fixed = results
+            .iter()
+            .filter(|(_, status)| matches!(status, FixStatus::Fixed))
+            .count();
+
+        output.push_str(&t!(
+            locale,
+            "wiki.groups_applied",
+            fixed = fixed,
+            count = results.len()
+        ));
+    } else {
+        output.push_str(&t!(locale, "wiki.groups_dry_run", count = results.len()));
+    }
+
+    crate::discord::respond(ctx, output).await
+}
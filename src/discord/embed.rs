@@ -0,0 +1,281 @@
+//! Splitting long, structured content into Discord embeds.
+//!
+//! Content is described as [`EmbedSection`]s, which are paginated into
+//! embeds and messages that respect Discord's embed limits. The pagination is
+//! kept free of any Discord types so it can be unit tested; only
+//! [`EmbedPage::to_embed`] builds the actual embed.
+
+use poise::serenity_prelude::{Color, CreateEmbed};
+
+const TITLE_LIMIT: usize = 256;
+const FIELD_NAME_LIMIT: usize = 256;
+const FIELD_VALUE_LIMIT: usize = 1024;
+const FIELDS_PER_EMBED: usize = 25;
+const EMBEDS_PER_MESSAGE: usize = 10;
+/// Limit of all characters combined in every embed of a single message.
+const CHARACTERS_PER_MESSAGE: usize = 6000;
+
+/// Name used for fields that should not display a name.
+pub(crate) const BLANK_FIELD_NAME: &str = "\u{200b}";
+
+const EMBED_COLOR: Color = Color::from_rgb(56, 130, 209);
+
+/// A titled part of the content, e.g. the list of reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EmbedSection {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/// A single embed worth of content.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct EmbedPage {
+    pub title: Option<String>,
+    pub fields: Vec<(String, String)>,
+}
+
+impl EmbedSection {
+    pub(crate) fn new(title: impl Into<String>) -> Self {
+        EmbedSection {
+            title: title.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a field. Empty values are skipped, as Discord rejects them.
+    pub(crate) fn field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let value = value.into();
+
+        if !value.trim().is_empty() {
+            self.fields.push((name.into(), value));
+        }
+
+        self
+    }
+
+    /// Splits the section into pages. Only the first page carries the title;
+    /// values too long for a single field continue in following fields
+    /// without a name.
+    fn into_pages(self) -> Vec<EmbedPage> {
+        let mut pages = Vec::new();
+        let mut page = EmbedPage {
+            title: Some(truncate(&self.title, TITLE_LIMIT)),
+            fields: Vec::new(),
+        };
+
+        for (name, value) in self.fields {
+            let mut name = truncate(&name, FIELD_NAME_LIMIT);
+
+            for chunk in split_text(&value, FIELD_VALUE_LIMIT) {
+                let field_len = name.chars().count() + chunk.chars().count();
+
+                if page.fields.len() == FIELDS_PER_EMBED
+                    || page.len() + field_len > CHARACTERS_PER_MESSAGE
+                {
+                    pages.push(std::mem::take(&mut page));
+                }
+
+                page.fields.push((name, chunk));
+                name = BLANK_FIELD_NAME.to_string();
+            }
+        }
+
+        pages.push(page);
+        pages
+    }
+}
+
+impl EmbedPage {
+    /// Number of characters Discord counts towards the message limit.
+    fn len(&self) -> usize {
+        self.title.as_ref().map_or(0, |title| title.chars().count())
+            + self
+                .fields
+                .iter()
+                .map(|(name, value)| name.chars().count() + value.chars().count())
+                .sum::<usize>()
+    }
+
+    pub(crate) fn to_embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new().color(EMBED_COLOR);
+
+        if let Some(title) = &self.title {
+            embed = embed.title(title);
+        }
+
+        embed.fields(
+            self.fields
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone(), false)),
+        )
+    }
+}
+
+/// Paginates sections into messages, each holding as many embeds as Discord
+/// allows. Every section starts a new embed, but may share a message with
+/// the previous section.
+pub(crate) fn paginate(sections: Vec<EmbedSection>) -> Vec<Vec<EmbedPage>> {
+    let mut messages = Vec::new();
+    let mut message: Vec<EmbedPage> = Vec::new();
+    let mut message_len = 0;
+
+    for page in sections.into_iter().flat_map(EmbedSection::into_pages) {
+        let page_len = page.len();
+
+        if !message.is_empty()
+            && (message.len() == EMBEDS_PER_MESSAGE
+                || message_len + page_len > CHARACTERS_PER_MESSAGE)
+        {
+            messages.push(std::mem::take(&mut message));
+            message_len = 0;
+        }
+
+        message_len += page_len;
+        message.push(page);
+    }
+
+    if !message.is_empty() {
+        messages.push(message);
+    }
+
+    messages
+}
+
+/// Splits text into chunks of at most `limit` characters, preferring to break
+/// at line breaks, then at whitespace.
+fn split_text(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+
+    while rest.chars().count() > limit {
+        let (hard_end, next_char) = rest
+            .char_indices()
+            .nth(limit)
+            .expect("text is longer than the limit");
+        // include the first character past the limit, so a break right at the
+        // limit is found
+        let head = &rest[..hard_end + next_char.len_utf8()];
+
+        let end = head
+            .rfind('\n')
+            .or_else(|| head.rfind(char::is_whitespace))
+            .filter(|&end| end > 0)
+            .unwrap_or(hard_end);
+
+        chunks.push(rest[..end].trim_end().to_string());
+        rest = rest[end..].trim_start();
+    }
+
+    if !rest.is_empty() {
+        chunks.push(rest.to_string());
+    }
+
+    chunks
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+
+    let mut truncated = text.chars().take(limit - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        paginate, split_text, EmbedSection, BLANK_FIELD_NAME, CHARACTERS_PER_MESSAGE,
+        FIELDS_PER_EMBED, FIELD_VALUE_LIMIT,
+    };
+
+    #[test]
+    fn short_text_is_not_split() {
+        assert_eq!(split_text("short text", 100), vec!["short text"]);
+    }
+
+    #[test]
+    fn text_is_split_at_whitespace() {
+        let chunks = split_text("aaaa bbbb cccc", 9);
+
+        assert_eq!(chunks, vec!["aaaa bbbb", "cccc"]);
+    }
+
+    #[test]
+    fn text_without_whitespace_is_split_hard() {
+        let chunks = split_text("abcdefghij", 4);
+
+        assert_eq!(chunks, vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn small_sections_share_a_message() {
+        let sections = vec![
+            EmbedSection::new("First").field("a", "1"),
+            EmbedSection::new("Second").field("b", "2"),
+        ];
+
+        let messages = paginate(sections);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].len(), 2);
+        assert_eq!(messages[0][1].title.as_deref(), Some("Second"));
+    }
+
+    #[test]
+    fn empty_fields_are_skipped() {
+        let section = EmbedSection::new("Title").field("empty", "  ");
+
+        assert!(section.fields.is_empty());
+    }
+
+    #[test]
+    fn long_values_continue_in_unnamed_fields() {
+        let value = "word ".repeat(FIELD_VALUE_LIMIT / 2);
+        let messages = paginate(vec![EmbedSection::new("Title").field("Member", value)]);
+
+        let fields = &messages[0][0].fields;
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[0].0, "Member");
+        assert_eq!(fields[1].0, BLANK_FIELD_NAME);
+        assert!(fields
+            .iter()
+            .all(|(_, value)| value.chars().count() <= FIELD_VALUE_LIMIT));
+    }
+
+    #[test]
+    fn many_fields_are_split_into_pages() {
+        let mut section = EmbedSection::new("Title");
+        for i in 0..FIELDS_PER_EMBED + 1 {
+            section = section.field(i.to_string(), "value");
+        }
+
+        let messages = paginate(vec![section]);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].len(), 2);
+        assert_eq!(messages[0][0].fields.len(), FIELDS_PER_EMBED);
+        assert_eq!(messages[0][1].title, None);
+    }
+
+    #[test]
+    fn messages_stay_within_character_limit() {
+        let mut section = EmbedSection::new("Title");
+        for i in 0..20 {
+            section = section.field(i.to_string(), "x".repeat(FIELD_VALUE_LIMIT));
+        }
+
+        let messages = paginate(vec![section]);
+
+        assert!(messages.len() > 1);
+        for message in messages {
+            let len: usize = message
+                .iter()
+                .flat_map(|page| page.fields.iter())
+                .map(|(name, value)| name.chars().count() + value.chars().count())
+                .sum();
+            assert!(len <= CHARACTERS_PER_MESSAGE);
+        }
+    }
+}
@@ -16,13 +16,19 @@ use tracing::{error, info, log::trace, warn};
 
 use crate::{
     database::models::member::Member,
-    discord::commands::{activity, meeting, member, report, silent_mode, summary},
+    discord::commands::{
+        activity, field, leave, me, meeting, member, report, role, silent_mode, summary, team, wiki,
+    },
     error::Error,
+    i18n::{self, t},
     meeting::MeetingStatus,
     SETTINGS,
 };
 
 mod commands;
+pub(crate) mod embed;
+mod permissions;
+pub(crate) mod roles;
 
 #[derive(Debug)]
 pub struct Data {
@@ -42,6 +48,52 @@ async fn event_handler(
         }
         FullEvent::VoiceStateUpdate { old, new } => {
             event_voice_state_update(framework, old, new).await;
+
+            if let Err(why) = crate::activity::sources::voice_state_updated(new) {
+                error!("Failed to record voice activity: {}", why);
+            }
+        }
+        FullEvent::Message { new_message } => {
+            if let Err(why) = crate::activity::sources::message_sent(new_message) {
+                error!("Failed to record message activity: {}", why);
+            }
+        }
+        FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Component(interaction),
+        } => {
+            if let Err(why) = crate::onboarding::handle_interaction(ctx, interaction).await {
+                error!("Failed to handle onboarding interaction: {}", why);
+            }
+
+            if let Err(why) = crate::member_events::handle_interaction(ctx, interaction).await {
+                error!("Failed to handle member event interaction: {}", why);
+            }
+        }
+        FullEvent::GuildMemberAddition { new_member }
+            if new_member.guild_id == SETTINGS.discord.server_id =>
+        {
+            if let Err(why) = crate::member_events::member_joined(&ctx.http, new_member).await {
+                error!("Failed to handle member joining: {}", why);
+            }
+        }
+        FullEvent::GuildMemberRemoval { guild_id, user, .. }
+            if *guild_id == SETTINGS.discord.server_id =>
+        {
+            if let Err(why) = crate::member_events::member_left(&ctx.http, user).await {
+                error!("Failed to handle member leaving: {}", why);
+            }
+        }
+        FullEvent::GuildMemberUpdate {
+            old_if_available,
+            event,
+            ..
+        } if event.guild_id == SETTINGS.discord.server_id => {
+            if let Err(why) =
+                crate::member_events::member_updated(&ctx.http, old_if_available.as_ref(), event)
+                    .await
+            {
+                error!("Failed to handle member update: {}", why);
+            }
         }
         _ => {}
     }
@@ -63,11 +115,9 @@ async fn event_voice_state_update(
     {
         match Member::find_by_discord_id(new.user_id.get().to_string()) {
             Ok(mut member) => {
-                let output = match meeting_status.add_member(&mut member) {
-                    Ok(msg) => msg,
-                    Err(e) => format!("{} could not join the meeting: {}", member.name(), e),
-                };
-                info!("{}", output);
+                if let Err(e) = meeting_status.add_member(&mut member) {
+                    info!("{} could not join the meeting: {}", member.name(), e);
+                }
             }
             Err(e) => warn!(
                 "User {} is not member of the organization: {:?}",
@@ -89,13 +139,9 @@ async fn event_ready(ctx: &serenity::Context, framework: poise::FrameworkContext
         for member in channel.guild().unwrap().members(ctx).unwrap() {
             match Member::find_by_discord_id(member.user.id.get().to_string()) {
                 Ok(mut member) => {
-                    let output = match meeting_status.add_member(&mut member) {
-                        Ok(msg) => msg,
-                        Err(e) => {
-                            format!("{} could not join the meeting: {}", member.name(), e)
-                        }
-                    };
-                    info!("{}", output);
+                    if let Err(e) = meeting_status.add_member(&mut member) {
+                        info!("{} could not join the meeting: {}", member.name(), e);
+                    }
                 }
                 Err(e) => warn!(
                     "User {} is not member of the organization: {:?}",
@@ -119,10 +165,16 @@ pub async fn start_bot() {
         commands: vec![
             activity(),
             member(),
+            me(),
             report(),
             summary(),
             meeting(),
             silent_mode(),
+            role(),
+            team(),
+            field(),
+            wiki(),
+            leave(),
         ],
         event_handler: |ctx, event, framework, _data| {
             Box::pin(event_handler(ctx, event, framework))
@@ -159,6 +211,12 @@ pub async fn start_bot() {
             )
             .await?;
             let meeting_status = crate::meeting::create_meeting_job(ctx).await.unwrap();
+            crate::wiki::groups::spawn_reconciliation_job();
+            crate::onboarding::spawn_reminder_job(ctx.http.clone());
+            roles::spawn_audit_job(ctx.http.clone());
+            crate::promotion::spawn_promotion_job(ctx.http.clone());
+            crate::inactivity::spawn_inactivity_job(ctx.http.clone());
+            crate::leave::spawn_leave_job(ctx.http.clone());
             Ok(Data { meeting_status })
         })
     });
@@ -180,26 +238,30 @@ async fn on_error(err: poise::FrameworkError<'_, Data, Error>) {
     let ctx = err.ctx();
     let err = std::error::Error::source(&err).and_then(|e| e.downcast_ref::<Error>());
 
-    let description = if let Some(err) = err {
-        format!("{}\n{}", err, message)
-    } else {
-        message.clone()
-    };
-
     error!({message, ?err}, "Error occurred");
 
     if let Some(ctx) = ctx {
+        let locale = i18n::user_locale(ctx);
+
+        // errors of the commands are shown in the user's locale, those of
+        // poise itself, e.g. invalid arguments, are only in English
+        let description = match err {
+            Some(err) => err.localized(locale),
+            None => message,
+        };
+
         let result = ctx
             .send(
                 CreateReply::default().embed(
                     CreateEmbed::new()
                         .color(Color::from_rgb(209, 53, 56))
                         .timestamp(chrono::Utc::now())
-                        .title("Error occurred")
+                        .title(t!(locale, "error.title"))
                         .description(description)
-                        .footer(CreateEmbedFooter::new(format!(
-                            "Command: {}",
-                            ctx.command().qualified_name
+                        .footer(CreateEmbedFooter::new(t!(
+                            locale,
+                            "error.footer",
+                            command = ctx.command().qualified_name
                         ))),
                 ),
             )
@@ -245,6 +307,23 @@ async fn respond(ctx: Context<'_>, content: String) -> Result<(), Error> {
     Ok(())
 }
 
+/// Replies with the given embed messages, e.g. produced by
+/// [`embed::paginate`].
+async fn respond_embeds(
+    ctx: Context<'_>,
+    messages: Vec<Vec<embed::EmbedPage>>,
+) -> Result<(), Error> {
+    for pages in messages {
+        let reply = pages.iter().fold(CreateReply::default(), |reply, page| {
+            reply.embed(page.to_embed())
+        });
+
+        poise::reply::send_reply(ctx, reply).await?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::discord::split_message;
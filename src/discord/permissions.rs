@@ -0,0 +1,125 @@
+//! Permission checks for commands that act on other members' data.
+
+use uuid::Uuid;
+
+use crate::{
+    database::models::{member::Member, role::Permission},
+    discord::Context,
+    error::Error,
+    SETTINGS,
+};
+
+/// Returns `true` if the command's author is a member whose role grants the
+/// permission.
+pub(crate) fn has_permission(ctx: Context<'_>, permission: Permission) -> bool {
+    Member::find_by_discord_id(ctx.author().id.to_string())
+        .and_then(|member| member.role())
+        .is_ok_and(|role| role.has_permission(permission))
+}
+
+/// Returns `true` if the command's author is an administrator of the server.
+pub(crate) async fn is_admin(ctx: Context<'_>) -> bool {
+    ctx.author_member()
+        .await
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.administrator())
+}
+
+/// Returns `true` if the command's author holds the configured lead role.
+/// The lead role is only checked inside the guild.
+async fn has_lead_role(ctx: Context<'_>) -> bool {
+    let Some(lead_role) = SETTINGS.discord.lead_role else {
+        return false;
+    };
+
+    match ctx.author_member().await {
+        Some(member) => member.roles.contains(&lead_role),
+        None => false,
+    }
+}
+
+/// Returns `true` if the command's author holds the configured lead role, or
+/// a member role granting [`Permission::ManageReports`].
+pub(crate) async fn is_lead(ctx: Context<'_>) -> bool {
+    has_permission(ctx, Permission::ManageReports) || has_lead_role(ctx).await
+}
+
+/// Returns `true` if the command's author may assign mentors and promote
+/// apprentices: holders of the lead role, administrators, and members whose
+/// role grants [`Permission::ManagePromotions`].
+pub(crate) async fn can_manage_promotions(ctx: Context<'_>) -> bool {
+    has_permission(ctx, Permission::ManagePromotions)
+        || has_lead_role(ctx).await
+        || is_admin(ctx).await
+}
+
+/// Decides whether a caller may act on a report.
+///
+/// `caller` is the caller's member id (if they are a member at all), `owner`
+/// the id of the member the report belongs to, and `locked` whether the
+/// report is attached to an already published summary. Leads may act on
+/// every report.
+pub(crate) fn check_report_access(
+    caller: Option<Uuid>,
+    owner: Uuid,
+    locked: bool,
+    is_lead: bool,
+) -> Result<(), Error> {
+    if is_lead {
+        return Ok(());
+    }
+
+    if caller != Some(owner) {
+        return Err(Error::NotReportOwner);
+    }
+
+    if locked {
+        return Err(Error::ReportLocked);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::check_report_access;
+    use crate::error::Error;
+
+    #[test]
+    fn owner_can_act_on_unlocked_report() {
+        let owner = Uuid::new_v4();
+
+        assert!(check_report_access(Some(owner), owner, false, false).is_ok());
+    }
+
+    #[test]
+    fn other_member_is_rejected() {
+        let err =
+            check_report_access(Some(Uuid::new_v4()), Uuid::new_v4(), false, false).unwrap_err();
+
+        assert!(matches!(err, Error::NotReportOwner));
+    }
+
+    #[test]
+    fn non_member_is_rejected() {
+        let err = check_report_access(None, Uuid::new_v4(), false, false).unwrap_err();
+
+        assert!(matches!(err, Error::NotReportOwner));
+    }
+
+    #[test]
+    fn owner_cannot_act_on_locked_report() {
+        let owner = Uuid::new_v4();
+
+        let err = check_report_access(Some(owner), owner, true, false).unwrap_err();
+
+        assert!(matches!(err, Error::ReportLocked));
+    }
+
+    #[test]
+    fn lead_can_act_on_any_report() {
+        assert!(check_report_access(None, Uuid::new_v4(), true, true).is_ok());
+    }
+}
@@ -0,0 +1,392 @@
+//! Auditing Discord roles against the member database.
+//!
+//! Every guild member matching a [`Member`] should hold exactly the Discord
+//! role of the member's role, as given by [`Role::discord_role`], and no other
+//! managed role (a Discord role of any role in the roles table). The scheduled
+//! audit only logs the mismatches; missing and extra roles are changed once an
+//! admin confirms them with `/member audit`, while untracked role holders and
+//! members missing from the server are left for admins to handle.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use poise::serenity_prelude::{Http, RoleId, UserId};
+use tracing::warn;
+
+use crate::{
+    database::models::{member::Member, role::Role},
+    error::Error,
+    i18n::t,
+    jobs,
+    reconcile::FixStatus,
+    SETTINGS,
+};
+
+/// Discord role a member's account should hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExpectedRole {
+    pub member: String,
+    pub user_id: UserId,
+    /// `None` for roles without a Discord role, such as ex-members.
+    pub role_id: Option<RoleId>,
+    /// Whether the member was seen leaving the server.
+    pub left_server: bool,
+}
+
+/// A member of the guild with their roles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GuildUser {
+    pub user_id: UserId,
+    pub name: String,
+    pub roles: Vec<RoleId>,
+    pub bot: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RoleMismatch {
+    /// The member does not hold the Discord role of their role.
+    Missing {
+        member: String,
+        user_id: UserId,
+        role_id: RoleId,
+    },
+    /// The member holds a managed role that is not the one of their role.
+    Extra {
+        member: String,
+        user_id: UserId,
+        role_id: RoleId,
+    },
+    /// The guild member holds a managed role but is not in the database.
+    /// These are only reported, as they should be added with `/member add`.
+    Untracked {
+        name: String,
+        user_id: UserId,
+        role_id: RoleId,
+    },
+    /// The account of a member with a Discord role, who was not seen leaving,
+    /// is not in the guild. These are only reported.
+    NotInGuild { member: String, user_id: UserId },
+}
+
+impl RoleMismatch {
+    pub(crate) fn localized(&self, locale: &str) -> String {
+        match self {
+            RoleMismatch::Missing {
+                member, role_id, ..
+            } => t!(
+                locale,
+                "member.audit_missing",
+                member = member,
+                role = format!("<@&{role_id}>")
+            ),
+            RoleMismatch::Extra {
+                member, role_id, ..
+            } => t!(
+                locale,
+                "member.audit_extra",
+                member = member,
+                role = format!("<@&{role_id}>")
+            ),
+            RoleMismatch::Untracked {
+                name,
+                user_id,
+                role_id,
+            } => t!(
+                locale,
+                "member.audit_untracked",
+                user = format!("{name} (<@{user_id}>)"),
+                role = format!("<@&{role_id}>")
+            ),
+            RoleMismatch::NotInGuild { member, user_id } => t!(
+                locale,
+                "member.audit_not_in_guild",
+                member = member,
+                user = format!("<@{user_id}>")
+            ),
+        }
+    }
+
+    pub(crate) fn is_fixable(&self) -> bool {
+        matches!(
+            self,
+            RoleMismatch::Missing { .. } | RoleMismatch::Extra { .. }
+        )
+    }
+
+    async fn fix(&self, http: &Http) -> Result<(), Error> {
+        let server_id = SETTINGS.discord.server_id;
+
+        match self {
+            RoleMismatch::Missing {
+                user_id, role_id, ..
+            } => Ok(http
+                .add_member_role(server_id, *user_id, *role_id, None)
+                .await?),
+            RoleMismatch::Extra {
+                user_id, role_id, ..
+            } => Ok(http
+                .remove_member_role(server_id, *user_id, *role_id, None)
+                .await?),
+            RoleMismatch::Untracked { .. } | RoleMismatch::NotInGuild { .. } => Ok(()),
+        }
+    }
+}
+
+/// Compares the guild members' roles with the Discord roles members should
+/// hold. Only `managed` roles are compared, and bots are skipped.
+pub(crate) fn find_mismatches(
+    expected: &[ExpectedRole],
+    users: &[GuildUser],
+    managed: &BTreeSet<RoleId>,
+) -> Vec<RoleMismatch> {
+    let mut mismatches = Vec::new();
+
+    for member in expected {
+        let Some(user) = users.iter().find(|user| user.user_id == member.user_id) else {
+            // ex-members and members who left are expected to be gone
+            if member.role_id.is_some() && !member.left_server {
+                mismatches.push(RoleMismatch::NotInGuild {
+                    member: member.member.clone(),
+                    user_id: member.user_id,
+                });
+            }
+            continue;
+        };
+
+        if let Some(role_id) = member.role_id {
+            if !user.roles.contains(&role_id) {
+                mismatches.push(RoleMismatch::Missing {
+                    member: member.member.clone(),
+                    user_id: member.user_id,
+                    role_id,
+                });
+            }
+        }
+
+        for &role_id in user.roles.iter().filter(|role| managed.contains(role)) {
+            if Some(role_id) != member.role_id {
+                mismatches.push(RoleMismatch::Extra {
+                    member: member.member.clone(),
+                    user_id: member.user_id,
+                    role_id,
+                });
+            }
+        }
+    }
+
+    for user in users.iter().filter(|user| !user.bot) {
+        if expected.iter().any(|member| member.user_id == user.user_id) {
+            continue;
+        }
+
+        for &role_id in user.roles.iter().filter(|role| managed.contains(role)) {
+            mismatches.push(RoleMismatch::Untracked {
+                name: user.name.clone(),
+                user_id: user.user_id,
+                role_id,
+            });
+        }
+    }
+
+    mismatches
+}
+
+async fn guild_users(http: &Http) -> Result<Vec<GuildUser>, Error> {
+    let mut users = Vec::new();
+    let mut after = None;
+
+    loop {
+        let page = SETTINGS
+            .discord
+            .server_id
+            .members(http, Some(1000), after)
+            .await?;
+
+        let Some(last) = page.last() else {
+            break;
+        };
+        after = Some(last.user.id);
+        let full = page.len() == 1000;
+
+        users.extend(page.into_iter().map(|member| GuildUser {
+            user_id: member.user.id,
+            name: member.display_name().to_string(),
+            roles: member.roles,
+            bot: member.user.bot,
+        }));
+
+        if !full {
+            break;
+        }
+    }
+
+    Ok(users)
+}
+
+/// Lists the mismatches between the guild members' roles and the database.
+pub(crate) async fn audit(http: &Http) -> Result<Vec<RoleMismatch>, Error> {
+    let mut expected = Vec::new();
+    for member in Member::list_with_discord_account()? {
+        if let Some(discord_id) = member.discord_id() {
+            expected.push(ExpectedRole {
+                member: member.name(),
+                user_id: UserId::new(discord_id.parse()?),
+                role_id: member.role()?.discord_role(),
+                left_server: member.left_server_at().is_some(),
+            });
+        }
+    }
+
+    let managed = Role::all()?
+        .iter()
+        .filter_map(Role::discord_role)
+        .collect::<BTreeSet<_>>();
+
+    Ok(find_mismatches(
+        &expected,
+        &guild_users(http).await?,
+        &managed,
+    ))
+}
+
+/// Fixes every fixable mismatch, continuing past failures.
+pub(crate) async fn fix(
+    http: &Http,
+    mismatches: Vec<RoleMismatch>,
+) -> Vec<(RoleMismatch, FixStatus)> {
+    let mut results = Vec::new();
+
+    for mismatch in mismatches {
+        let status = if mismatch.is_fixable() {
+            match mismatch.fix(http).await {
+                Ok(()) => FixStatus::Fixed,
+                Err(why) => FixStatus::Failed(why),
+            }
+        } else {
+            FixStatus::NotApplied
+        };
+
+        results.push((mismatch, status));
+    }
+
+    results
+}
+
+/// Periodically audits the Discord roles and logs the mismatches. They are
+/// never fixed without an admin confirming them with `/member audit`.
+pub(crate) fn spawn_audit_job(http: Arc<Http>) {
+    jobs::spawn_periodic(
+        "Discord role audit",
+        SETTINGS.discord.audit_interval_hours,
+        false,
+        move || {
+            let http = http.clone();
+            async move {
+                for mismatch in audit(&http).await? {
+                    warn!("Discord role mismatch: {:?}", mismatch);
+                }
+
+                Ok(())
+            }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use poise::serenity_prelude::{RoleId, UserId};
+
+    use super::{find_mismatches, ExpectedRole, GuildUser, RoleMismatch};
+
+    const MEMBERS: RoleId = RoleId::new(1);
+    const APPRENTICES: RoleId = RoleId::new(2);
+    const OTHER: RoleId = RoleId::new(3);
+
+    fn managed() -> BTreeSet<RoleId> {
+        BTreeSet::from([MEMBERS, APPRENTICES])
+    }
+
+    fn expected(member: &str, user_id: u64, role_id: Option<RoleId>) -> ExpectedRole {
+        ExpectedRole {
+            member: member.to_string(),
+            user_id: UserId::new(user_id),
+            role_id,
+            left_server: false,
+        }
+    }
+
+    fn user(name: &str, user_id: u64, roles: &[RoleId]) -> GuildUser {
+        GuildUser {
+            user_id: UserId::new(user_id),
+            name: name.to_string(),
+            roles: roles.to_vec(),
+            bot: false,
+        }
+    }
+
+    #[test]
+    fn matching_roles_have_no_mismatches() {
+        let expected = vec![
+            expected("Alice", 10, Some(MEMBERS)),
+            expected("Bob", 11, None),
+        ];
+        let users = vec![user("Alice", 10, &[MEMBERS, OTHER]), user("Bob", 11, &[])];
+
+        assert!(find_mismatches(&expected, &users, &managed()).is_empty());
+    }
+
+    #[test]
+    fn member_with_wrong_role_is_missing_and_extra() {
+        let expected = vec![expected("Alice", 10, Some(MEMBERS))];
+        let users = vec![user("Alice", 10, &[APPRENTICES])];
+
+        assert_eq!(
+            find_mismatches(&expected, &users, &managed()),
+            vec![
+                RoleMismatch::Missing {
+                    member: "Alice".to_string(),
+                    user_id: UserId::new(10),
+                    role_id: MEMBERS,
+                },
+                RoleMismatch::Extra {
+                    member: "Alice".to_string(),
+                    user_id: UserId::new(10),
+                    role_id: APPRENTICES,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn untracked_and_absent_accounts_are_reported() {
+        let expected = vec![expected("Alice", 10, Some(MEMBERS))];
+        let mut bot = user("Bot", 12, &[MEMBERS]);
+        bot.bot = true;
+        let users = vec![user("Eve", 11, &[MEMBERS]), bot];
+
+        assert_eq!(
+            find_mismatches(&expected, &users, &managed()),
+            vec![
+                RoleMismatch::NotInGuild {
+                    member: "Alice".to_string(),
+                    user_id: UserId::new(10),
+                },
+                RoleMismatch::Untracked {
+                    name: "Eve".to_string(),
+                    user_id: UserId::new(11),
+                    role_id: MEMBERS,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn departed_accounts_are_not_reported() {
+        let mut left = expected("Bob", 11, Some(MEMBERS));
+        left.left_server = true;
+        let expected = vec![expected("Alice", 10, None), left];
+
+        assert!(find_mismatches(&expected, &[], &managed()).is_empty());
+    }
+}
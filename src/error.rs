@@ -1,6 +1,8 @@
 use poise::serenity_prelude as serenity;
 use thiserror::Error;
 
+use crate::i18n::t;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Error with serenity: {0}")]
@@ -16,6 +18,21 @@ pub enum Error {
         #[from]
         source: crate::wiki::WikiError,
     },
+    #[error("Error from Trello: {source}")]
+    TrelloError {
+        #[from]
+        source: crate::trello::TrelloError,
+    },
+    #[error("Error reading file: {source}")]
+    IoError {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("Error parsing JSON: {source}")]
+    JsonError {
+        #[from]
+        source: serde_json::Error,
+    },
     #[error("Error parsing schedule: {source}")]
     CronError {
         #[from]
@@ -46,8 +63,6 @@ pub enum Error {
         user_id: uuid::Uuid,
         meeting_id: uuid::Uuid,
     },
-    #[error("Summary too long to fit in messages")]
-    SummaryTooLong,
     #[error("No messages to send summary to")]
     NoSummaryMessages,
     #[error("No meeting is ongoing")]
@@ -58,10 +73,159 @@ pub enum Error {
     GuildChannelNotFound,
     #[error("Note cannot be empty")]
     EmptyNote,
+    #[error("You can only manage your own reports")]
+    NotReportOwner,
+    #[error("Report belongs to an already published summary")]
+    ReportLocked,
+    #[error("Member has no Discord account")]
+    NoDiscordAccount,
+    #[error("Member has no wiki account")]
+    NoWikiAccount,
+    #[error("A wiki account with the email {0} already exists, ask an administrator to link it")]
+    WikiAccountExists(String),
+    #[error("Wiki account {wiki_id} is already linked to {member}")]
+    WikiAccountTaken { wiki_id: i64, member: String },
+    #[error("Wiki user {0} not found")]
+    WikiUserNotFound(i64),
+    #[error("Role {0} not found")]
+    RoleNotFound(String),
+    #[error("Role {0} is built in and cannot be removed")]
+    BuiltInRole(String),
+    #[error("Role {0} is still held by members or ex-members")]
+    RoleInUse(String),
+    #[error("Profile field {0} not found")]
+    ProfileFieldNotFound(String),
+    #[error("{value} is not a valid {kind}")]
+    InvalidFieldValue { value: String, kind: String },
+    #[error("Team {0} not found")]
+    TeamNotFound(String),
+    #[error("Only leads and administrators can do this")]
+    NotLead,
+    #[error("{0} is not an apprentice")]
+    NotApprentice(String),
+    #[error("{0} is already an ex-member")]
+    AlreadyOffboarded(String),
+    #[error("The member of this archive was deleted from the database")]
+    ArchivedMemberDeleted,
+    #[error("{0} is not a valid date, use YYYY-MM-DD")]
+    InvalidDate(String),
+    #[error("A leave cannot end before it starts")]
+    InvalidLeavePeriod,
+    #[error("No leave of {member} starts on {start}")]
+    LeaveNotFound {
+        member: String,
+        start: chrono::NaiveDate,
+    },
+    #[error("You can only manage your own leaves")]
+    NotLeaveOwner,
+    #[error("The leave overlaps the leave from {start} to {end}")]
+    LeaveOverlaps {
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+    },
+    #[error("Only administrators can add leaves starting in the past")]
+    LeaveInPast,
+    #[error("Only administrators can add leaves of more than {0} days in a row")]
+    LeaveTooLong(i64),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
+impl Error {
+    /// Returns the message shown to users in their locale. Errors they cannot
+    /// act on are only prefixed, their details come from the libraries. Every
+    /// variant is listed, so a new one has to be given a message.
+    pub(crate) fn localized(&self, locale: &str) -> String {
+        match self {
+            Error::UserAlreadyInMeeting {
+                user_id,
+                meeting_id,
+            } => t!(
+                locale,
+                "error.user_already_in_meeting",
+                user = user_id,
+                meeting = meeting_id
+            ),
+            Error::UserNotInMeeting {
+                user_id,
+                meeting_id,
+            } => t!(
+                locale,
+                "error.user_not_in_meeting",
+                user = user_id,
+                meeting = meeting_id
+            ),
+            Error::NoSummaryMessages => t!(locale, "error.no_summary_messages"),
+            Error::NoMeetingOngoing => t!(locale, "error.no_meeting_ongoing"),
+            Error::NoMeetingPlanned => t!(locale, "error.no_meeting_planned"),
+            Error::GuildChannelNotFound => t!(locale, "error.guild_channel_not_found"),
+            Error::EmptyNote => t!(locale, "error.empty_note"),
+            Error::NotReportOwner => t!(locale, "error.not_report_owner"),
+            Error::ReportLocked => t!(locale, "error.report_locked"),
+            Error::NoDiscordAccount => t!(locale, "error.no_discord_account"),
+            Error::NoWikiAccount => t!(locale, "error.no_wiki_account"),
+            Error::WikiAccountExists(email) => {
+                t!(locale, "error.wiki_account_exists", email = email)
+            }
+            Error::WikiAccountTaken { wiki_id, member } => t!(
+                locale,
+                "error.wiki_account_taken",
+                wiki_id = wiki_id,
+                member = member
+            ),
+            Error::WikiUserNotFound(wiki_id) => {
+                t!(locale, "error.wiki_user_not_found", wiki_id = wiki_id)
+            }
+            Error::RoleNotFound(role) => t!(locale, "error.role_not_found", role = role),
+            Error::BuiltInRole(role) => t!(locale, "error.built_in_role", role = role),
+            Error::RoleInUse(role) => t!(locale, "error.role_in_use", role = role),
+            Error::ProfileFieldNotFound(field) => {
+                t!(locale, "error.profile_field_not_found", field = field)
+            }
+            Error::InvalidFieldValue { value, kind } => t!(
+                locale,
+                "error.invalid_field_value",
+                value = value,
+                kind = kind
+            ),
+            Error::TeamNotFound(team) => t!(locale, "error.team_not_found", team = team),
+            Error::NotLead => t!(locale, "error.not_lead"),
+            Error::NotApprentice(member) => t!(locale, "error.not_apprentice", member = member),
+            Error::AlreadyOffboarded(member) => {
+                t!(locale, "error.already_offboarded", member = member)
+            }
+            Error::ArchivedMemberDeleted => t!(locale, "error.archived_member_deleted"),
+            Error::InvalidDate(date) => t!(locale, "error.invalid_date", date = date),
+            Error::InvalidLeavePeriod => t!(locale, "error.invalid_leave_period"),
+            Error::LeaveNotFound { member, start } => t!(
+                locale,
+                "error.leave_not_found",
+                member = member,
+                start = start
+            ),
+            Error::NotLeaveOwner => t!(locale, "error.not_leave_owner"),
+            Error::LeaveOverlaps { start, end } => {
+                t!(locale, "error.leave_overlaps", start = start, end = end)
+            }
+            Error::LeaveInPast => t!(locale, "error.leave_in_past"),
+            Error::LeaveTooLong(days) => t!(locale, "error.leave_too_long", days = days),
+            Error::SerenityError(_)
+            | Error::ConfigError(_)
+            | Error::FmtError(_)
+            | Error::ReqwestError(_)
+            | Error::WikiError { .. }
+            | Error::TrelloError { .. }
+            | Error::IoError { .. }
+            | Error::JsonError { .. }
+            | Error::CronError { .. }
+            | Error::ParseIntError { .. }
+            | Error::DieselError { .. }
+            | Error::R2d2Error { .. }
+            | Error::Other(_) => t!(locale, "error.internal", error = self),
+        }
+    }
+}
+
 // `serenity::Error` is ~136 bytes, large enough that boxing it keeps `Error`
 // under the `clippy::result_large_err` threshold; a manual `From` preserves the
 // `?` ergonomics we'd otherwise get from `#[from]`.
@@ -70,3 +234,39 @@ impl From<serenity::Error> for Error {
         Error::SerenityError(Box::new(err))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    #[test]
+    fn english_messages_match_display() {
+        let errors = [
+            Error::NoMeetingOngoing,
+            Error::RoleNotFound("Lead".to_string()),
+            Error::InvalidFieldValue {
+                value: "abc".to_string(),
+                kind: "number".to_string(),
+            },
+            Error::LeaveNotFound {
+                member: "Alice".to_string(),
+                start: chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap(),
+            },
+            Error::LeaveTooLong(60),
+        ];
+
+        for error in errors {
+            assert_eq!(error.localized("en"), error.to_string());
+        }
+    }
+
+    #[test]
+    fn internal_errors_keep_their_details() {
+        let error = Error::from(anyhow::anyhow!("connection refused"));
+
+        assert_eq!(
+            error.localized("en"),
+            "Something went wrong: connection refused"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Translations of user-facing text.
+//!
+//! Texts live in per-language catalogs in `locales/`, embedded into the
+//! binary. Each text is looked up by its key (`section.name`) and may contain
+//! `{placeholders}` filled in with the [`t!`] macro.
+//!
+//! Messages addressed to the whole guild (e.g. summaries) use the guild
+//! locale from the `locale` config option. Replies to commands use the
+//! author's Discord locale when it is supported, and the guild locale
+//! otherwise.
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use tracing::warn;
+
+use crate::{discord::Context, SETTINGS};
+
+/// Locale used when a text is missing from the requested catalog.
+pub const FALLBACK_LOCALE: &str = "en";
+
+const CATALOG_FILES: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.toml")),
+    ("pl", include_str!("../locales/pl.toml")),
+];
+
+lazy_static! {
+    static ref CATALOGS: HashMap<&'static str, HashMap<String, String>> = CATALOG_FILES
+        .iter()
+        .map(|(locale, file)| (*locale, parse_catalog(locale, file)))
+        .collect();
+}
+
+/// Looks up a translated text and fills in its placeholders.
+///
+/// ```ignore
+/// t!(locale, "member.added", member = member)
+/// ```
+macro_rules! t {
+    ($locale:expr, $key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::translate(
+            $locale,
+            $key,
+            &[$((stringify!($name), $value.to_string())),*],
+        )
+    };
+}
+
+pub(crate) use t;
+
+fn parse_catalog(locale: &str, file: &str) -> HashMap<String, String> {
+    let table = file
+        .parse::<toml::Table>()
+        .unwrap_or_else(|e| panic!("Invalid catalog for locale {locale}: {e}"));
+
+    let mut catalog = HashMap::new();
+    flatten(&mut catalog, None, table);
+    catalog
+}
+
+/// Flattens nested tables into `section.name` keys.
+fn flatten(catalog: &mut HashMap<String, String>, prefix: Option<&str>, table: toml::Table) {
+    for (name, value) in table {
+        let key = match prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name,
+        };
+
+        match value {
+            toml::Value::String(text) => {
+                catalog.insert(key, text);
+            }
+            toml::Value::Table(table) => flatten(catalog, Some(&key), table),
+            other => panic!("Catalog value for {key} must be a string, got {other}"),
+        }
+    }
+}
+
+/// Returns the supported locale matching a Discord locale such as `en-US` or
+/// `pl`, if there is one.
+pub(crate) fn resolve(locale: &str) -> Option<&'static str> {
+    let language = locale.split(['-', '_']).next().unwrap_or(locale);
+
+    CATALOG_FILES
+        .iter()
+        .map(|(supported, _)| *supported)
+        .find(|supported| supported.eq_ignore_ascii_case(language))
+}
+
+/// Locale of messages addressed to the whole guild.
+pub(crate) fn guild_locale() -> &'static str {
+    resolve(&SETTINGS.locale).unwrap_or(FALLBACK_LOCALE)
+}
+
+/// Locale of replies to the command's author.
+pub(crate) fn user_locale(ctx: Context<'_>) -> &'static str {
+    ctx.locale().and_then(resolve).unwrap_or_else(guild_locale)
+}
+
+/// Returns the text for `key` in the given locale, with `{name}` placeholders
+/// replaced by the matching arguments. Falls back to [`FALLBACK_LOCALE`] and
+/// then to the key itself when the text is missing.
+pub(crate) fn translate(locale: &str, key: &str, args: &[(&str, String)]) -> String {
+    let text = CATALOGS
+        .get(locale)
+        .and_then(|catalog| catalog.get(key))
+        .or_else(|| {
+            warn!("Missing {locale} translation for {key}");
+            CATALOGS[FALLBACK_LOCALE].get(key)
+        });
+
+    let Some(text) = text else {
+        warn!("Missing translation for {key}");
+        return key.to_string();
+    };
+
+    args.iter().fold(text.clone(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{resolve, translate, CATALOGS, FALLBACK_LOCALE};
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn catalogs_have_the_same_keys() {
+        let reference = &CATALOGS[FALLBACK_LOCALE];
+
+        for (locale, catalog) in CATALOGS.iter() {
+            for key in reference.keys() {
+                assert!(catalog.contains_key(key), "{locale} is missing {key}");
+            }
+
+            for key in catalog.keys() {
+                assert!(
+                    reference.contains_key(key),
+                    "{FALLBACK_LOCALE} is missing {key} from {locale}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn catalogs_have_the_same_placeholders() {
+        let reference = &CATALOGS[FALLBACK_LOCALE];
+
+        for (locale, catalog) in CATALOGS.iter() {
+            for (key, text) in catalog {
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(&reference[key]),
+                    "{locale} placeholders of {key} differ"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        let text = translate(
+            "en",
+            "common.page",
+            &[("page", 2.to_string()), ("total", 5.to_string())],
+        );
+
+        assert_eq!(text, "Page 2/5");
+    }
+
+    #[test]
+    fn missing_key_falls_back_to_key() {
+        assert_eq!(translate("pl", "does.not_exist", &[]), "does.not_exist");
+    }
+
+    #[test]
+    fn discord_locales_are_resolved() {
+        assert_eq!(resolve("en-US"), Some("en"));
+        assert_eq!(resolve("pl"), Some("pl"));
+        assert_eq!(resolve("de"), None);
+    }
+
+    #[test]
+    fn placeholders_are_found() {
+        assert_eq!(
+            placeholders("{member} Last active: {date}"),
+            BTreeSet::from(["member", "date"])
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Escalation of members' inactivity.
+//!
+//! Members are checked every `inactivity.check_interval_hours`, and the
+//! steps due for the days they have been inactive are taken: a reminder DM,
+//! then a notice to the leads, then offboarding, which is offered to admins
+//! in `discord.admin_channel` or done right away with
+//! `inactivity.auto_offboard`. Offboarding only follows the reminder after
+//! the days configured between the two, so a member inactive for long still
+//! gets a chance to answer it. Every step is recorded with the day the
+//! inactivity started, so it is taken once per inactivity; a member who is
+//! active again starts over. Days on leave are not counted, and members are
+//! only escalated once they are inactive under their role's activity
+//! threshold, like in `/activity list`. Nothing happens while silent mode is
+//! enabled.
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use poise::serenity_prelude::{CreateMessage, Http, UserId};
+use tracing::{error, info};
+
+use crate::{
+    activity,
+    database::models::{
+        inactivity_escalation::{EscalationStep, InactivityEscalation},
+        leave::Leave,
+        member::Member,
+        role::Role,
+    },
+    error::Error,
+    i18n::{self, t},
+    jobs,
+    member_events::{self, Offer},
+    offboarding::{self, StepStatus},
+    promotion, settings, SETTINGS,
+};
+
+/// Returns the first day of the member's inactivity: their last activity or
+/// the day they got their role, whichever is later.
+pub(crate) fn inactive_since(member: &Member) -> NaiveDate {
+    let role_since = member.role_since().date();
+
+    member
+        .last_activity()
+        .map_or(role_since, |last_activity| last_activity.max(role_since))
+}
+
+/// Returns how many days after `since` up to `today` the member has been
+/// inactive, leaving out their leaves, or `None` if they are not inactive
+/// under their role's threshold, e.g. because they are on leave.
+pub(crate) fn escalation_days(
+    since: NaiveDate,
+    today: NaiveDate,
+    leaves: &[(NaiveDate, NaiveDate)],
+    threshold_days: i64,
+) -> Option<i64> {
+    activity::is_inactive(Some(since), today, leaves, threshold_days)
+        .then(|| activity::days_inactive(since, today, leaves))
+}
+
+/// Returns how many days the member has been inactive, or `None` if they are
+/// not inactive under their role's threshold.
+pub(crate) fn days_inactive(member: &Member) -> Result<Option<i64>, Error> {
+    Ok(escalation_days(
+        inactive_since(member),
+        chrono::Local::now().date_naive(),
+        &Leave::periods(member.id())?,
+        member.role()?.threshold_days(),
+    ))
+}
+
+/// Returns the steps due after the days of inactivity that were not taken
+/// yet, in order. `taken` holds the steps already taken with their day.
+/// Offboarding is only due once the warning, if enabled, was taken at least
+/// the days between the two steps before `today`.
+pub(crate) fn due_steps(
+    days: i64,
+    taken: &[(EscalationStep, NaiveDate)],
+    today: NaiveDate,
+    policy: &settings::Inactivity,
+) -> Vec<EscalationStep> {
+    let offboarding = if policy.auto_offboard {
+        EscalationStep::Offboarded
+    } else {
+        EscalationStep::OffboardingProposed
+    };
+
+    let warned_long_enough = policy.warn_after_days <= 0
+        || taken.iter().any(|(step, taken_on)| {
+            *step == EscalationStep::Warned
+                && (today - *taken_on).num_days()
+                    >= policy.offboard_after_days - policy.warn_after_days
+        });
+
+    [
+        (EscalationStep::Warned, policy.warn_after_days),
+        (
+            EscalationStep::LeadsNotified,
+            policy.notify_leads_after_days,
+        ),
+        (offboarding, policy.offboard_after_days),
+    ]
+    .into_iter()
+    .filter(|(step, after_days)| {
+        *after_days > 0
+            && days >= *after_days
+            && !taken.iter().any(|(taken_step, _)| taken_step == step)
+            && (*step != offboarding || warned_long_enough)
+    })
+    .map(|(step, _)| step)
+    .collect()
+}
+
+/// Offboards the inactive member and records it.
+pub(crate) async fn offboard(http: &Http, member: &Member) -> Result<Vec<StepStatus>, Error> {
+    let since = inactive_since(member);
+
+    let steps = offboarding::offboard(
+        http,
+        member,
+        t!(i18n::guild_locale(), "inactivity.reason", date = since),
+    )
+    .await?;
+
+    InactivityEscalation::record(member.id(), EscalationStep::Offboarded, since)?;
+
+    Ok(steps)
+}
+
+async fn take_step(
+    http: &Http,
+    member: &Member,
+    step: EscalationStep,
+    days: i64,
+) -> Result<(), Error> {
+    let locale = i18n::guild_locale();
+
+    match step {
+        EscalationStep::Warned => {
+            let discord_id = member
+                .discord_id()
+                .and_then(|id| id.parse::<u64>().ok())
+                .ok_or(Error::NoDiscordAccount)?;
+
+            UserId::new(discord_id)
+                .create_dm_channel(http)
+                .await?
+                .send_message(
+                    http,
+                    CreateMessage::new().content(t!(
+                        locale,
+                        "inactivity.warning",
+                        member = member.name(),
+                        days = days
+                    )),
+                )
+                .await?;
+        }
+        EscalationStep::LeadsNotified => {
+            promotion::notify_leads(
+                http,
+                t!(
+                    locale,
+                    "inactivity.leads_notice",
+                    member = member.name(),
+                    days = days
+                ),
+            )
+            .await?;
+        }
+        EscalationStep::OffboardingProposed => {
+            member_events::notify_admins(
+                http,
+                t!(
+                    locale,
+                    "inactivity.offboarding_proposal",
+                    member = member.name(),
+                    days = days
+                ),
+                Some((
+                    Offer::Offboard(member.id()),
+                    t!(locale, "inactivity.offboard_button"),
+                )),
+            )
+            .await;
+        }
+        EscalationStep::Offboarded => {
+            let steps = offboard(http, member).await?;
+
+            member_events::notify_admins(
+                http,
+                format!(
+                    "{}\n{}",
+                    t!(
+                        locale,
+                        "inactivity.offboarded",
+                        member = member.name(),
+                        days = days
+                    ),
+                    offboarding::checklist(locale, &steps, &SETTINGS.offboarding.manual_steps)
+                ),
+                None,
+            )
+            .await;
+
+            // recorded by `offboard`
+            return Ok(());
+        }
+    }
+
+    InactivityEscalation::record(member.id(), step, inactive_since(member))?;
+
+    Ok(())
+}
+
+/// Takes the steps due for every inactive member. A failed step is retried
+/// on the next check.
+async fn escalate(http: &Http) -> Result<(), Error> {
+    for member in Member::list_current()? {
+        let since = inactive_since(&member);
+
+        let Some(days) = days_inactive(&member)? else {
+            continue;
+        };
+
+        let taken = InactivityEscalation::steps_taken(member.id(), since)?;
+
+        let today = chrono::Local::now().date_naive();
+
+        for step in due_steps(days, &taken, today, &SETTINGS.inactivity) {
+            info!(
+                "Inactivity step {:?} for {} inactive for {} days",
+                step,
+                member.name(),
+                days
+            );
+
+            if let Err(why) = take_step(http, &member, step, days).await {
+                error!(
+                    "Failed to take inactivity step {:?} for {}: {}",
+                    step,
+                    member.name(),
+                    why
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Periodically escalates the members' inactivity, unless silent mode is
+/// enabled.
+pub(crate) fn spawn_inactivity_job(http: Arc<Http>) {
+    jobs::spawn_periodic(
+        "Inactivity escalation",
+        SETTINGS.inactivity.check_interval_hours,
+        true,
+        move || {
+            let http = http.clone();
+            async move { escalate(&http).await }
+        },
+    );
+}
+
+/// Whether the member is still inactive long enough to be offboarded, e.g.
+/// when an admin accepts an offboarding offered earlier.
+pub(crate) fn is_offboarding_due(member: &Member) -> Result<bool, Error> {
+    Ok(member.role_id() != Role::EX_MEMBER
+        && SETTINGS.inactivity.offboard_after_days > 0
+        && days_inactive(member)?
+            .is_some_and(|days| days >= SETTINGS.inactivity.offboard_after_days))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::{due_steps, escalation_days};
+    use crate::{database::models::inactivity_escalation::EscalationStep, settings::Inactivity};
+
+    fn policy(auto_offboard: bool) -> Inactivity {
+        Inactivity {
+            warn_after_days: 30,
+            notify_leads_after_days: 45,
+            offboard_after_days: 60,
+            auto_offboard,
+            check_interval_hours: 24,
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn days_ago(days: i64) -> NaiveDate {
+        today() - Duration::days(days)
+    }
+
+    #[test]
+    fn steps_are_due_after_their_days() {
+        assert!(due_steps(29, &[], today(), &policy(false)).is_empty());
+        assert_eq!(
+            due_steps(30, &[], today(), &policy(false)),
+            vec![EscalationStep::Warned]
+        );
+        assert_eq!(
+            due_steps(
+                50,
+                &[(EscalationStep::Warned, days_ago(20))],
+                today(),
+                &policy(false)
+            ),
+            vec![EscalationStep::LeadsNotified]
+        );
+        assert_eq!(
+            due_steps(
+                60,
+                &[
+                    (EscalationStep::Warned, days_ago(30)),
+                    (EscalationStep::LeadsNotified, days_ago(15))
+                ],
+                today(),
+                &policy(false)
+            ),
+            vec![EscalationStep::OffboardingProposed]
+        );
+    }
+
+    #[test]
+    fn offboarding_waits_for_the_warning() {
+        assert_eq!(
+            due_steps(90, &[], today(), &policy(true)),
+            vec![EscalationStep::Warned, EscalationStep::LeadsNotified]
+        );
+
+        let taken = [
+            (EscalationStep::Warned, days_ago(29)),
+            (EscalationStep::LeadsNotified, days_ago(29)),
+        ];
+
+        assert!(due_steps(119, &taken, today(), &policy(true)).is_empty());
+        assert_eq!(
+            due_steps(120, &taken, today() + Duration::days(1), &policy(true)),
+            vec![EscalationStep::Offboarded]
+        );
+    }
+
+    #[test]
+    fn members_within_their_role_threshold_are_not_escalated() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let since = today - Duration::days(60);
+
+        assert_eq!(escalation_days(since, today, &[], 30), Some(60));
+        assert_eq!(escalation_days(since, today, &[], 90), None);
+
+        let leave = (today - Duration::days(5), today + Duration::days(5));
+
+        assert_eq!(escalation_days(since, today, &[leave], 30), None);
+    }
+
+    #[test]
+    fn disabled_steps_are_skipped() {
+        let policy = Inactivity {
+            warn_after_days: 0,
+            ..policy(false)
+        };
+
+        assert_eq!(
+            due_steps(45, &[], today(), &policy),
+            vec![EscalationStep::LeadsNotified]
+        );
+        assert_eq!(
+            due_steps(
+                60,
+                &[(EscalationStep::LeadsNotified, today())],
+                today(),
+                &policy
+            ),
+            vec![EscalationStep::OffboardingProposed]
+        );
+    }
+}
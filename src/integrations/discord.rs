@@ -0,0 +1,80 @@
+//! Discord roles matching the members' roles.
+
+use async_trait::async_trait;
+use poise::serenity_prelude::Http;
+
+use super::Integration;
+use crate::{
+    database::models::{member::Member, role::Role},
+    error::Error,
+};
+
+pub(crate) struct DiscordRoles;
+
+fn discord_id(member: &Member) -> Result<Option<u64>, Error> {
+    Ok(match member.discord_id() {
+        Some(discord_id) => Some(discord_id.parse()?),
+        None => None,
+    })
+}
+
+#[async_trait]
+impl Integration for DiscordRoles {
+    fn name(&self) -> &'static str {
+        "Discord"
+    }
+
+    async fn on_member_added(&self, http: &Http, member: &Member) -> Result<(), Error> {
+        if let Some(discord_id) = discord_id(member)? {
+            member.role()?.add_role(&http, discord_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_role_changed(
+        &self,
+        http: &Http,
+        member: &Member,
+        old_role: &Role,
+    ) -> Result<(), Error> {
+        if let Some(discord_id) = discord_id(member)? {
+            Role::swap_roles(&member.role()?, old_role, &http, discord_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_member_removed(&self, http: &Http, member: &Member) -> Result<(), Error> {
+        if let Some(discord_id) = discord_id(member)? {
+            member.role()?.remove_role(&http, discord_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_profile_updated(
+        &self,
+        http: &Http,
+        old: &Member,
+        member: &Member,
+    ) -> Result<(), Error> {
+        let old_discord_id = discord_id(old)?;
+        let discord_id = discord_id(member)?;
+
+        if old_discord_id == discord_id {
+            return Ok(());
+        }
+
+        // the role moves to the new Discord account
+        if let Some(old_discord_id) = old_discord_id {
+            old.role()?.remove_role(&http, old_discord_id).await?;
+        }
+
+        if let Some(discord_id) = discord_id {
+            member.role()?.add_role(&http, discord_id).await?;
+        }
+
+        Ok(())
+    }
+}
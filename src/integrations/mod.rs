@@ -0,0 +1,295 @@
+//! External services kept in sync with the member database.
+//!
+//! Every service is an [`Integration`] notified about member lifecycle
+//! events. The enabled integrations are configured in the `integrations`
+//! section of the config and collected in [`INTEGRATIONS`]. A failing
+//! integration does not stop the others: failures are collected and
+//! reported by the command that caused the event.
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use poise::serenity_prelude::Http;
+use tracing::{error, info};
+
+use crate::{
+    database::models::{member::Member, role::Role},
+    error::Error,
+    i18n::t,
+    trello::TRELLO,
+    SETTINGS,
+};
+
+mod discord;
+mod trello;
+mod wiki;
+
+lazy_static! {
+    pub(crate) static ref INTEGRATIONS: Integrations = Integrations::from_settings();
+}
+
+/// An external service notified about member lifecycle events. All hooks do
+/// nothing by default.
+#[async_trait]
+pub(crate) trait Integration: Send + Sync {
+    /// Name used in logs and error reports.
+    fn name(&self) -> &'static str;
+
+    /// Called after a new member was added to the database.
+    async fn on_member_added(&self, _http: &Http, _member: &Member) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called after the member's role was changed from `old_role`.
+    async fn on_role_changed(
+        &self,
+        _http: &Http,
+        _member: &Member,
+        _old_role: &Role,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called before the member is removed from the organization.
+    async fn on_member_removed(&self, _http: &Http, _member: &Member) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called after the member was updated, with the member as it was before.
+    async fn on_profile_updated(
+        &self,
+        _http: &Http,
+        _old: &Member,
+        _member: &Member,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Failures of integrations while handling an event.
+#[derive(Debug, Default)]
+pub(crate) struct IntegrationErrors(pub Vec<(&'static str, Error)>);
+
+impl IntegrationErrors {
+    fn push(&mut self, integration: &'static str, event: &str, why: Error) {
+        error!("Integration {integration} failed on {event}: {why}");
+
+        self.0.push((integration, why));
+    }
+
+    /// Merges failures of another event.
+    pub(crate) fn extend(&mut self, other: IntegrationErrors) {
+        self.0.extend(other.0);
+    }
+
+    /// Returns a line describing every failure.
+    pub(crate) fn localized(&self, locale: &str) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(integration, why)| {
+                t!(
+                    locale,
+                    "member.integration_failed",
+                    integration = integration,
+                    error = why
+                )
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for IntegrationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (integration, why)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+
+            write!(f, "{integration}: {why}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The enabled integrations.
+pub(crate) struct Integrations {
+    integrations: Vec<Box<dyn Integration>>,
+}
+
+impl Integrations {
+    pub(crate) fn new(integrations: Vec<Box<dyn Integration>>) -> Self {
+        Integrations { integrations }
+    }
+
+    fn from_settings() -> Self {
+        let mut integrations: Vec<Box<dyn Integration>> = Vec::new();
+
+        if SETTINGS.integrations.discord {
+            integrations.push(Box::new(discord::DiscordRoles));
+        }
+
+        if SETTINGS.integrations.wiki {
+            integrations.push(Box::new(wiki::Wiki));
+        }
+
+        if let Some(client) = TRELLO.as_ref().filter(|_| SETTINGS.integrations.trello) {
+            integrations.push(Box::new(trello::Trello { client }));
+        }
+
+        info!(
+            "Enabled integrations: {:?}",
+            integrations
+                .iter()
+                .map(|integration| integration.name())
+                .collect::<Vec<_>>()
+        );
+
+        Integrations::new(integrations)
+    }
+
+    /// Names of the enabled integrations, in the order they run.
+    pub(crate) fn names(&self) -> Vec<&'static str> {
+        self.integrations
+            .iter()
+            .map(|integration| integration.name())
+            .collect()
+    }
+
+    pub(crate) async fn member_added(&self, http: &Http, member: &Member) -> IntegrationErrors {
+        let mut errors = IntegrationErrors::default();
+
+        for integration in &self.integrations {
+            if let Err(why) = integration.on_member_added(http, member).await {
+                errors.push(integration.name(), "member added", why);
+            }
+        }
+
+        errors
+    }
+
+    pub(crate) async fn role_changed(
+        &self,
+        http: &Http,
+        member: &Member,
+        old_role: &Role,
+    ) -> IntegrationErrors {
+        let mut errors = IntegrationErrors::default();
+
+        for integration in &self.integrations {
+            if let Err(why) = integration.on_role_changed(http, member, old_role).await {
+                errors.push(integration.name(), "role changed", why);
+            }
+        }
+
+        errors
+    }
+
+    pub(crate) async fn member_removed(&self, http: &Http, member: &Member) -> IntegrationErrors {
+        let mut errors = IntegrationErrors::default();
+
+        for integration in &self.integrations {
+            if let Err(why) = integration.on_member_removed(http, member).await {
+                errors.push(integration.name(), "member removed", why);
+            }
+        }
+
+        errors
+    }
+
+    pub(crate) async fn profile_updated(
+        &self,
+        http: &Http,
+        old: &Member,
+        member: &Member,
+    ) -> IntegrationErrors {
+        let mut errors = IntegrationErrors::default();
+
+        for integration in &self.integrations {
+            if let Err(why) = integration.on_profile_updated(http, old, member).await {
+                errors.push(integration.name(), "profile updated", why);
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use async_trait::async_trait;
+    use poise::serenity_prelude::Http;
+
+    use super::{Integration, Integrations};
+    use crate::{
+        database::models::{member::Member, role::Role},
+        error::Error,
+    };
+
+    struct Counting {
+        name: &'static str,
+        fail: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Integration for Counting {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn on_member_added(&self, _http: &Http, _member: &Member) -> Result<(), Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+
+            if self.fail {
+                Err(Error::NoWikiAccount)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn member() -> Member {
+        Member::new("Alice".to_string(), None, None, None, Role::MEMBER, None)
+    }
+
+    #[tokio::test]
+    async fn failure_does_not_stop_other_integrations() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let integrations = Integrations::new(vec![
+            Box::new(Counting {
+                name: "first",
+                fail: true,
+                calls: Arc::clone(&calls),
+            }),
+            Box::new(Counting {
+                name: "second",
+                fail: false,
+                calls: Arc::clone(&calls),
+            }),
+        ]);
+
+        let errors = integrations.member_added(&Http::new(""), &member()).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(errors.0.len(), 1);
+        assert_eq!(errors.0[0].0, "first");
+    }
+
+    #[tokio::test]
+    async fn unhandled_events_succeed() {
+        let integrations = Integrations::new(vec![Box::new(Counting {
+            name: "counting",
+            fail: true,
+            calls: Arc::new(AtomicUsize::new(0)),
+        })]);
+
+        let errors = integrations.member_removed(&Http::new(""), &member()).await;
+
+        assert!(errors.0.is_empty());
+    }
+}
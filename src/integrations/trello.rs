@@ -0,0 +1,73 @@
+//! Trello board membership matching the members.
+
+use async_trait::async_trait;
+use poise::serenity_prelude::Http;
+
+use super::Integration;
+use crate::{database::models::member::Member, error::Error, trello::TrelloClient, SETTINGS};
+
+pub(crate) struct Trello {
+    pub client: &'static TrelloClient,
+}
+
+impl Trello {
+    fn board_id(&self) -> &'static str {
+        SETTINGS
+            .trello
+            .as_ref()
+            .map(|trello| trello.board_id.as_str())
+            .expect("Trello integration requires the trello settings")
+    }
+}
+
+#[async_trait]
+impl Integration for Trello {
+    fn name(&self) -> &'static str {
+        "Trello"
+    }
+
+    async fn on_member_added(&self, _http: &Http, member: &Member) -> Result<(), Error> {
+        if let Some(trello_id) = member.trello_id() {
+            self.client
+                .add_board_member(self.board_id(), trello_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_member_removed(&self, _http: &Http, member: &Member) -> Result<(), Error> {
+        if let Some(trello_id) = member.trello_id() {
+            self.client
+                .remove_board_member(self.board_id(), trello_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_profile_updated(
+        &self,
+        _http: &Http,
+        old: &Member,
+        member: &Member,
+    ) -> Result<(), Error> {
+        if old.trello_id() == member.trello_id() {
+            return Ok(());
+        }
+
+        if let Some(old_trello_id) = old.trello_id() {
+            self.client
+                .remove_board_member(self.board_id(), old_trello_id)
+                .await?;
+        }
+
+        if let Some(trello_id) = member.trello_id() {
+            self.client
+                .add_board_member(self.board_id(), trello_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Wiki.js groups and profiles matching the members.
+
+use async_trait::async_trait;
+use poise::serenity_prelude::Http;
+
+use super::Integration;
+use crate::{
+    database::models::{member::Member, role::Role},
+    error::Error,
+    wiki::{
+        profile::{self, WikiProfile},
+        WIKI,
+    },
+    SETTINGS,
+};
+
+pub(crate) struct Wiki;
+
+#[async_trait]
+impl Integration for Wiki {
+    fn name(&self) -> &'static str {
+        "Wiki.js"
+    }
+
+    async fn on_member_added(&self, _http: &Http, member: &Member) -> Result<(), Error> {
+        if member.wiki_id().is_some() {
+            member.assign_wiki_group_by_role().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_role_changed(
+        &self,
+        _http: &Http,
+        member: &Member,
+        old_role: &Role,
+    ) -> Result<(), Error> {
+        let Some(wiki_id) = member.wiki_id() else {
+            return Ok(());
+        };
+
+        let old_group = Member::wiki_group_of(old_role);
+        let group = member.wiki_group()?;
+
+        if old_group != group {
+            WIKI.unassign_user_group(wiki_id, old_group).await?;
+            WIKI.assign_user_group(wiki_id, group).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_member_removed(&self, _http: &Http, member: &Member) -> Result<(), Error> {
+        let Some(wiki_id) = member.wiki_id() else {
+            return Ok(());
+        };
+
+        // the account stays as a guest account, which can no longer log in
+        member.unassign_wiki_group_by_role().await?;
+        WIKI.assign_user_group(wiki_id, SETTINGS.wiki.guest_group_id)
+            .await?;
+
+        let profile = WikiProfile {
+            is_active: false,
+            ..member.wiki_profile()
+        };
+
+        profile::sync_profile(wiki_id, &profile).await?;
+
+        Ok(())
+    }
+
+    async fn on_profile_updated(
+        &self,
+        _http: &Http,
+        old: &Member,
+        member: &Member,
+    ) -> Result<(), Error> {
+        let guest_group = SETTINGS.wiki.guest_group_id;
+
+        if old.wiki_id() != member.wiki_id() {
+            // the old account becomes a guest account
+            if let Some(old_wiki_id) = old.wiki_id() {
+                let old_group = old.wiki_group()?;
+
+                if old_group != guest_group {
+                    WIKI.unassign_user_group(old_wiki_id, old_group).await?;
+                    WIKI.assign_user_group(old_wiki_id, guest_group).await?;
+                }
+            }
+
+            if let Some(wiki_id) = member.wiki_id() {
+                let group = member.wiki_group()?;
+
+                if group != guest_group {
+                    WIKI.unassign_user_group(wiki_id, guest_group).await?;
+                    WIKI.assign_user_group(wiki_id, group).await?;
+                }
+            }
+        }
+
+        member.sync_wiki_profile().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+//! Periodic background jobs.
+
+use std::{future::Future, time::Duration};
+
+use tracing::{error, info};
+
+use crate::{error::Error, silent};
+
+/// Runs `job` every `hours` hours, starting right away, and logs its
+/// failures. Nothing is spawned when `hours` is 0. With `respect_silent` the
+/// runs are skipped while silent mode is enabled; jobs which only act when
+/// silent mode is disabled check it themselves.
+pub(crate) fn spawn_periodic<F, Fut>(name: &'static str, hours: u64, respect_silent: bool, job: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), Error>> + Send,
+{
+    if hours == 0 {
+        info!("{} is disabled", name);
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(hours * 60 * 60));
+
+        loop {
+            interval.tick().await;
+
+            if respect_silent && silent::is_enabled() {
+                continue;
+            }
+
+            if let Err(why) = job().await {
+                error!("{} failed: {}", name, why);
+            }
+        }
+    });
+}
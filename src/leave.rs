@@ -0,0 +1,179 @@
+//! Leaves of absence.
+//!
+//! Members, or admins for them, set the periods they are away with `/leave
+//! add`. The days on leave are not counted towards the members' inactivity,
+//! and members on leave are never inactive. Once a leave ends, the member's
+//! return is announced in `leave.announcement_channel`, unless silent mode is
+//! enabled.
+
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate};
+use poise::serenity_prelude::{CreateAllowedMentions, CreateMessage, Http};
+use tracing::info;
+
+use crate::{
+    database::models::{leave::Leave, member::Member, role::Role},
+    error::Error,
+    i18n::{self, t},
+    jobs, SETTINGS,
+};
+
+/// Returns announced later than this many days after the leave are skipped,
+/// e.g. after silent mode was enabled for a while.
+const ANNOUNCE_WITHIN_DAYS: i64 = 7;
+
+/// Parses a `YYYY-MM-DD` date.
+pub(crate) fn parse_date(value: &str) -> Result<NaiveDate, Error> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| Error::InvalidDate(value.to_string()))
+}
+
+/// Returns the leave among `leaves` overlapping the period from `start` to
+/// `end`, if any.
+pub(crate) fn overlapping(
+    start: NaiveDate,
+    end: NaiveDate,
+    leaves: &[(NaiveDate, NaiveDate)],
+) -> Option<(NaiveDate, NaiveDate)> {
+    leaves
+        .iter()
+        .copied()
+        .find(|(leave_start, leave_end)| *leave_start <= end && *leave_end >= start)
+}
+
+/// Returns how many days in a row a member is away with a new leave from
+/// `start` to `end`, counting the `leaves` right before and after it.
+pub(crate) fn consecutive_days(
+    mut start: NaiveDate,
+    mut end: NaiveDate,
+    leaves: &[(NaiveDate, NaiveDate)],
+) -> i64 {
+    loop {
+        let extended = leaves.iter().fold((start, end), |(start, end), leave| {
+            if leave.0 <= end + Duration::days(1) && leave.1 >= start - Duration::days(1) {
+                (start.min(leave.0), end.max(leave.1))
+            } else {
+                (start, end)
+            }
+        });
+
+        if extended == (start, end) {
+            return (end - start).num_days() + 1;
+        }
+
+        (start, end) = extended;
+    }
+}
+
+/// Whether the return from the leave ending on `end` is still worth
+/// announcing on `today`.
+fn is_announced(end: NaiveDate, today: NaiveDate) -> bool {
+    (today - end).num_days() <= ANNOUNCE_WITHIN_DAYS
+}
+
+/// Announces the members whose leave ended. Members who are on another leave
+/// or were offboarded are not announced.
+async fn announce_returns(http: &Http) -> Result<(), Error> {
+    let locale = i18n::guild_locale();
+    let today = chrono::Local::now().date_naive();
+    let channel_id = SETTINGS
+        .leave
+        .announcement_channel
+        .unwrap_or(SETTINGS.discord.summary_channel);
+
+    for mut leave in Leave::returns_to_announce(today)? {
+        let member = Member::find_by_id(leave.member_id())?;
+
+        if is_announced(leave.end_date(), today)
+            && member.role_id() != Role::EX_MEMBER
+            && Leave::on(member.id(), today)?.is_none()
+        {
+            let mention = member
+                .discord_id()
+                .map(|id| format!("<@{id}>"))
+                .unwrap_or_else(|| member.name());
+
+            channel_id
+                .send_message(
+                    http,
+                    CreateMessage::new()
+                        .content(t!(locale, "leave.returned", member = mention))
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                )
+                .await?;
+
+            info!("Return from leave announced: {:?}", leave);
+        }
+
+        leave.set_return_announced()?;
+    }
+
+    Ok(())
+}
+
+/// Periodically announces the members back from their leave, unless silent
+/// mode is enabled.
+pub(crate) fn spawn_leave_job(http: Arc<Http>) {
+    jobs::spawn_periodic(
+        "Return announcement",
+        SETTINGS.leave.check_interval_hours,
+        true,
+        move || {
+            let http = http.clone();
+            async move { announce_returns(&http).await }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{consecutive_days, is_announced, overlapping, parse_date};
+
+    #[test]
+    fn dates_are_parsed() {
+        assert_eq!(
+            parse_date(" 2026-10-18 ").unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+        );
+        assert!(parse_date("18.10.2026").is_err());
+    }
+
+    #[test]
+    fn late_returns_are_not_announced() {
+        let end = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+
+        assert!(is_announced(
+            end,
+            NaiveDate::from_ymd_opt(2026, 10, 2).unwrap()
+        ));
+        assert!(!is_announced(
+            end,
+            NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()
+        ));
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn overlapping_leaves_are_found() {
+        let leaves = [(day(5), day(10))];
+
+        assert_eq!(overlapping(day(10), day(12), &leaves), Some(leaves[0]));
+        assert_eq!(overlapping(day(1), day(5), &leaves), Some(leaves[0]));
+        assert_eq!(overlapping(day(11), day(12), &leaves), None);
+    }
+
+    #[test]
+    fn chained_leaves_are_counted_together() {
+        let leaves = [(day(1), day(10)), (day(21), day(25)), (day(28), day(30))];
+
+        assert_eq!(consecutive_days(day(11), day(20), &[]), 10);
+        assert_eq!(consecutive_days(day(11), day(20), &leaves), 25);
+        assert_eq!(consecutive_days(day(12), day(19), &leaves), 8);
+    }
+}
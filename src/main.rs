@@ -10,12 +10,25 @@ use tracing_subscriber::{
     prelude::__tracing_subscriber_SubscriberExt,
 };
 
+mod activity;
 mod database;
 mod discord;
 pub mod error;
+mod i18n;
+mod inactivity;
+mod integrations;
+mod jobs;
+mod leave;
 mod meeting;
+mod member_events;
+mod offboarding;
+mod onboarding;
+mod promotion;
+mod reconcile;
+mod recruitment;
 mod settings;
 mod silent;
+pub mod trello;
 pub mod wiki;
 
 lazy_static! {
@@ -37,6 +50,30 @@ async fn main() {
     );
 
     database::run_migrations();
+
+    if let Err(why) = database::models::role::Role::seed_from_settings() {
+        tracing::error!("Could not seed the roles from the settings: {}", why);
+    }
+
+    if let Err(why) = database::models::team::Team::reload_member_teams() {
+        tracing::error!("Could not load the teams: {}", why);
+    }
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("import-trello") {
+        if let Err(why) = trello::import::run(&args[1..]) {
+            tracing::error!("Trello import failed: {}", why);
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    if let Err(why) = wiki::init() {
+        tracing::error!("Unable to create the wiki client: {}", why);
+        std::process::exit(1);
+    }
+
     discord::start_bot().await;
 }
 
@@ -20,6 +20,7 @@ use uuid::Uuid;
 
 use crate::{
     database::models::{
+        activity_event::ActivityKind,
         meeting::{Meeting, MeetingMembers},
         member::Member,
     },
@@ -149,12 +150,12 @@ impl MeetingStatus {
 
             let end_time = meeting.meeting_data.end_date.unwrap();
 
+            let meeting_id = meeting.meeting_data.id();
+
             let members = meeting.meeting_data.members()?;
 
             for mut member in members {
-                member.set_last_activity(end_time.into());
-
-                member.update()?;
+                member.record_activity(ActivityKind::Meeting, meeting_id, end_time)?;
             }
 
             *meeting = MeetingStatus::new(&scheduled_cron, channel_id)?;
@@ -165,14 +166,14 @@ impl MeetingStatus {
         Ok(())
     }
 
-    pub fn add_member(&mut self, member: &mut Member) -> Result<String, Error> {
+    pub fn add_member(&mut self, member: &mut Member) -> Result<(), Error> {
         let meeting = self.meeting();
         match meeting.add_member(member) {
-            Ok(msg) => {
+            Ok(()) => {
                 info!("{} joined", member.name());
                 self.members
                     .push(MeetingMembers::new(member.id(), self.meeting_id()));
-                Ok(msg)
+                Ok(())
             }
             Err(e) => {
                 error!("Error adding member to meeting: {}", e);
@@ -181,7 +182,9 @@ impl MeetingStatus {
         }
     }
 
-    pub fn remove_member(&mut self, member: &mut Member) -> Result<String, Error> {
+    /// Removes the member from the meeting. Returns `false` if the member was
+    /// not in the meeting.
+    pub fn remove_member(&mut self, member: &mut Member) -> Result<bool, Error> {
         self.members.retain(|m| m.member_id() != member.id());
 
         let meeting = self.meeting();
@@ -0,0 +1,473 @@
+//! Keeping the member table current with the Discord server.
+//!
+//! Members leaving the server are offboarded, or flagged for review when
+//! `discord.offboard_on_leave` is off. Ex-members rejoining the server and
+//! members changing their nickname are offered to admins in
+//! `discord.admin_channel` as a reinstatement or a name update. The offers'
+//! buttons encode what they apply to in their `custom_id` and are handled by
+//! [`handle_interaction`] from the global event handler, so they keep working
+//! after restarts. New users joining through a tracked invite are recruited,
+//! see [`recruitment`](crate::recruitment).
+
+use poise::serenity_prelude::{
+    self as serenity, ComponentInteraction, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditMessage, Http,
+    User, UserId,
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    database::models::{member::Member, offboarding::Offboarding},
+    error::Error,
+    i18n::{self, t},
+    inactivity,
+    integrations::INTEGRATIONS,
+    offboarding, recruitment, silent, SETTINGS,
+};
+
+const CUSTOM_ID_PREFIX: &str = "member_event:";
+
+/// Change offered to admins with a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Offer {
+    /// Reinstate the ex-member from the offboarding.
+    Reinstate(Uuid),
+    /// Rename the member to their display name on the server.
+    Rename(Uuid),
+    /// Offboard the inactive member.
+    Offboard(Uuid),
+}
+
+/// What happens to a member who left the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LeaveAction {
+    Offboard,
+    /// Flag the member for review by an admin.
+    Flag,
+}
+
+/// Returns the `custom_id` of the offer's button.
+pub(crate) fn custom_id(offer: Offer) -> String {
+    match offer {
+        Offer::Reinstate(id) => format!("{CUSTOM_ID_PREFIX}reinstate:{}", id.as_simple()),
+        Offer::Rename(id) => format!("{CUSTOM_ID_PREFIX}rename:{}", id.as_simple()),
+        Offer::Offboard(id) => format!("{CUSTOM_ID_PREFIX}offboard:{}", id.as_simple()),
+    }
+}
+
+/// Returns the offer encoded in the `custom_id`, if it belongs to an offer's
+/// button.
+pub(crate) fn parse_custom_id(custom_id: &str) -> Option<Offer> {
+    let (action, id) = custom_id.strip_prefix(CUSTOM_ID_PREFIX)?.split_once(':')?;
+    let id = Uuid::parse_str(id).ok()?;
+
+    match action {
+        "reinstate" => Some(Offer::Reinstate(id)),
+        "rename" => Some(Offer::Rename(id)),
+        "offboard" => Some(Offer::Offboard(id)),
+        _ => None,
+    }
+}
+
+/// Members are only offboarded automatically when it is enabled and the bot
+/// may act on its own.
+pub(crate) fn leave_action(offboard_on_leave: bool, silent: bool) -> LeaveAction {
+    if offboard_on_leave && !silent {
+        LeaveAction::Offboard
+    } else {
+        LeaveAction::Flag
+    }
+}
+
+/// Whether the change of the display name from `old` to `new` should be
+/// offered as the member's new name. Updates without the old name, e.g.
+/// after a restart, are not offered, as they are mostly role changes.
+pub(crate) fn offers_rename(old: Option<&str>, new: &str, member_name: &str) -> bool {
+    old.is_some_and(|old| old != new) && new != member_name
+}
+
+/// Posts the message to the admin channel, unless silent mode is enabled or
+/// no admin channel is configured.
+pub(crate) async fn notify_admins(http: &Http, content: String, offer: Option<(Offer, String)>) {
+    if silent::is_enabled() {
+        info!("Silent mode, not notifying admins: {}", content);
+        return;
+    }
+
+    let Some(channel_id) = SETTINGS.discord.admin_channel else {
+        info!("No admin channel, not notifying admins: {}", content);
+        return;
+    };
+
+    let mut message = CreateMessage::new().content(content);
+
+    if let Some((offer, label)) = offer {
+        message = message.components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+            custom_id(offer),
+        )
+        .label(label)])]);
+    }
+
+    if let Err(why) = channel_id.send_message(http, message).await {
+        error!("Failed to notify admins: {}", why);
+    }
+}
+
+/// Offboards the member who left the server, or flags them for review.
+pub(crate) async fn member_left(http: &Http, user: &User) -> Result<(), Error> {
+    let Ok(mut member) = Member::find_by_discord_id(user.id.to_string()) else {
+        return Ok(());
+    };
+
+    let locale = i18n::guild_locale();
+
+    match leave_action(SETTINGS.discord.offboard_on_leave, silent::is_enabled()) {
+        LeaveAction::Offboard => {
+            let steps =
+                offboarding::offboard(http, &member, t!(locale, "member_events.left_reason"))
+                    .await?;
+
+            notify_admins(
+                http,
+                format!(
+                    "{}\n{}",
+                    t!(locale, "member_events.offboarded", member = member.name()),
+                    offboarding::checklist(locale, &steps, &SETTINGS.offboarding.manual_steps)
+                ),
+                None,
+            )
+            .await;
+        }
+        LeaveAction::Flag => {
+            member.set_left_server(true)?;
+
+            info!("Member flagged after leaving the server: {:?}", member);
+
+            notify_admins(
+                http,
+                t!(locale, "member_events.flagged", member = member.name()),
+                None,
+            )
+            .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Clears the flag of a member who came back, offers to reinstate an
+/// ex-member who rejoined, or recruits a new user who joined through a
+/// tracked invite.
+pub(crate) async fn member_joined(http: &Http, new_member: &serenity::Member) -> Result<(), Error> {
+    let locale = i18n::guild_locale();
+    let discord_id = new_member.user.id.to_string();
+
+    // the invites' uses are recorded on every join, so the next join is
+    // matched to the right invite
+    let invite = recruitment::track_join(http).await.unwrap_or_else(|why| {
+        warn!("Failed to track invites: {}", why);
+
+        None
+    });
+
+    if let Ok(mut member) = Member::find_by_discord_id(&discord_id) {
+        if member.left_server_at().is_some() {
+            member.set_left_server(false)?;
+
+            info!("Flagged member rejoined the server: {:?}", member);
+
+            notify_admins(
+                http,
+                t!(locale, "member_events.returned", member = member.name()),
+                None,
+            )
+            .await;
+        }
+
+        return Ok(());
+    }
+
+    let Some(archive) = Offboarding::find_by_discord_id(discord_id)? else {
+        if let Some(invite) = invite {
+            let member = recruitment::recruit(http, new_member, &invite).await?;
+
+            notify_admins(
+                http,
+                t!(
+                    locale,
+                    "member_events.recruited",
+                    member = member.name(),
+                    role = invite.role()?,
+                    code = invite.code()
+                ),
+                None,
+            )
+            .await;
+        }
+
+        return Ok(());
+    };
+
+    notify_admins(
+        http,
+        t!(
+            locale,
+            "member_events.ex_member_joined",
+            user = format!("<@{}>", new_member.user.id),
+            date = archive.offboarded_at().format("%Y-%m-%d"),
+            reason = archive.reason()
+        ),
+        Some((
+            Offer::Reinstate(archive.id()),
+            t!(locale, "member_events.reinstate_button"),
+        )),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Offers the member's new display name on the server as their name.
+pub(crate) async fn member_updated(
+    http: &Http,
+    old: Option<&serenity::Member>,
+    event: &serenity::GuildMemberUpdateEvent,
+) -> Result<(), Error> {
+    let Ok(member) = Member::find_by_discord_id(event.user.id.to_string()) else {
+        return Ok(());
+    };
+
+    let new_name = event
+        .nick
+        .as_ref()
+        .or(event.user.global_name.as_ref())
+        .unwrap_or(&event.user.name);
+
+    if !offers_rename(old.map(|old| old.display_name()), new_name, &member.name()) {
+        return Ok(());
+    }
+
+    let locale = i18n::guild_locale();
+
+    notify_admins(
+        http,
+        t!(
+            locale,
+            "member_events.renamed",
+            member = member.name(),
+            name = new_name
+        ),
+        Some((
+            Offer::Rename(member.id()),
+            t!(locale, "member_events.rename_button"),
+        )),
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn respond_ephemeral(
+    ctx: &serenity::Context,
+    interaction: &ComponentInteraction,
+    content: String,
+) -> Result<(), Error> {
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Replaces the offer's button with the outcome.
+async fn resolve_offer(
+    ctx: &serenity::Context,
+    interaction: &ComponentInteraction,
+    outcome: String,
+) -> Result<(), Error> {
+    let mut message = interaction.message.clone();
+    let content = format!("{}\n{}", message.content, outcome);
+
+    message
+        .edit(
+            ctx,
+            EditMessage::new().content(content).components(Vec::new()),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn reinstate(ctx: &serenity::Context, locale: &str, id: Uuid) -> Result<String, Error> {
+    let Some(archive) = Offboarding::find_by_id(id)?.filter(|a| a.reinstated_at().is_none()) else {
+        return Ok(t!(locale, "member_events.expired"));
+    };
+
+    let role = archive.previous_role()?;
+    let (member, steps) = offboarding::reinstate(&ctx.http, archive).await?;
+
+    let mut lines = vec![t!(
+        locale,
+        "member_events.reinstated",
+        member = member.name(),
+        role = role
+    )];
+
+    for step in steps {
+        if let Some(why) = step.error {
+            lines.push(t!(
+                locale,
+                "member.integration_failed",
+                integration = step.integration,
+                error = why
+            ));
+        }
+    }
+
+    Ok(lines.join("\n"))
+}
+
+async fn rename(ctx: &serenity::Context, locale: &str, id: Uuid) -> Result<String, Error> {
+    let mut member = Member::find_by_id(id)?;
+
+    let Some(discord_id) = member.discord_id().and_then(|id| id.parse::<u64>().ok()) else {
+        return Ok(t!(locale, "member_events.expired"));
+    };
+
+    let guild_member = SETTINGS
+        .discord
+        .server_id
+        .member(ctx, UserId::new(discord_id))
+        .await?;
+
+    let old = member.clone();
+    member.set_name(guild_member.display_name().to_string())?;
+
+    info!("Member renamed from the server: {:?}", member);
+
+    let mut lines = vec![t!(
+        locale,
+        "member_events.name_updated",
+        member = member.name()
+    )];
+
+    for (integration, why) in INTEGRATIONS
+        .profile_updated(&ctx.http, &old, &member)
+        .await
+        .0
+    {
+        lines.push(t!(
+            locale,
+            "member.integration_failed",
+            integration = integration,
+            error = why
+        ));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+async fn offboard(ctx: &serenity::Context, locale: &str, id: Uuid) -> Result<String, Error> {
+    let member = Member::find_by_id(id)?;
+
+    // the member may have been active or offboarded since the offer
+    if !inactivity::is_offboarding_due(&member)? {
+        return Ok(t!(locale, "member_events.expired"));
+    }
+
+    let steps = inactivity::offboard(&ctx.http, &member).await?;
+
+    Ok(format!(
+        "{}\n{}",
+        t!(locale, "member.removed", member = member.name()),
+        offboarding::checklist(locale, &steps, &SETTINGS.offboarding.manual_steps)
+    ))
+}
+
+/// Handles a click on an offer's button. Only admins may accept offers, and
+/// interactions with other components are ignored.
+pub(crate) async fn handle_interaction(
+    ctx: &serenity::Context,
+    interaction: &ComponentInteraction,
+) -> Result<(), Error> {
+    let Some(offer) = parse_custom_id(&interaction.data.custom_id) else {
+        return Ok(());
+    };
+
+    let locale = i18n::guild_locale();
+
+    let is_admin = interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.administrator());
+
+    if !is_admin {
+        return respond_ephemeral(ctx, interaction, t!(locale, "member_events.not_allowed")).await;
+    }
+
+    info!("Offer {:?} accepted by {}", offer, interaction.user.name);
+
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    let outcome = match offer {
+        Offer::Reinstate(id) => reinstate(ctx, locale, id).await,
+        Offer::Rename(id) => rename(ctx, locale, id).await,
+        Offer::Offboard(id) => offboard(ctx, locale, id).await,
+    };
+
+    let outcome = outcome.unwrap_or_else(|why| {
+        warn!("Failed to apply offer {:?}: {}", offer, why);
+
+        t!(locale, "member_events.failed", error = why)
+    });
+
+    resolve_offer(ctx, interaction, outcome).await
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{custom_id, leave_action, offers_rename, parse_custom_id, LeaveAction, Offer};
+
+    #[test]
+    fn custom_id_round_trips() {
+        let id = Uuid::new_v4();
+
+        for offer in [Offer::Reinstate(id), Offer::Rename(id), Offer::Offboard(id)] {
+            assert_eq!(parse_custom_id(&custom_id(offer)), Some(offer));
+        }
+
+        assert_eq!(parse_custom_id("onboarding:abc"), None);
+        assert_eq!(
+            parse_custom_id(&format!("member_event:ban:{}", id.as_simple())),
+            None
+        );
+    }
+
+    #[test]
+    fn members_are_only_offboarded_when_enabled_and_not_silent() {
+        assert_eq!(leave_action(true, false), LeaveAction::Offboard);
+        assert_eq!(leave_action(true, true), LeaveAction::Flag);
+        assert_eq!(leave_action(false, false), LeaveAction::Flag);
+    }
+
+    #[test]
+    fn only_changed_names_are_offered() {
+        assert!(offers_rename(Some("Alice"), "Ally", "Alice"));
+        assert!(!offers_rename(Some("Alice"), "Alice", "Alice"));
+        assert!(!offers_rename(Some("Alice"), "Ally", "Ally"));
+        assert!(!offers_rename(None, "Ally", "Alice"));
+    }
+}
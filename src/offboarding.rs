@@ -0,0 +1,181 @@
+//! Offboarding of members leaving the organization, and their reinstatement.
+//!
+//! Offboarding runs every integration as a separate step and archives the
+//! member's role and external accounts in an [`Offboarding`] before they
+//! are cleared from the member. Steps that cannot be automated, such as
+//! revoking repository access, are listed for admins as a checklist from the
+//! `offboarding.manual_steps` config option.
+
+use poise::serenity_prelude::Http;
+use tracing::info;
+
+use crate::{
+    database::models::{
+        member::Member, offboarding::Offboarding, onboarding::Onboarding, role::Role,
+    },
+    error::Error,
+    i18n::t,
+    integrations::{IntegrationErrors, INTEGRATIONS},
+};
+
+/// Result of a single integration step.
+#[derive(Debug)]
+pub(crate) struct StepStatus {
+    pub integration: &'static str,
+    pub error: Option<Error>,
+}
+
+/// Pairs every integration with its failure, if it failed.
+pub(crate) fn step_statuses(
+    integrations: &[&'static str],
+    mut errors: IntegrationErrors,
+) -> Vec<StepStatus> {
+    integrations
+        .iter()
+        .map(|&integration| {
+            let error = errors
+                .0
+                .iter()
+                .position(|(name, _)| *name == integration)
+                .map(|i| errors.0.remove(i).1);
+
+            StepStatus { integration, error }
+        })
+        .collect()
+}
+
+/// Lists the integration steps with their status, followed by the manual
+/// steps left to admins.
+pub(crate) fn checklist(locale: &str, steps: &[StepStatus], manual_steps: &[String]) -> String {
+    let mut lines = vec![t!(locale, "offboarding.checklist")];
+
+    for step in steps {
+        lines.push(match &step.error {
+            None => t!(locale, "offboarding.step_done", step = step.integration),
+            Some(why) => t!(
+                locale,
+                "offboarding.step_failed",
+                step = step.integration,
+                error = why
+            ),
+        });
+    }
+
+    if !manual_steps.is_empty() {
+        lines.push(t!(locale, "offboarding.manual_steps"));
+
+        for step in manual_steps {
+            lines.push(t!(locale, "offboarding.manual_step", step = step));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Offboards the member: runs the integrations, archives the member's
+/// external accounts and turns them into an ex-member.
+pub(crate) async fn offboard(
+    http: &Http,
+    member: &Member,
+    reason: String,
+) -> Result<Vec<StepStatus>, Error> {
+    // the accounts were already archived and cleared
+    if member.role_id() == Role::EX_MEMBER {
+        return Err(Error::AlreadyOffboarded(member.name()));
+    }
+
+    let errors = INTEGRATIONS.member_removed(http, member).await;
+    let steps = step_statuses(&INTEGRATIONS.names(), errors);
+
+    let failed_steps = steps
+        .iter()
+        .filter_map(|step| {
+            let why = step.error.as_ref()?;
+
+            Some(format!("{}: {}", step.integration, why))
+        })
+        .collect();
+
+    Onboarding::delete_for_member(member.id())?;
+    Offboarding::archive(member, reason, failed_steps)?;
+    member.delete()?;
+
+    info!("Member offboarded: {:?}", member);
+
+    Ok(steps)
+}
+
+/// Restores the ex-member's previous role and external accounts from their
+/// latest offboarding, and runs the integrations for them.
+pub(crate) async fn reinstate(
+    http: &Http,
+    mut offboarding: Offboarding,
+) -> Result<(Member, Vec<StepStatus>), Error> {
+    let old = Member::find_by_id(
+        offboarding
+            .member_id()
+            .ok_or(Error::ArchivedMemberDeleted)?,
+    )?;
+
+    let mut member = old.clone();
+    offboarding.restore(&mut member)?;
+    member.update()?;
+    member.refresh_activity()?;
+    offboarding.set_reinstated()?;
+
+    info!("Member reinstated: {:?}", member);
+
+    // leaving the ex-member role moves the wiki account out of the guest
+    // group, and restoring the accounts gives them back their Discord role,
+    // Trello board and active wiki account
+    let mut errors = INTEGRATIONS
+        .role_changed(http, &member, &Role::find(Role::EX_MEMBER)?)
+        .await;
+    errors.extend(INTEGRATIONS.profile_updated(http, &old, &member).await);
+
+    Ok((member, step_statuses(&INTEGRATIONS.names(), errors)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checklist, step_statuses, StepStatus};
+    use crate::{error::Error, integrations::IntegrationErrors};
+
+    #[test]
+    fn every_integration_has_a_status() {
+        let errors = IntegrationErrors(vec![("Wiki.js", Error::NoWikiAccount)]);
+
+        let steps = step_statuses(&["Discord", "Wiki.js"], errors);
+
+        assert_eq!(steps.len(), 2);
+        assert!(steps[0].error.is_none());
+        assert!(matches!(steps[1].error, Some(Error::NoWikiAccount)));
+    }
+
+    #[test]
+    fn checklist_lists_failures_and_manual_steps() {
+        let steps = vec![
+            StepStatus {
+                integration: "Discord",
+                error: None,
+            },
+            StepStatus {
+                integration: "Wiki.js",
+                error: Some(Error::NoWikiAccount),
+            },
+        ];
+
+        let checklist = checklist("en", &steps, &["Revoke repository access".to_string()]);
+
+        assert_eq!(
+            checklist.lines().collect::<Vec<_>>(),
+            vec![
+                "Offboarding checklist:",
+                "✅ Discord",
+                "❌ Wiki.js: Member has no wiki account",
+                "Manual steps:",
+                "⬜ Revoke repository access",
+            ]
+        );
+    }
+}
@@ -0,0 +1,520 @@
+//! Onboarding of new members.
+//!
+//! Onboarding is a persisted state machine (see [`OnboardingStep`]), so it
+//! survives restarts and answers that come hours later. The welcome DM's
+//! button encodes the onboarding's ID in its `custom_id` and is handled by
+//! [`handle_interaction`] from the global event handler, so it keeps working
+//! for as long as the onboarding waits for the member.
+//!
+//! Steps that only need the bot are run by [`advance`]. The reminder job
+//! resumes them if they failed, and reminds members who have not answered
+//! the welcome DM yet.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::NaiveDateTime;
+use poise::serenity_prelude::{
+    self as serenity, ChannelId, ComponentInteraction, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditMessage, Http,
+    UserId,
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    database::models::{
+        member::Member,
+        onboarding::{Onboarding, OnboardingStep},
+    },
+    error::Error,
+    i18n::{self, t},
+    integrations::{IntegrationErrors, INTEGRATIONS},
+    jobs,
+    wiki::WIKI,
+    SETTINGS,
+};
+
+const CUSTOM_ID_PREFIX: &str = "onboarding:";
+
+/// How long the wiki email modal waits for the member to submit it.
+const MODAL_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// Stalled onboardings are checked this often, the reminder interval only
+/// spaces out the reminders of each onboarding.
+const CHECK_STALLED_EVERY_HOURS: u64 = 1;
+
+/// Modal to get the Discord email of a user to connect or create a new wiki
+/// account
+#[derive(Debug, poise::Modal)]
+#[name = "Discord email for Wiki account"]
+pub(crate) struct WikiEmailModal {
+    #[name = "Discord email"]
+    #[placeholder = "example@domain.com"]
+    #[min_length = 1]
+    #[max_length = 254]
+    pub wiki_email: String,
+}
+
+/// Returns the `custom_id` of the wiki email button of the onboarding.
+pub(crate) fn custom_id(id: Uuid) -> String {
+    format!("{CUSTOM_ID_PREFIX}{}", id.as_simple())
+}
+
+/// Returns the onboarding ID encoded in the `custom_id`, if it belongs to an
+/// onboarding button.
+pub(crate) fn parse_custom_id(custom_id: &str) -> Option<Uuid> {
+    Uuid::parse_str(custom_id.strip_prefix(CUSTOM_ID_PREFIX)?).ok()
+}
+
+/// What the reminder job does with a pending onboarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Nudge {
+    Wait,
+    /// Retry the step, which only needs the bot.
+    Resume,
+    /// Remind the member to answer the welcome DM.
+    Remind,
+}
+
+/// Decides whether a pending onboarding on `step`, last nudged at
+/// `last_nudge`, is stalled. Every step is nudged at most `max_reminders`
+/// times, `interval` apart.
+pub(crate) fn nudge(
+    step: OnboardingStep,
+    last_nudge: NaiveDateTime,
+    reminders_sent: i32,
+    now: NaiveDateTime,
+    interval: chrono::Duration,
+    max_reminders: u32,
+) -> Nudge {
+    if step == OnboardingStep::Done
+        || now - last_nudge < interval
+        || reminders_sent >= max_reminders as i32
+    {
+        Nudge::Wait
+    } else if step.awaits_member() {
+        Nudge::Remind
+    } else {
+        Nudge::Resume
+    }
+}
+
+fn email_button(onboarding: &Onboarding, locale: &str) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(custom_id(onboarding.id())).label(t!(locale, "onboarding.email_button"))
+    ])
+}
+
+/// Sends the member a DM with the wiki email button and stores it.
+async fn send_email_request(
+    http: &Http,
+    onboarding: &mut Onboarding,
+    member: &Member,
+    content: String,
+) -> Result<(), Error> {
+    let discord_id = member
+        .discord_id()
+        .and_then(|id| id.parse::<u64>().ok())
+        .ok_or(Error::NoDiscordAccount)?;
+
+    let dm = UserId::new(discord_id).create_dm_channel(http).await?;
+    let message = dm
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(content)
+                .components(vec![email_button(onboarding, i18n::guild_locale())]),
+        )
+        .await?;
+
+    onboarding.set_dm(dm.id.to_string(), message.id.to_string())?;
+
+    Ok(())
+}
+
+/// Starts the onboarding of a new member and runs its first steps.
+pub(crate) async fn start(http: &Http, member: &Member) -> Result<IntegrationErrors, Error> {
+    let onboarding = Onboarding::start(member.id())?;
+
+    info!("Onboarding of {} started", member.name());
+
+    advance(http, onboarding).await
+}
+
+/// Runs the steps that only need the bot, until the onboarding is done or
+/// waits for the member. Returns the failures of integrations assigning the
+/// member's roles; the onboarding stays on that step until they succeed.
+pub(crate) async fn advance(
+    http: &Http,
+    mut onboarding: Onboarding,
+) -> Result<IntegrationErrors, Error> {
+    // the new member's locale is not known, DMs use the guild's
+    let locale = i18n::guild_locale();
+
+    loop {
+        let member = Member::find_by_id(onboarding.member_id())?;
+
+        let next = match onboarding.step() {
+            OnboardingStep::Invited if member.wiki_id().is_some() => OnboardingStep::WikiLinked,
+            OnboardingStep::Invited => {
+                let result = send_email_request(
+                    http,
+                    &mut onboarding,
+                    &member,
+                    t!(locale, "onboarding.welcome"),
+                )
+                .await;
+
+                if let Err(why) = result {
+                    onboarding.set_last_error(&why)?;
+
+                    return Err(why);
+                }
+
+                OnboardingStep::AwaitingWikiEmail
+            }
+            // an admin may have linked the account with `/member update`
+            OnboardingStep::AwaitingWikiEmail if member.wiki_id().is_some() => {
+                OnboardingStep::WikiLinked
+            }
+            OnboardingStep::AwaitingWikiEmail => return Ok(IntegrationErrors::default()),
+            OnboardingStep::WikiLinked => {
+                let errors = INTEGRATIONS.member_added(http, &member).await;
+
+                if !errors.0.is_empty() {
+                    onboarding.set_last_error(&errors)?;
+
+                    return Ok(errors);
+                }
+
+                OnboardingStep::RolesAssigned
+            }
+            OnboardingStep::RolesAssigned => OnboardingStep::Done,
+            OnboardingStep::Done => return Ok(IntegrationErrors::default()),
+        };
+
+        info!(
+            "Onboarding of {} moved from {:?} to {:?}",
+            member.name(),
+            onboarding.step(),
+            next
+        );
+
+        onboarding = onboarding.set_step(next)?;
+    }
+}
+
+async fn respond_ephemeral(
+    ctx: &serenity::Context,
+    interaction: &ComponentInteraction,
+    content: String,
+) -> Result<(), Error> {
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Handles a click on an onboarding's wiki email button: asks for the email,
+/// links the wiki account and continues the onboarding. Interactions with
+/// other components are ignored.
+pub(crate) async fn handle_interaction(
+    ctx: &serenity::Context,
+    interaction: &ComponentInteraction,
+) -> Result<(), Error> {
+    let Some(id) = parse_custom_id(&interaction.data.custom_id) else {
+        return Ok(());
+    };
+
+    let locale = i18n::guild_locale();
+
+    let pending = match Onboarding::find_by_id(id)? {
+        Some(onboarding) if onboarding.step() == OnboardingStep::AwaitingWikiEmail => {
+            let member = Member::find_by_id(onboarding.member_id())?;
+            let author = interaction.user.id.to_string();
+
+            (member.discord_id() == Some(&author)).then_some((onboarding, member))
+        }
+        _ => None,
+    };
+
+    let Some((mut onboarding, mut member)) = pending else {
+        return respond_ephemeral(ctx, interaction, t!(locale, "onboarding.expired")).await;
+    };
+
+    info!("User {} responded to wiki email modal", member.name());
+
+    // poise needs `AsRef<serenity::Context>`, which `Arc` provides
+    let Some(email) = poise::execute_modal_on_component_interaction::<WikiEmailModal>(
+        Arc::new(ctx.clone()),
+        interaction.clone(),
+        None,
+        Some(MODAL_TIMEOUT),
+    )
+    .await?
+    else {
+        // the button keeps working, the member can try again
+        warn!("User {} did not submit the wiki email modal", member.name());
+        return Ok(());
+    };
+
+    let mut message = interaction.message.clone();
+
+    let mut errors = match create_wiki_account(&ctx.http, &mut member, email.wiki_email).await {
+        Ok(errors) => errors,
+        Err(why) => {
+            error!("Failed to create wiki account: {}", why);
+
+            onboarding.set_last_error(&why)?;
+
+            let content = match why {
+                Error::WikiAccountExists(_) => t!(locale, "onboarding.wiki_exists"),
+                _ => t!(locale, "onboarding.wiki_failed"),
+            };
+
+            let _ = message.edit(ctx, EditMessage::new().content(content)).await;
+
+            return Ok(());
+        }
+    };
+
+    let _ = message
+        .edit(
+            ctx,
+            EditMessage::new()
+                .content(t!(
+                    locale,
+                    "onboarding.wiki_created",
+                    url = SETTINGS.wiki.url
+                ))
+                .components(Vec::new()),
+        )
+        .await;
+
+    let onboarding = onboarding.set_step(OnboardingStep::WikiLinked)?;
+
+    errors.extend(advance(&ctx.http, onboarding).await?);
+
+    // moving to the next step clears the error, so it is stored once the
+    // onboarding went as far as it could
+    if !errors.0.is_empty() {
+        warn!("Onboarding of {} failed: {}", member.name(), errors);
+
+        if let Some(mut onboarding) = Onboarding::find_by_member_id(member.id())? {
+            onboarding.set_last_error(&errors)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a wiki account with the email for the member, links it and
+/// updates the integrations. Discord does not share members' emails with
+/// bots, so nothing proves the email is theirs: an existing account with the
+/// email is never linked, that is left to admins with `/member update`.
+pub(crate) async fn create_wiki_account(
+    http: &Http,
+    member: &mut Member,
+    email: String,
+) -> Result<IntegrationErrors, Error> {
+    let wiki_id = WIKI
+        .create_guest_user(email.clone(), member.name())
+        .await?
+        .ok_or(Error::WikiAccountExists(email))?;
+
+    Member::check_wiki_id_free(wiki_id, Some(member.id()))?;
+
+    let old = member.clone();
+    member.set_wiki_id(wiki_id);
+    member.update()?;
+
+    info!("Wiki account {} linked to {}", wiki_id, member.name());
+
+    Ok(INTEGRATIONS.profile_updated(http, &old, member).await)
+}
+
+/// Continues the onboarding of the member after an admin changed their
+/// profile, e.g. linked their wiki account.
+pub(crate) async fn resume_for_member(
+    http: &Http,
+    member: &Member,
+) -> Result<IntegrationErrors, Error> {
+    match Onboarding::find_by_member_id(member.id())? {
+        Some(onboarding) if onboarding.step() != OnboardingStep::Done => {
+            advance(http, onboarding).await
+        }
+        _ => Ok(IntegrationErrors::default()),
+    }
+}
+
+async fn remind(http: &Http, mut onboarding: Onboarding) -> Result<(), Error> {
+    let member = Member::find_by_id(onboarding.member_id())?;
+    let locale = i18n::guild_locale();
+
+    // the old button keeps working, but the member is pointed at the new one
+    if let Some((channel_id, message_id)) = onboarding.dm() {
+        if let (Ok(channel_id), Ok(message_id)) =
+            (channel_id.parse::<u64>(), message_id.parse::<u64>())
+        {
+            let _ = ChannelId::new(channel_id)
+                .edit_message(http, message_id, EditMessage::new().components(Vec::new()))
+                .await;
+        }
+    }
+
+    send_email_request(
+        http,
+        &mut onboarding,
+        &member,
+        t!(locale, "onboarding.reminder"),
+    )
+    .await?;
+
+    onboarding.record_reminder()?;
+
+    info!("Reminded {} to finish onboarding", member.name());
+
+    Ok(())
+}
+
+/// Nudges every stalled onboarding: reminds members who have not answered
+/// the welcome DM and retries failed steps.
+async fn nudge_stalled(http: &Http) -> Result<(), Error> {
+    let now = chrono::Local::now().naive_local();
+    let interval = chrono::Duration::hours(SETTINGS.onboarding.reminder_interval_hours as i64);
+
+    for onboarding in Onboarding::list_pending()? {
+        let action = nudge(
+            onboarding.step(),
+            onboarding.last_nudge(),
+            onboarding.reminders_sent(),
+            now,
+            interval,
+            SETTINGS.onboarding.max_reminders,
+        );
+
+        let result = match action {
+            Nudge::Wait => continue,
+            Nudge::Remind => remind(http, onboarding.clone()).await,
+            Nudge::Resume => {
+                let mut retried = onboarding.clone();
+                retried.record_reminder()?;
+
+                advance(http, retried).await.map(|_| ())
+            }
+        };
+
+        if let Err(why) = result {
+            error!(
+                "Failed to nudge onboarding {} ({:?}): {}",
+                onboarding.id(),
+                action,
+                why
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Periodically nudges stalled onboardings, unless silent mode is enabled.
+pub(crate) fn spawn_reminder_job(http: Arc<Http>) {
+    let hours = if SETTINGS.onboarding.reminder_interval_hours == 0 {
+        0
+    } else {
+        CHECK_STALLED_EVERY_HOURS
+    };
+
+    jobs::spawn_periodic("Onboarding reminder", hours, true, move || {
+        let http = http.clone();
+        async move { nudge_stalled(&http).await }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+    use uuid::Uuid;
+
+    use super::{custom_id, nudge, parse_custom_id, Nudge};
+    use crate::database::models::onboarding::OnboardingStep;
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, 17)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn custom_id_round_trips() {
+        let id = Uuid::new_v4();
+
+        assert_eq!(parse_custom_id(&custom_id(id)), Some(id));
+        assert_eq!(parse_custom_id("discord_email_button"), None);
+        assert_eq!(parse_custom_id("onboarding:not-a-uuid"), None);
+    }
+
+    #[test]
+    fn recent_onboarding_is_not_nudged() {
+        let step = OnboardingStep::AwaitingWikiEmail;
+
+        assert_eq!(
+            nudge(step, at(10), 0, at(12), Duration::hours(4), 3),
+            Nudge::Wait
+        );
+        assert_eq!(
+            nudge(step, at(10), 0, at(14), Duration::hours(4), 3),
+            Nudge::Remind
+        );
+    }
+
+    #[test]
+    fn automatic_steps_are_resumed() {
+        assert_eq!(
+            nudge(
+                OnboardingStep::WikiLinked,
+                at(1),
+                0,
+                at(12),
+                Duration::hours(4),
+                3
+            ),
+            Nudge::Resume
+        );
+        assert_eq!(
+            nudge(
+                OnboardingStep::Done,
+                at(1),
+                0,
+                at(12),
+                Duration::hours(4),
+                3
+            ),
+            Nudge::Wait
+        );
+    }
+
+    #[test]
+    fn reminders_are_bounded() {
+        assert_eq!(
+            nudge(
+                OnboardingStep::AwaitingWikiEmail,
+                at(1),
+                3,
+                at(12),
+                Duration::hours(4),
+                3
+            ),
+            Nudge::Wait
+        );
+    }
+}
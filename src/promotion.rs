@@ -0,0 +1,232 @@
+//! Promotion of apprentices to members.
+//!
+//! Apprentices are checked against the `promotion` checklist: meetings
+//! attended and reports written since they got their role, and days in the
+//! role. Leads are told once an apprentice completes it, and promote them
+//! with `/member promote`, which changes the role through the integrations
+//! like `/member update` does: the Discord roles are swapped and the wiki
+//! account is moved to the member role's wiki group.
+
+use std::sync::Arc;
+
+use poise::serenity_prelude::{CreateAllowedMentions, CreateMessage, Http};
+use tracing::info;
+
+use crate::{
+    database::models::{meeting::Meeting, member::Member, report::Report, role::Role},
+    error::Error,
+    i18n::{self, t},
+    integrations::{IntegrationErrors, INTEGRATIONS},
+    jobs, settings, silent, SETTINGS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Criterion {
+    Meetings,
+    Reports,
+    DaysInRole,
+}
+
+/// What an apprentice did since they got their role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Progress {
+    pub meetings: i64,
+    pub reports: i64,
+    pub days_in_role: i64,
+}
+
+/// An item of the promotion checklist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Check {
+    pub criterion: Criterion,
+    pub done: i64,
+    pub required: i64,
+}
+
+impl Check {
+    pub(crate) fn is_met(&self) -> bool {
+        self.done >= self.required
+    }
+
+    pub(crate) fn localized(&self, locale: &str) -> String {
+        let key = match self.criterion {
+            Criterion::Meetings => "promotion.meetings",
+            Criterion::Reports => "promotion.reports",
+            Criterion::DaysInRole => "promotion.days_in_role",
+        };
+
+        format!(
+            "{} {}",
+            if self.is_met() { "✅" } else { "❌" },
+            t!(locale, key, done = self.done, required = self.required)
+        )
+    }
+}
+
+/// Compares the apprentice's progress with the checklist.
+pub(crate) fn checklist(progress: &Progress, criteria: &settings::Promotion) -> Vec<Check> {
+    vec![
+        Check {
+            criterion: Criterion::Meetings,
+            done: progress.meetings,
+            required: criteria.min_meetings,
+        },
+        Check {
+            criterion: Criterion::Reports,
+            done: progress.reports,
+            required: criteria.min_reports,
+        },
+        Check {
+            criterion: Criterion::DaysInRole,
+            done: progress.days_in_role,
+            required: criteria.min_days_in_role,
+        },
+    ]
+}
+
+pub(crate) fn is_ready(checks: &[Check]) -> bool {
+    checks.iter().all(Check::is_met)
+}
+
+/// Counts the member's meetings and reports since they got their role.
+pub(crate) fn progress(member: &Member) -> Result<Progress, Error> {
+    let since = member.role_since();
+
+    Ok(Progress {
+        meetings: Meeting::count_attended_since(member.id(), since)?,
+        reports: Report::count_since(member.id(), since)?,
+        days_in_role: (chrono::Local::now().naive_local() - since).num_days(),
+    })
+}
+
+/// Returns the member's promotion checklist.
+pub(crate) fn evaluate(member: &Member) -> Result<Vec<Check>, Error> {
+    Ok(checklist(&progress(member)?, &SETTINGS.promotion))
+}
+
+/// Promotes the apprentice to member, updating their Discord roles and wiki
+/// group through the integrations.
+pub(crate) async fn promote(http: &Http, member: &mut Member) -> Result<IntegrationErrors, Error> {
+    if member.role_id() != Role::APPRENTICE {
+        return Err(Error::NotApprentice(member.name()));
+    }
+
+    let old_role = member.role()?;
+
+    member.set_role(&Role::find(Role::MEMBER)?);
+    *member = member.update()?;
+    member.refresh_activity()?;
+
+    info!("Apprentice promoted to member: {:?}", member);
+
+    Ok(INTEGRATIONS.role_changed(http, member, &old_role).await)
+}
+
+/// Posts the message to the lead channel, mentioning the lead role, unless
+/// silent mode is enabled or no lead channel is configured.
+pub(crate) async fn notify_leads(http: &Http, content: String) -> Result<(), Error> {
+    if silent::is_enabled() {
+        info!("Silent mode, not notifying leads: {}", content);
+        return Ok(());
+    }
+
+    let Some(channel_id) = SETTINGS.discord.lead_channel else {
+        info!("No lead channel, not notifying leads: {}", content);
+        return Ok(());
+    };
+
+    let (content, mentions) = match SETTINGS.discord.lead_role {
+        Some(lead_role) => (
+            format!("<@&{lead_role}> {content}"),
+            CreateAllowedMentions::new().roles(vec![lead_role]),
+        ),
+        None => (content, CreateAllowedMentions::new()),
+    };
+
+    channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(content)
+                .allowed_mentions(mentions),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Tells the leads about the apprentices who completed the checklist, once
+/// per apprentice and role.
+async fn notify_ready(http: &Http) -> Result<(), Error> {
+    let locale = i18n::guild_locale();
+
+    for mut member in Member::list_with_role(Role::APPRENTICE)? {
+        if member.promotion_notified() || !is_ready(&evaluate(&member)?) {
+            continue;
+        }
+
+        notify_leads(http, t!(locale, "promotion.ready", member = member.name())).await?;
+
+        member.set_promotion_notified()?;
+    }
+
+    Ok(())
+}
+
+/// Periodically checks the apprentices, unless silent mode is enabled.
+pub(crate) fn spawn_promotion_job(http: Arc<Http>) {
+    jobs::spawn_periodic(
+        "Promotion check",
+        SETTINGS.promotion.check_interval_hours,
+        true,
+        move || {
+            let http = http.clone();
+            async move { notify_ready(&http).await }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checklist, is_ready, Criterion, Progress};
+    use crate::settings::Promotion;
+
+    fn criteria() -> Promotion {
+        Promotion {
+            min_meetings: 4,
+            min_reports: 2,
+            min_days_in_role: 30,
+            check_interval_hours: 24,
+        }
+    }
+
+    #[test]
+    fn apprentice_meeting_every_criterion_is_ready() {
+        let progress = Progress {
+            meetings: 4,
+            reports: 5,
+            days_in_role: 31,
+        };
+
+        assert!(is_ready(&checklist(&progress, &criteria())));
+    }
+
+    #[test]
+    fn unmet_criteria_are_reported() {
+        let progress = Progress {
+            meetings: 3,
+            reports: 2,
+            days_in_role: 10,
+        };
+
+        let checks = checklist(&progress, &criteria());
+        let unmet = checks
+            .iter()
+            .filter(|check| !check.is_met())
+            .map(|check| check.criterion)
+            .collect::<Vec<_>>();
+
+        assert!(!is_ready(&checks));
+        assert_eq!(unmet, vec![Criterion::Meetings, Criterion::DaysInRole]);
+    }
+}
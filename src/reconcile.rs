@@ -0,0 +1,22 @@
+//! Outcome of fixing a mismatch between the member database and a service,
+//! shared by the wiki group reconciliation and the Discord role audit.
+
+use crate::{error::Error, i18n::t};
+
+#[derive(Debug)]
+pub(crate) enum FixStatus {
+    NotApplied,
+    Fixed,
+    /// Fixes are applied one by one, so the other fixes still go on.
+    Failed(Error),
+}
+
+impl FixStatus {
+    pub(crate) fn localized(&self, locale: &str) -> Option<String> {
+        match self {
+            FixStatus::NotApplied => None,
+            FixStatus::Fixed => Some(t!(locale, "common.fixed")),
+            FixStatus::Failed(why) => Some(t!(locale, "common.fix_failed", error = why)),
+        }
+    }
+}
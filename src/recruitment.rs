@@ -0,0 +1,186 @@
+//! Recruitment through tracked invites.
+//!
+//! `/member invite` creates a Discord invite and stores it with the role its
+//! users get. Discord does not tell which invite a user joined through, so on
+//! every join the uses of the stored invites are compared with the server's
+//! invites; the invite whose uses went up, or which is gone while it had one
+//! use left, is the one the user came through. The user then becomes a member
+//! with the invite's role and their onboarding starts. Invites gone for any
+//! other reason, e.g. revoked in Discord, are closed without a join.
+
+use std::collections::HashMap;
+
+use poise::serenity_prelude::{self as serenity, Http};
+use tracing::{info, warn};
+
+use crate::{
+    database::models::{invite::Invite, member::Member, onboarding::Onboarding},
+    error::Error,
+    onboarding, silent, SETTINGS,
+};
+
+/// Finds the invite a user joined through from the `tracked` invites' codes,
+/// recorded uses and max uses, and the `current` uses of the server's invites
+/// by code. Returns `None` if no invite or more than one invite could have
+/// been used.
+pub(crate) fn find_used_invite(
+    tracked: &[(&str, i32, i32)],
+    current: &HashMap<String, u64>,
+) -> Option<String> {
+    let mut used = tracked
+        .iter()
+        .filter(|(code, uses, max_uses)| match current.get(*code) {
+            Some(&now) => now > *uses as u64,
+            // invites are deleted once used up, but also when revoked
+            None => *max_uses > 0 && uses + 1 == *max_uses,
+        });
+
+    let (code, ..) = used.next()?;
+
+    if used.next().is_some() {
+        return None;
+    }
+
+    Some(code.to_string())
+}
+
+/// Returns the tracked invite the user who just joined came through, and
+/// records the current uses of all tracked invites.
+pub(crate) async fn track_join(http: &Http) -> Result<Option<Invite>, Error> {
+    let tracked = Invite::list_active()?;
+
+    if tracked.is_empty() {
+        return Ok(None);
+    }
+
+    let current = SETTINGS
+        .discord
+        .server_id
+        .invites(http)
+        .await?
+        .into_iter()
+        .map(|invite| (invite.code, invite.uses))
+        .collect::<HashMap<_, _>>();
+
+    let codes = tracked
+        .iter()
+        .map(|invite| (invite.code(), invite.uses(), invite.max_uses()))
+        .collect::<Vec<_>>();
+    let used = find_used_invite(&codes, &current);
+
+    if used.is_none() {
+        warn!("Could not tell which tracked invite was used, if any");
+    }
+
+    let mut used_invite = None;
+
+    for mut invite in tracked {
+        let is_used = used.as_deref() == Some(invite.code());
+
+        let invite = match current.get(invite.code()) {
+            Some(&uses) => invite.set_uses(uses as i32)?,
+            None => invite.close(is_used)?,
+        };
+
+        if is_used {
+            used_invite = Some(invite);
+        }
+    }
+
+    Ok(used_invite)
+}
+
+/// Adds the user who joined through the invite as a member with the invite's
+/// role and starts their onboarding. While silent mode is enabled the welcome
+/// DM is not sent; the reminder job sends it once silent mode is disabled.
+pub(crate) async fn recruit(
+    http: &Http,
+    new_member: &serenity::Member,
+    invite: &Invite,
+) -> Result<Member, Error> {
+    let member = Member::new(
+        new_member.display_name().to_string(),
+        Some(new_member.user.id.to_string()),
+        None,
+        None,
+        invite.role_id(),
+        None,
+    )
+    .insert()?;
+
+    info!(
+        "Member recruited through invite {}: {:?}",
+        invite.code(),
+        member
+    );
+
+    if silent::is_enabled() {
+        Onboarding::start(member.id())?;
+
+        info!("Silent mode, onboarding of {} deferred", member.name());
+    } else {
+        let errors = onboarding::start(http, &member).await?;
+
+        if !errors.0.is_empty() {
+            warn!("Onboarding of {} failed: {}", member.name(), errors);
+        }
+    }
+
+    Ok(member)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::find_used_invite;
+
+    fn current(invites: &[(&str, u64)]) -> HashMap<String, u64> {
+        invites
+            .iter()
+            .map(|(code, uses)| (code.to_string(), *uses))
+            .collect()
+    }
+
+    #[test]
+    fn invite_with_more_uses_was_used() {
+        let tracked = [("abc", 1, 0), ("def", 0, 0)];
+
+        assert_eq!(
+            find_used_invite(&tracked, &current(&[("abc", 1), ("def", 1)])),
+            Some("def".to_string())
+        );
+    }
+
+    #[test]
+    fn used_up_invite_was_used() {
+        let tracked = [("abc", 2, 3), ("def", 0, 0)];
+
+        assert_eq!(
+            find_used_invite(&tracked, &current(&[("def", 0)])),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn revoked_invite_was_not_used() {
+        let tracked = [("abc", 0, 0), ("def", 0, 5)];
+
+        assert_eq!(find_used_invite(&tracked, &current(&[])), None);
+        assert_eq!(
+            find_used_invite(&tracked, &current(&[("def", 1)])),
+            Some("def".to_string())
+        );
+    }
+
+    #[test]
+    fn untracked_or_ambiguous_joins_are_not_matched() {
+        let tracked = [("abc", 0, 0), ("def", 0, 1)];
+
+        assert_eq!(
+            find_used_invite(&tracked, &current(&[("abc", 0), ("def", 0)])),
+            None
+        );
+        assert_eq!(find_used_invite(&tracked, &current(&[("abc", 1)])), None);
+    }
+}
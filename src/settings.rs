@@ -22,9 +22,32 @@ pub struct Settings {
     /// missing from the config.
     #[serde(default = "default_require_presence")]
     pub require_presence: bool,
+    /// Locale of messages addressed to the whole guild, such as summaries,
+    /// and of replies to users whose Discord locale is not supported.
+    /// Defaults to `pl`.
+    #[serde(default = "default_locale")]
+    pub locale: String,
     pub meeting: Meeting,
     pub discord: Discord,
     pub wiki: Wiki,
+    #[serde(default)]
+    pub integrations: Integrations,
+    /// Trello board members are invited to. The Trello integration is
+    /// disabled when missing.
+    #[serde(default)]
+    pub trello: Option<Trello>,
+    #[serde(default)]
+    pub onboarding: Onboarding,
+    #[serde(default)]
+    pub offboarding: Offboarding,
+    #[serde(default)]
+    pub promotion: Promotion,
+    #[serde(default)]
+    pub activity_sources: ActivitySources,
+    #[serde(default)]
+    pub inactivity: Inactivity,
+    #[serde(default)]
+    pub leave: Leave,
 }
 
 fn default_silent_mode() -> bool {
@@ -35,19 +58,66 @@ fn default_require_presence() -> bool {
     true
 }
 
+fn default_locale() -> String {
+    "pl".to_string()
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Meeting {
     pub channel_id: ChannelId,
     pub cron: String,
+    /// How many hours after a meeting ends reports still count towards its
+    /// summary. Reports written within that window are added to the already
+    /// sent summary, which is then edited. Defaults to 2 hours.
+    #[serde(default = "default_report_cutoff_hours")]
+    pub report_cutoff_hours: i64,
+}
+
+fn default_report_cutoff_hours() -> i64 {
+    2
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Discord {
     pub token: String,
-    pub member_role: RoleId,
-    pub apprentice_role: RoleId,
+    /// Discord role of the `Member` role, used to fill in the roles table
+    /// when the role has none. Deprecated: use `/role update` instead.
+    #[serde(default)]
+    pub member_role: Option<RoleId>,
+    /// Discord role of the `Apprentice` role, used like `member_role`.
+    #[serde(default)]
+    pub apprentice_role: Option<RoleId>,
+    /// Role allowed to manage other members' reports, including reports
+    /// locked by an already published summary. Roles granting the
+    /// `ManageReports` permission allow the same.
+    #[serde(default)]
+    pub lead_role: Option<RoleId>,
     pub summary_channel: ChannelId,
     pub server_id: GuildId,
+    /// How often, in hours, the guild members' roles are compared with the
+    /// database. `0` disables the check. Defaults to 24 hours.
+    #[serde(default = "default_audit_interval_hours")]
+    pub audit_interval_hours: u64,
+    /// Channel where admins are told about members leaving and joining the
+    /// server, and offered to reinstate ex-members and rename members.
+    #[serde(default)]
+    pub admin_channel: Option<ChannelId>,
+    /// Whether members leaving the server are offboarded, instead of being
+    /// flagged for review. Defaults to `false`.
+    #[serde(default)]
+    pub offboard_on_leave: bool,
+    /// Channel new members land in when they join through an invite created
+    /// with `/member invite`. Defaults to the channel the command is used in.
+    #[serde(default)]
+    pub invite_channel: Option<ChannelId>,
+    /// Channel where leads are told about apprentices ready to be promoted.
+    /// `lead_role` is mentioned when set.
+    #[serde(default)]
+    pub lead_channel: Option<ChannelId>,
+}
+
+fn default_audit_interval_hours() -> u64 {
+    24
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -56,8 +126,373 @@ pub struct Wiki {
     pub graphql: String,
     pub token: String,
     pub provider_key: String,
-    pub member_group_id: i64,
+    /// Wiki group of the `Member` and `Apprentice` roles, used to fill in the
+    /// roles table when they have none. Deprecated: use `/role update`
+    /// instead.
+    #[serde(default)]
+    pub member_group_id: Option<i64>,
+    /// Wiki group of accounts whose role has no wiki group, such as
+    /// ex-members.
     pub guest_group_id: i64,
+    /// Path under which meeting summaries are published, without leading or
+    /// trailing slashes. Defaults to `meetings`.
+    #[serde(default = "default_summaries_path")]
+    pub summaries_path: String,
+    /// Locale of the pages created on the wiki. Defaults to `en`.
+    #[serde(default = "default_wiki_locale")]
+    pub locale: String,
+    /// How often, in hours, wiki group membership is compared with the
+    /// database. `0` disables the check. Defaults to 24 hours.
+    #[serde(default = "default_reconcile_interval_hours")]
+    pub reconcile_interval_hours: u64,
+    /// Whether the periodic check fixes the mismatches it finds, instead of
+    /// only logging them. Defaults to `false`.
+    #[serde(default)]
+    pub reconcile_auto_fix: bool,
+    /// Timeout of a single request to the wiki, in seconds. Defaults to 10.
+    #[serde(default = "default_wiki_timeout_secs")]
+    pub timeout_secs: u64,
+    /// How many times a request failing with a transient error (timeout,
+    /// connection or server error) is retried. Creating users and pages is
+    /// only retried on connection errors. Defaults to 3.
+    #[serde(default = "default_wiki_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_summaries_path() -> String {
+    "meetings".to_string()
+}
+
+fn default_wiki_locale() -> String {
+    "en".to_string()
+}
+
+fn default_reconcile_interval_hours() -> u64 {
+    24
+}
+
+fn default_wiki_timeout_secs() -> u64 {
+    10
+}
+
+fn default_wiki_max_retries() -> u32 {
+    3
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Trello {
+    #[serde(default = "default_trello_api_url")]
+    pub api_url: String,
+    pub key: String,
+    pub token: String,
+    pub board_id: String,
+    /// Timeout of a single request to Trello, in seconds. Defaults to 10.
+    #[serde(default = "default_trello_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_trello_api_url() -> String {
+    "https://api.trello.com/1".to_string()
+}
+
+fn default_trello_timeout_secs() -> u64 {
+    10
+}
+
+/// External services kept in sync with the members. All are enabled by
+/// default.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Integrations {
+    /// Give members the Discord role matching their role.
+    #[serde(default = "default_integration_enabled")]
+    pub discord: bool,
+    /// Keep wiki accounts in the group matching the member's role, and their
+    /// profiles in sync.
+    #[serde(default = "default_integration_enabled")]
+    pub wiki: bool,
+    /// Invite members to the Trello board and mirror their reports on their
+    /// report cards. Requires the `trello` section.
+    #[serde(default = "default_integration_enabled")]
+    pub trello: bool,
+}
+
+impl Default for Integrations {
+    fn default() -> Self {
+        Integrations {
+            discord: default_integration_enabled(),
+            wiki: default_integration_enabled(),
+            trello: default_integration_enabled(),
+        }
+    }
+}
+
+fn default_integration_enabled() -> bool {
+    true
+}
+
+/// Reminders for onboardings that stalled.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Onboarding {
+    /// Hours between reminders of a stalled onboarding step. `0` disables
+    /// reminders. Defaults to 24.
+    #[serde(default = "default_reminder_interval_hours")]
+    pub reminder_interval_hours: u64,
+    /// Reminders sent for a single step before giving up. Defaults to 3.
+    #[serde(default = "default_max_reminders")]
+    pub max_reminders: u32,
+}
+
+impl Default for Onboarding {
+    fn default() -> Self {
+        Onboarding {
+            reminder_interval_hours: default_reminder_interval_hours(),
+            max_reminders: default_max_reminders(),
+        }
+    }
+}
+
+fn default_reminder_interval_hours() -> u64 {
+    24
+}
+
+fn default_max_reminders() -> u32 {
+    3
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Offboarding {
+    /// Steps admins have to do by hand when a member leaves, listed after
+    /// `/member remove`.
+    #[serde(default = "default_manual_steps")]
+    pub manual_steps: Vec<String>,
+}
+
+impl Default for Offboarding {
+    fn default() -> Self {
+        Offboarding {
+            manual_steps: default_manual_steps(),
+        }
+    }
+}
+
+fn default_manual_steps() -> Vec<String> {
+    vec![
+        "Revoke access to the code repositories".to_string(),
+        "Collect the equipment lent from the asset store".to_string(),
+    ]
+}
+
+/// Checklist an apprentice has to complete before being promoted to member.
+/// Meetings and reports are counted since the apprentice got their role.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Promotion {
+    /// Meetings attended. Defaults to 4.
+    #[serde(default = "default_min_meetings")]
+    pub min_meetings: i64,
+    /// Reports written. Defaults to 4.
+    #[serde(default = "default_min_reports")]
+    pub min_reports: i64,
+    /// Days as an apprentice. Defaults to 30.
+    #[serde(default = "default_min_days_in_role")]
+    pub min_days_in_role: i64,
+    /// How often, in hours, apprentices are checked and leads told about the
+    /// ones ready to be promoted. `0` disables the check. Defaults to 24.
+    #[serde(default = "default_promotion_check_interval_hours")]
+    pub check_interval_hours: u64,
+}
+
+impl Default for Promotion {
+    fn default() -> Self {
+        Promotion {
+            min_meetings: default_min_meetings(),
+            min_reports: default_min_reports(),
+            min_days_in_role: default_min_days_in_role(),
+            check_interval_hours: default_promotion_check_interval_hours(),
+        }
+    }
+}
+
+fn default_min_meetings() -> i64 {
+    4
+}
+
+fn default_min_reports() -> i64 {
+    4
+}
+
+fn default_min_days_in_role() -> i64 {
+    30
+}
+
+fn default_promotion_check_interval_hours() -> u64 {
+    24
+}
+
+/// What counts as a member's activity. A member is active on a day when the
+/// weights of their activity that day add up to at least 1. Disabled sources
+/// are not recorded, or not taken into account if they were before.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ActivitySources {
+    /// Reports written. Enabled with weight 1 by default.
+    #[serde(default)]
+    pub reports: ActivitySource,
+    /// Meetings attended. Enabled with weight 1 by default.
+    #[serde(default)]
+    pub meetings: ActivitySource,
+    /// Messages sent in the listed channels. Disabled by default.
+    #[serde(default = "default_disabled_source")]
+    pub messages: ActivitySource,
+    /// Time spent in the listed voice channels. Disabled by default.
+    #[serde(default = "default_disabled_source")]
+    pub voice: ActivitySource,
+}
+
+impl Default for ActivitySources {
+    fn default() -> Self {
+        ActivitySources {
+            reports: ActivitySource::default(),
+            meetings: ActivitySource::default(),
+            messages: default_disabled_source(),
+            voice: default_disabled_source(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ActivitySource {
+    #[serde(default = "default_source_enabled")]
+    pub enabled: bool,
+    /// Weight of every message, voice session, report or meeting. Defaults
+    /// to 1.
+    #[serde(default = "default_source_weight")]
+    pub weight: f64,
+    /// Channels whose messages or voice sessions count. Only used by the
+    /// `messages` and `voice` sources.
+    #[serde(default)]
+    pub channels: Vec<ChannelId>,
+    /// Minimum length of a voice session to count. Only used by the `voice`
+    /// source. Defaults to 15 minutes.
+    #[serde(default = "default_source_min_minutes")]
+    pub min_minutes: i64,
+}
+
+impl Default for ActivitySource {
+    fn default() -> Self {
+        ActivitySource {
+            enabled: default_source_enabled(),
+            weight: default_source_weight(),
+            channels: Vec::new(),
+            min_minutes: default_source_min_minutes(),
+        }
+    }
+}
+
+fn default_disabled_source() -> ActivitySource {
+    ActivitySource {
+        enabled: false,
+        ..ActivitySource::default()
+    }
+}
+
+fn default_source_enabled() -> bool {
+    true
+}
+
+fn default_source_weight() -> f64 {
+    1.0
+}
+
+fn default_source_min_minutes() -> i64 {
+    15
+}
+
+/// Escalation of members' inactivity, counted in days since their last
+/// activity or since they got their role, whichever is later. `0` disables a
+/// step.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Inactivity {
+    /// Days after which the member is reminded by DM. Defaults to 30.
+    #[serde(default = "default_warn_after_days")]
+    pub warn_after_days: i64,
+    /// Days after which the leads are told in `discord.lead_channel`.
+    /// Defaults to 45.
+    #[serde(default = "default_notify_leads_after_days")]
+    pub notify_leads_after_days: i64,
+    /// Days after which admins are offered to offboard the member, or the
+    /// member is offboarded with `auto_offboard`. Defaults to 60.
+    #[serde(default = "default_offboard_after_days")]
+    pub offboard_after_days: i64,
+    /// Whether inactive members are offboarded without asking the admins.
+    /// Defaults to `false`.
+    #[serde(default)]
+    pub auto_offboard: bool,
+    /// How often, in hours, members are checked for inactivity. `0` disables
+    /// the escalation. Defaults to 24.
+    #[serde(default = "default_inactivity_check_interval_hours")]
+    pub check_interval_hours: u64,
+}
+
+impl Default for Inactivity {
+    fn default() -> Self {
+        Inactivity {
+            warn_after_days: default_warn_after_days(),
+            notify_leads_after_days: default_notify_leads_after_days(),
+            offboard_after_days: default_offboard_after_days(),
+            auto_offboard: false,
+            check_interval_hours: default_inactivity_check_interval_hours(),
+        }
+    }
+}
+
+fn default_warn_after_days() -> i64 {
+    30
+}
+
+fn default_notify_leads_after_days() -> i64 {
+    45
+}
+
+fn default_offboard_after_days() -> i64 {
+    60
+}
+
+fn default_inactivity_check_interval_hours() -> u64 {
+    24
+}
+
+/// Leaves of absence, which do not count towards members' inactivity.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Leave {
+    /// Channel where members coming back from their leave are announced.
+    /// Defaults to `discord.summary_channel`.
+    #[serde(default)]
+    pub announcement_channel: Option<ChannelId>,
+    /// How often, in hours, ended leaves are checked to announce the
+    /// members' return. `0` disables the announcements. Defaults to 24.
+    #[serde(default = "default_leave_check_interval_hours")]
+    pub check_interval_hours: u64,
+    /// Longest leave, in days, members can add themselves. Longer ones are
+    /// added by admins. `0` disables the limit. Defaults to 60.
+    #[serde(default = "default_leave_max_days")]
+    pub max_days: i64,
+}
+
+impl Default for Leave {
+    fn default() -> Self {
+        Leave {
+            announcement_channel: None,
+            check_interval_hours: default_leave_check_interval_hours(),
+            max_days: default_leave_max_days(),
+        }
+    }
+}
+
+fn default_leave_check_interval_hours() -> u64 {
+    24
+}
+
+fn default_leave_max_days() -> i64 {
+    60
 }
 
 impl Settings {
@@ -0,0 +1,360 @@
+//! Importing historical reports from a Trello board JSON export.
+//!
+//! Reports used to be written as comments on the members' report cards. The
+//! importer matches each comment's card to a member by
+//! `trello_report_card_id` and keeps only comments written by that member
+//! (matched by `trello_id`, members without one are skipped). Imported
+//! reports keep the comment's date and ID, so running the import again skips
+//! them, and are marked as published so they do not end up in the next
+//! summary. They are never mirrored, so editing or removing them leaves the
+//! original comment untouched.
+//!
+//! With `--apply` all reports are imported in one transaction.
+//!
+//! ```text
+//! octobot import-trello <export.json> [--apply]
+//! ```
+//!
+//! Without `--apply` the importer only prints what it would do.
+
+use std::{collections::HashSet, fmt::Display, path::Path};
+
+use chrono::{DateTime, NaiveDateTime};
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    database::models::{member::Member, report::Report},
+    error::Error,
+};
+
+/// The part of a board export the importer reads.
+#[derive(Debug, Deserialize)]
+pub(crate) struct BoardExport {
+    #[serde(default)]
+    actions: Vec<ExportAction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportAction {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    id_member_creator: Option<String>,
+    date: String,
+    #[serde(default)]
+    data: ActionData,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ActionData {
+    text: Option<String>,
+    card: Option<ActionCard>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActionCard {
+    id: String,
+}
+
+/// A member with a report card.
+#[derive(Debug, Clone)]
+pub(crate) struct CardOwner {
+    pub member_id: Uuid,
+    pub name: String,
+    pub card_id: String,
+    pub trello_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImportedReport {
+    pub member_id: Uuid,
+    pub member: String,
+    pub card_id: String,
+    pub comment_id: String,
+    pub date: NaiveDateTime,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SkipReason {
+    /// The card is not any member's report card.
+    UnknownCard,
+    /// The card's member has no `trello_id`, so the comment's author cannot
+    /// be checked.
+    UnknownAuthor,
+    /// The comment was written by someone other than the card's member.
+    OtherAuthor,
+    AlreadyImported,
+    Empty,
+    InvalidDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ImportEntry {
+    Import(ImportedReport),
+    Skip {
+        comment_id: String,
+        reason: SkipReason,
+    },
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            SkipReason::UnknownCard => "not on a member's report card",
+            SkipReason::UnknownAuthor => "the card's member has no Trello ID",
+            SkipReason::OtherAuthor => "not written by the card's member",
+            SkipReason::AlreadyImported => "already imported",
+            SkipReason::Empty => "empty comment",
+            SkipReason::InvalidDate => "invalid date",
+        };
+
+        write!(f, "{reason}")
+    }
+}
+
+impl Display for ImportEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportEntry::Import(report) => write!(
+                f,
+                "import {} by {} on {}",
+                report.comment_id,
+                report.member,
+                report.date.format("%Y-%m-%d %H:%M")
+            ),
+            ImportEntry::Skip { comment_id, reason } => write!(f, "skip {comment_id}: {reason}"),
+        }
+    }
+}
+
+/// Decides what to do with every comment in the export. `imported` holds the
+/// IDs of comments that were already imported.
+pub(crate) fn plan_import(
+    export: &BoardExport,
+    owners: &[CardOwner],
+    imported: &HashSet<String>,
+) -> Vec<ImportEntry> {
+    export
+        .actions
+        .iter()
+        .filter(|action| action.kind == "commentCard")
+        .map(|action| {
+            let skip = |reason| ImportEntry::Skip {
+                comment_id: action.id.clone(),
+                reason,
+            };
+
+            let Some(owner) = action
+                .data
+                .card
+                .as_ref()
+                .and_then(|card| owners.iter().find(|owner| owner.card_id == card.id))
+            else {
+                return skip(SkipReason::UnknownCard);
+            };
+
+            let Some(trello_id) = owner.trello_id.as_deref() else {
+                return skip(SkipReason::UnknownAuthor);
+            };
+
+            if action.id_member_creator.as_deref() != Some(trello_id) {
+                return skip(SkipReason::OtherAuthor);
+            }
+
+            if imported.contains(&action.id) {
+                return skip(SkipReason::AlreadyImported);
+            }
+
+            let content = action.data.text.as_deref().unwrap_or_default().trim();
+            if content.is_empty() {
+                return skip(SkipReason::Empty);
+            }
+
+            let Ok(date) = DateTime::parse_from_rfc3339(&action.date) else {
+                return skip(SkipReason::InvalidDate);
+            };
+
+            ImportEntry::Import(ImportedReport {
+                member_id: owner.member_id,
+                member: owner.name.clone(),
+                card_id: owner.card_id.clone(),
+                comment_id: action.id.clone(),
+                date: date.with_timezone(&chrono::Local).naive_local(),
+                content: content.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Reads the export and prints the planned import. When `apply` is set, the
+/// reports are created and every member's activity is refreshed.
+pub(crate) fn import(path: &Path, apply: bool) -> Result<Vec<ImportEntry>, Error> {
+    let export: BoardExport = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+
+    let owners = Member::list_with_report_card()?
+        .into_iter()
+        .filter_map(|member| {
+            Some(CardOwner {
+                member_id: member.id(),
+                name: member.name(),
+                card_id: member.trello_report_card_id()?.to_string(),
+                trello_id: member.trello_id().map(str::to_string),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let entries = plan_import(&export, &owners, &Report::trello_comment_ids()?);
+
+    if apply {
+        let reports = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                ImportEntry::Import(report) => Some(report),
+                ImportEntry::Skip { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        Report::import(&reports)?;
+
+        Member::refresh_all_activities()?;
+
+        info!("Imported reports from {}", path.display());
+    }
+
+    Ok(entries)
+}
+
+/// Runs the `import-trello` command with its arguments.
+pub(crate) fn run(args: &[String]) -> Result<(), Error> {
+    let apply = args.iter().any(|arg| arg == "--apply");
+    let Some(path) = args.iter().find(|arg| !arg.starts_with("--")) else {
+        eprintln!("Usage: octobot import-trello <export.json> [--apply]");
+        return Ok(());
+    };
+
+    let entries = import(Path::new(path), apply)?;
+
+    for entry in &entries {
+        println!("{entry}");
+    }
+
+    let count = entries
+        .iter()
+        .filter(|entry| matches!(entry, ImportEntry::Import(_)))
+        .count();
+
+    if apply {
+        println!("Imported {count} of {} comments", entries.len());
+    } else {
+        println!(
+            "Would import {count} of {} comments, run again with --apply to import them",
+            entries.len()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::{plan_import, BoardExport, CardOwner, ImportEntry, SkipReason};
+
+    fn owner() -> CardOwner {
+        CardOwner {
+            member_id: Uuid::nil(),
+            name: "Alice".to_string(),
+            card_id: "card".to_string(),
+            trello_id: Some("alice".to_string()),
+        }
+    }
+
+    fn comment(id: &str, author: &str, card: &str, text: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "type": "commentCard",
+            "idMemberCreator": author,
+            "date": "2021-03-04T18:30:00.000Z",
+            "data": { "text": text, "card": { "id": card, "name": "Alice" } }
+        })
+    }
+
+    fn export(actions: Vec<serde_json::Value>) -> BoardExport {
+        serde_json::from_value(json!({ "id": "board", "actions": actions })).unwrap()
+    }
+
+    fn reasons(entries: &[ImportEntry]) -> Vec<Option<SkipReason>> {
+        entries
+            .iter()
+            .map(|entry| match entry {
+                ImportEntry::Import(_) => None,
+                ImportEntry::Skip { reason, .. } => Some(*reason),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn members_comments_are_imported() {
+        let export = export(vec![comment("1", "alice", "card", " Did things \n")]);
+
+        let entries = plan_import(&export, &[owner()], &HashSet::new());
+
+        let [ImportEntry::Import(report)] = entries.as_slice() else {
+            panic!("expected an import, got {entries:?}");
+        };
+        assert_eq!(report.comment_id, "1");
+        assert_eq!(report.content, "Did things");
+        assert_eq!(report.member, "Alice");
+    }
+
+    #[test]
+    fn unmatched_comments_are_skipped() {
+        let export = export(vec![
+            comment("1", "alice", "other", "Text"),
+            comment("2", "bob", "card", "Text"),
+            comment("3", "alice", "card", "Text"),
+            comment("4", "alice", "card", "  "),
+            json!({ "id": "5", "type": "createCard", "date": "2021-03-04T18:30:00.000Z" }),
+        ]);
+
+        let entries = plan_import(&export, &[owner()], &HashSet::from(["3".to_string()]));
+
+        assert_eq!(
+            reasons(&entries),
+            vec![
+                Some(SkipReason::UnknownCard),
+                Some(SkipReason::OtherAuthor),
+                Some(SkipReason::AlreadyImported),
+                Some(SkipReason::Empty),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_are_skipped_without_trello_id() {
+        let export = export(vec![
+            comment("1", "alice", "card", "Text"),
+            comment("2", "bob", "card", "Text"),
+        ]);
+        let owner = CardOwner {
+            trello_id: None,
+            ..owner()
+        };
+
+        assert_eq!(
+            reasons(&plan_import(&export, &[owner], &HashSet::new())),
+            vec![
+                Some(SkipReason::UnknownAuthor),
+                Some(SkipReason::UnknownAuthor)
+            ]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Trello integration.
+//!
+//! Members are invited to the organization's board, and every report is
+//! mirrored as a comment on the member's report card. All requests go
+//! through [`TrelloClient`]; [`TRELLO`] is the client configured from the
+//! settings, or `None` when the `trello` section is missing.
+
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use reqwest::{header, Method, StatusCode};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::SETTINGS;
+
+pub(crate) mod import;
+pub(crate) mod reports;
+#[cfg(test)]
+mod tests;
+
+lazy_static! {
+    pub static ref TRELLO: Option<TrelloClient> = SETTINGS.trello.as_ref().map(|trello| {
+        TrelloClient::new(
+            trello.api_url.clone(),
+            &trello.key,
+            &trello.token,
+            Duration::from_secs(trello.timeout_secs),
+        )
+        .expect("Unable to create the Trello client")
+    });
+}
+
+#[derive(Error, Debug)]
+pub enum TrelloError {
+    #[error("Trello reqwest error: {source:?}")]
+    ReqwestError { source: reqwest::Error },
+    #[error("Trello responded with HTTP status {status}")]
+    HttpStatus { status: StatusCode },
+    #[error("Trello rejected the API key or token")]
+    Unauthorized,
+    #[error("Trello object not found")]
+    NotFound,
+}
+
+impl From<reqwest::Error> for TrelloError {
+    fn from(source: reqwest::Error) -> Self {
+        TrelloError::ReqwestError { source }
+    }
+}
+
+/// An action on a card, e.g. a comment.
+#[derive(Debug, Deserialize)]
+struct Action {
+    id: String,
+}
+
+/// Client of the Trello REST API.
+#[derive(Debug, Clone)]
+pub struct TrelloClient {
+    http: reqwest::Client,
+    api_url: String,
+}
+
+impl TrelloClient {
+    /// Creates a client of the API at `api_url`, e.g.
+    /// `https://api.trello.com/1`.
+    pub fn new(
+        api_url: impl Into<String>,
+        key: &str,
+        token: &str,
+        timeout: Duration,
+    ) -> Result<Self, TrelloError> {
+        let mut headers = header::HeaderMap::new();
+
+        // passing the credentials in a header keeps them out of the URLs
+        // included in errors and logs
+        let mut auth_value = header::HeaderValue::from_str(&format!(
+            "OAuth oauth_consumer_key=\"{key}\", oauth_token=\"{token}\""
+        ))
+        .map_err(|_| TrelloError::Unauthorized)?;
+        auth_value.set_sensitive(true);
+        headers.insert(header::AUTHORIZATION, auth_value);
+
+        let http = reqwest::Client::builder()
+            .user_agent("octobot/".to_owned() + env!("CARGO_PKG_VERSION"))
+            .default_headers(headers)
+            .timeout(timeout)
+            .build()?;
+
+        Ok(TrelloClient {
+            http,
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+        })
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<reqwest::Response, TrelloError> {
+        let response = self
+            .http
+            .request(method, format!("{}{path}", self.api_url))
+            .query(query)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(TrelloError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(TrelloError::NotFound),
+            status if !status.is_success() => Err(TrelloError::HttpStatus { status }),
+            _ => Ok(response),
+        }
+    }
+
+    /// Adds the Trello user to the board as a normal member.
+    pub async fn add_board_member(
+        &self,
+        board_id: &str,
+        member_id: &str,
+    ) -> Result<(), TrelloError> {
+        self.send(
+            Method::PUT,
+            &format!("/boards/{board_id}/members/{member_id}"),
+            &[("type", "normal")],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes the Trello user from the board. Succeeds if they are not on
+    /// the board.
+    pub async fn remove_board_member(
+        &self,
+        board_id: &str,
+        member_id: &str,
+    ) -> Result<(), TrelloError> {
+        match self
+            .send(
+                Method::DELETE,
+                &format!("/boards/{board_id}/members/{member_id}"),
+                &[],
+            )
+            .await
+        {
+            Ok(_) | Err(TrelloError::NotFound) => Ok(()),
+            Err(why) => Err(why),
+        }
+    }
+
+    /// Comments on the card. Returns the comment's ID.
+    pub async fn add_comment(&self, card_id: &str, text: &str) -> Result<String, TrelloError> {
+        let action: Action = self
+            .send(
+                Method::POST,
+                &format!("/cards/{card_id}/actions/comments"),
+                &[("text", text)],
+            )
+            .await?
+            .json()
+            .await?;
+
+        Ok(action.id)
+    }
+
+    pub async fn update_comment(
+        &self,
+        card_id: &str,
+        comment_id: &str,
+        text: &str,
+    ) -> Result<(), TrelloError> {
+        self.send(
+            Method::PUT,
+            &format!("/cards/{card_id}/actions/{comment_id}/comments"),
+            &[("text", text)],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the comment. Succeeds if it was already deleted.
+    pub async fn delete_comment(&self, card_id: &str, comment_id: &str) -> Result<(), TrelloError> {
+        match self
+            .send(
+                Method::DELETE,
+                &format!("/cards/{card_id}/actions/{comment_id}/comments"),
+                &[],
+            )
+            .await
+        {
+            Ok(_) | Err(TrelloError::NotFound) => Ok(()),
+            Err(why) => Err(why),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Mirroring reports as comments on the members' report cards.
+//!
+//! Every report is mirrored by one comment on its member's report card. The
+//! card and comment are stored with the report, so editing the report edits
+//! the comment, and moving it to another member moves the comment to their
+//! card. Reports imported from Trello are left out, their comments are the
+//! members' own.
+
+use super::{TrelloError, TRELLO};
+use crate::{
+    database::models::{member::Member, report::Report},
+    error::Error,
+    i18n::{self, t},
+    SETTINGS,
+};
+
+/// Change needed to make the report's comment match its member's card.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum MirrorAction<'a> {
+    /// The report is not mirrored and its member has no report card.
+    Nothing,
+    Create {
+        card: &'a str,
+    },
+    Update {
+        card: &'a str,
+        comment: &'a str,
+    },
+    /// The report was moved to a member with another report card.
+    Move {
+        from: &'a str,
+        comment: &'a str,
+        to: &'a str,
+    },
+    /// The report was moved to a member without a report card.
+    Remove {
+        card: &'a str,
+        comment: &'a str,
+    },
+}
+
+/// Compares the report's current comment, as `(card, comment)`, with the
+/// report card it should be on.
+pub(crate) fn mirror_action<'a>(
+    current: Option<(&'a str, &'a str)>,
+    card: Option<&'a str>,
+) -> MirrorAction<'a> {
+    match (current, card) {
+        (None, None) => MirrorAction::Nothing,
+        (None, Some(card)) => MirrorAction::Create { card },
+        (Some((from, comment)), Some(to)) if from == to => {
+            MirrorAction::Update { card: to, comment }
+        }
+        (Some((from, comment)), Some(to)) => MirrorAction::Move { from, comment, to },
+        (Some((card, comment)), None) => MirrorAction::Remove { card, comment },
+    }
+}
+
+fn comment_text(report: &Report) -> String {
+    t!(
+        i18n::guild_locale(),
+        "trello.report_comment",
+        date = report.create_date.format("%Y-%m-%d %H:%M"),
+        content = report.content
+    )
+}
+
+/// Creates, updates or moves the comment mirroring the report. Does nothing
+/// when Trello is not configured or its integration is disabled, or for
+/// imported reports.
+pub(crate) async fn mirror_report(report: &mut Report) -> Result<(), Error> {
+    let Some(trello) = TRELLO.as_ref().filter(|_| SETTINGS.integrations.trello) else {
+        return Ok(());
+    };
+
+    if report.imported() {
+        return Ok(());
+    }
+
+    let member = Member::find_by_id(report.member_id)?;
+    let text = comment_text(report);
+
+    let comment = match mirror_action(report.trello_comment(), member.trello_report_card_id()) {
+        MirrorAction::Nothing => return Ok(()),
+        MirrorAction::Create { card } => Some((card, trello.add_comment(card, &text).await?)),
+        MirrorAction::Update { card, comment } => {
+            match trello.update_comment(card, comment, &text).await {
+                Ok(()) => return Ok(()),
+                // the comment was deleted on Trello
+                Err(TrelloError::NotFound) => Some((card, trello.add_comment(card, &text).await?)),
+                Err(why) => return Err(why.into()),
+            }
+        }
+        MirrorAction::Move { from, comment, to } => {
+            trello.delete_comment(from, comment).await?;
+
+            Some((to, trello.add_comment(to, &text).await?))
+        }
+        MirrorAction::Remove { card, comment } => {
+            trello.delete_comment(card, comment).await?;
+
+            None
+        }
+    }
+    .map(|(card, comment)| (card.to_string(), comment));
+
+    report.set_trello_comment(comment)?;
+
+    Ok(())
+}
+
+/// Deletes the comment mirroring the report, if there is one.
+pub(crate) async fn unmirror_report(report: &Report) -> Result<(), Error> {
+    let (Some(trello), Some((card, comment)), false) = (
+        TRELLO.as_ref().filter(|_| SETTINGS.integrations.trello),
+        report.trello_comment(),
+        report.imported(),
+    ) else {
+        return Ok(());
+    };
+
+    Ok(trello.delete_comment(card, comment).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mirror_action, MirrorAction};
+
+    #[test]
+    fn unmirrored_report_is_commented() {
+        assert_eq!(
+            mirror_action(None, Some("card")),
+            MirrorAction::Create { card: "card" }
+        );
+        assert_eq!(mirror_action(None, None), MirrorAction::Nothing);
+    }
+
+    #[test]
+    fn comment_on_same_card_is_updated() {
+        assert_eq!(
+            mirror_action(Some(("card", "comment")), Some("card")),
+            MirrorAction::Update {
+                card: "card",
+                comment: "comment"
+            }
+        );
+    }
+
+    #[test]
+    fn comment_follows_report_to_another_member() {
+        assert_eq!(
+            mirror_action(Some(("old", "comment")), Some("new")),
+            MirrorAction::Move {
+                from: "old",
+                comment: "comment",
+                to: "new"
+            }
+        );
+        assert_eq!(
+            mirror_action(Some(("old", "comment")), None),
+            MirrorAction::Remove {
+                card: "old",
+                comment: "comment"
+            }
+        );
+    }
+}
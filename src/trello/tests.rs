@@ -0,0 +1,126 @@
+//! Tests of [`TrelloClient`] against a mock REST server.
+
+use std::time::Duration;
+
+use serde_json::json;
+use wiremock::{
+    matchers::{header_regex, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use super::{TrelloClient, TrelloError};
+
+fn client(server: &MockServer) -> TrelloClient {
+    TrelloClient::new(server.uri(), "key", "token", Duration::from_millis(200)).unwrap()
+}
+
+#[tokio::test]
+async fn credentials_are_sent_in_header() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/boards/board/members/alice"))
+        .and(query_param("type", "normal"))
+        .and(header_regex(
+            "authorization",
+            r#"^OAuth oauth_consumer_key="key", oauth_token="token"$"#,
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "board" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server)
+        .add_board_member("board", "alice")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn rejected_token_is_unauthorized() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let err = client(&server)
+        .add_board_member("board", "alice")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, TrelloError::Unauthorized));
+}
+
+#[tokio::test]
+async fn removing_absent_member_succeeds() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("DELETE"))
+        .and(path("/boards/board/members/alice"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server)
+        .remove_board_member("board", "alice")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn comment_returns_its_id() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/cards/card/actions/comments"))
+        .and(query_param("text", "Report"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "id": "comment", "type": "commentCard" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let id = client(&server).add_comment("card", "Report").await.unwrap();
+
+    assert_eq!(id, "comment");
+}
+
+#[tokio::test]
+async fn updating_deleted_comment_is_not_found() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/cards/card/actions/comment/comments"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let err = client(&server)
+        .update_comment("card", "comment", "Report")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, TrelloError::NotFound));
+}
+
+#[tokio::test]
+async fn server_error_is_reported() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let err = client(&server)
+        .delete_comment("card", "comment")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, TrelloError::HttpStatus { status } if status.as_u16() == 500));
+}
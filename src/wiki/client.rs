@@ -0,0 +1,482 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use graphql_client::{GraphQLQuery, QueryBody, Response};
+use reqwest::{header, StatusCode};
+use tracing::{info, warn};
+
+use super::{
+    activate_user, assign_user_group, create_page, create_user, deactivate_user, find_page,
+    get_group_users, get_user,
+    pages::{PageStore, WikiPage},
+    search_user, unassign_user_group, update_page, update_user_name, ActivateUser, AssignUserGroup,
+    CreatePage, CreateUser, DeactivateUser, FindPage, GetGroupUsers, GetUser, SearchUser,
+    UnassignUserGroup, UpdatePage, UpdateUserName, WikiError,
+};
+use crate::{error::Error, SETTINGS};
+
+/// Connection settings of a [`WikiClient`].
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Timeout of a single request.
+    pub timeout: Duration,
+    /// How many times a request failing with a transient error is retried.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled with every following one.
+    pub backoff: Duration,
+    /// Locale of the wiki's pages.
+    pub locale: String,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        ClientOptions {
+            timeout: Duration::from_secs(10),
+            max_retries: 3,
+            backoff: Duration::from_millis(500),
+            locale: "en".to_string(),
+        }
+    }
+}
+
+/// Client of the Wiki.js GraphQL API.
+#[derive(Debug, Clone)]
+pub struct WikiClient {
+    http: reqwest::Client,
+    endpoint: String,
+    options: ClientOptions,
+}
+
+/// Status of a mutation, as returned in its `responseResult`.
+trait ResponseStatus {
+    fn into_result(self) -> Result<(), WikiError>;
+}
+
+macro_rules! response_status {
+    ($($status:ty),* $(,)?) => {
+        $(
+            impl ResponseStatus for $status {
+                fn into_result(self) -> Result<(), WikiError> {
+                    if self.succeeded {
+                        Ok(())
+                    } else {
+                        Err(WikiError::Error {
+                            code: self.error_code,
+                            slug: self.slug,
+                            message: self.message,
+                        })
+                    }
+                }
+            }
+        )*
+    };
+}
+
+response_status!(
+    assign_user_group::AssignUserGroupGroupsAssignUserResponseResult,
+    unassign_user_group::UnassignUserGroupGroupsUnassignUserResponseResult,
+    create_user::CreateUserUsersCreateResponseResult,
+    create_page::CreatePagePagesCreateResponseResult,
+    update_page::UpdatePagePagesUpdateResponseResult,
+    update_user_name::UpdateUserNameUsersUpdateResponseResult,
+    activate_user::ActivateUserUsersActivateResponseResult,
+    deactivate_user::DeactivateUserUsersDeactivateResponseResult,
+);
+
+/// Returns the value of a field the wiki should always respond with.
+fn required<T>(value: Option<T>, field: &'static str) -> Result<T, WikiError> {
+    value.ok_or(WikiError::MissingData { field })
+}
+
+/// Returns the response's data, or its errors. Wiki.js reports a bad token
+/// as a `Forbidden` error.
+fn into_data<T>(response: Response<T>) -> Result<T, WikiError> {
+    if let Some(errors) = response.errors.filter(|errors| !errors.is_empty()) {
+        if errors.iter().any(|error| error.message == "Forbidden") {
+            return Err(WikiError::Unauthorized);
+        }
+
+        return Err(errors.into());
+    }
+
+    required(response.data, "data")
+}
+
+/// Removes errors matching the predicate. Returns `true` if the response
+/// only contained such errors.
+fn ignore_errors<T>(
+    response: &mut Response<T>,
+    predicate: impl Fn(&graphql_client::Error) -> bool,
+) -> bool {
+    let Some(errors) = response.errors.take() else {
+        return false;
+    };
+
+    let had_errors = !errors.is_empty();
+    let errors = errors
+        .into_iter()
+        .filter(|error| !predicate(error))
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        had_errors
+    } else {
+        response.errors = Some(errors);
+        false
+    }
+}
+
+impl WikiClient {
+    pub fn new(
+        endpoint: impl Into<String>,
+        token: &str,
+        options: ClientOptions,
+    ) -> Result<Self, WikiError> {
+        let mut headers = header::HeaderMap::new();
+
+        let mut auth_value =
+            header::HeaderValue::from_str(token).map_err(|_| WikiError::Unauthorized)?;
+        auth_value.set_sensitive(true);
+        headers.insert(header::AUTHORIZATION, auth_value);
+
+        let http = reqwest::Client::builder()
+            .user_agent("octobot/".to_owned() + env!("CARGO_PKG_VERSION"))
+            .default_headers(headers)
+            .timeout(options.timeout)
+            .build()?;
+
+        Ok(WikiClient {
+            http,
+            endpoint: endpoint.into(),
+            options,
+        })
+    }
+
+    /// Creates the client of the wiki from the config.
+    pub fn from_settings() -> Result<Self, WikiError> {
+        WikiClient::new(
+            SETTINGS.wiki.graphql.clone(),
+            &SETTINGS.wiki.token,
+            ClientOptions {
+                timeout: Duration::from_secs(SETTINGS.wiki.timeout_secs),
+                max_retries: SETTINGS.wiki.max_retries,
+                locale: SETTINGS.wiki.locale.clone(),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Sends the query, retrying transient failures with exponential
+    /// backoff.
+    async fn post<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<Response<Q::ResponseData>, WikiError> {
+        self.post_retrying::<Q>(variables, WikiError::is_transient)
+            .await
+    }
+
+    /// Sends a mutation that must not be applied twice, e.g. a create. A
+    /// timed out request may have been applied, so it is only retried when
+    /// it did not reach the wiki.
+    async fn post_once<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<Response<Q::ResponseData>, WikiError> {
+        self.post_retrying::<Q>(variables, WikiError::is_unsent)
+            .await
+    }
+
+    async fn post_retrying<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+        retry: fn(&WikiError) -> bool,
+    ) -> Result<Response<Q::ResponseData>, WikiError> {
+        let body = Q::build_query(variables);
+        let mut attempt = 0;
+
+        loop {
+            match self.send::<Q>(&body).await {
+                Err(why) if retry(&why) && attempt < self.options.max_retries => {
+                    let delay = self.options.backoff * 2u32.pow(attempt);
+                    attempt += 1;
+
+                    warn!(
+                        "Wiki request {} failed, retrying in {:?} ({attempt}/{}): {why}",
+                        body.operation_name, delay, self.options.max_retries
+                    );
+
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send<Q: GraphQLQuery>(
+        &self,
+        body: &QueryBody<Q::Variables>,
+    ) -> Result<Response<Q::ResponseData>, WikiError> {
+        let response = self.http.post(&self.endpoint).json(body).send().await?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(WikiError::Unauthorized),
+            status if !status.is_success() => Err(WikiError::HttpStatus { status }),
+            _ => Ok(response.json().await?),
+        }
+    }
+
+    pub async fn assign_user_group(&self, user_id: i64, group_id: i64) -> Result<(), WikiError> {
+        let mut response = self
+            .post::<AssignUserGroup>(assign_user_group::Variables { user_id, group_id })
+            .await?;
+
+        if ignore_errors(&mut response, |error| {
+            error.message.contains("User is already assigned to group.")
+        }) {
+            info!("Wiki user is already in the group");
+            return Ok(());
+        }
+
+        let data = into_data(response)?;
+
+        required(
+            required(
+                required(data.groups, "groups")?.assign_user,
+                "groups.assignUser",
+            )?
+            .response_result,
+            "groups.assignUser.responseResult",
+        )?
+        .into_result()
+    }
+
+    pub async fn unassign_user_group(&self, user_id: i64, group_id: i64) -> Result<(), WikiError> {
+        let response = self
+            .post::<UnassignUserGroup>(unassign_user_group::Variables { user_id, group_id })
+            .await?;
+
+        let data = into_data(response)?;
+
+        required(
+            required(
+                required(data.groups, "groups")?.unassign_user,
+                "groups.unassignUser",
+            )?
+            .response_result,
+            "groups.unassignUser.responseResult",
+        )?
+        .into_result()
+    }
+
+    /// Creates a user. Returns the new user's ID, if the wiki responded with
+    /// it.
+    pub async fn create_user(
+        &self,
+        email: String,
+        name: String,
+        provider_key: String,
+        groups: Vec<i64>,
+    ) -> Result<Option<i64>, WikiError> {
+        let response = self
+            .post_once::<CreateUser>(create_user::Variables {
+                email,
+                name,
+                provider_key,
+                groups: groups.into_iter().map(Some).collect(),
+            })
+            .await?;
+
+        let data = into_data(response)?;
+        let create = required(required(data.users, "users")?.create, "users.create")?;
+
+        create.response_result.into_result()?;
+
+        Ok(create.user.map(|user| user.id))
+    }
+
+    pub async fn find_user_by_email(&self, email: String) -> Result<Option<i64>, WikiError> {
+        let response = self
+            .post::<SearchUser>(search_user::Variables { query: email })
+            .await?;
+
+        let data = into_data(response)?;
+        let users = required(required(data.users, "users")?.search, "users.search")?;
+
+        Ok(users.into_iter().flatten().next().map(|user| user.id))
+    }
+
+    /// Creates a guest account with the given email and returns its ID, or
+    /// `None` if there already is an account with the email.
+    pub async fn create_guest_user(
+        &self,
+        email: String,
+        name: String,
+    ) -> Result<Option<i64>, WikiError> {
+        if self.find_user_by_email(email.clone()).await?.is_some() {
+            return Ok(None);
+        }
+
+        let user_id = self
+            .create_user(
+                email.clone(),
+                name,
+                SETTINGS.wiki.provider_key.clone(),
+                vec![SETTINGS.wiki.guest_group_id],
+            )
+            .await?;
+
+        match user_id {
+            Some(id) => Ok(Some(id)),
+            None => required(
+                self.find_user_by_email(email).await?,
+                "users.search after users.create",
+            )
+            .map(Some),
+        }
+    }
+
+    /// Returns the wiki user with the given ID, if it exists.
+    pub async fn get_user(
+        &self,
+        id: i64,
+    ) -> Result<Option<get_user::GetUserUsersSingle>, WikiError> {
+        let response = self.post::<GetUser>(get_user::Variables { id }).await?;
+
+        let data = into_data(response)?;
+
+        Ok(required(data.users, "users")?.single)
+    }
+
+    pub async fn update_user_name(&self, id: i64, name: String) -> Result<(), WikiError> {
+        let response = self
+            .post::<UpdateUserName>(update_user_name::Variables { id, name })
+            .await?;
+
+        let data = into_data(response)?;
+
+        required(
+            required(required(data.users, "users")?.update, "users.update")?.response_result,
+            "users.update.responseResult",
+        )?
+        .into_result()
+    }
+
+    /// Activates or deactivates the wiki user. Deactivated users cannot log in.
+    pub async fn set_user_active(&self, id: i64, active: bool) -> Result<(), WikiError> {
+        if active {
+            let data = into_data(
+                self.post::<ActivateUser>(activate_user::Variables { id })
+                    .await?,
+            )?;
+
+            required(
+                required(required(data.users, "users")?.activate, "users.activate")?
+                    .response_result,
+                "users.activate.responseResult",
+            )?
+            .into_result()
+        } else {
+            let data = into_data(
+                self.post::<DeactivateUser>(deactivate_user::Variables { id })
+                    .await?,
+            )?;
+
+            required(
+                required(
+                    required(data.users, "users")?.deactivate,
+                    "users.deactivate",
+                )?
+                .response_result,
+                "users.deactivate.responseResult",
+            )?
+            .into_result()
+        }
+    }
+
+    /// Returns the users assigned to the group. Returns an empty list if the
+    /// group does not exist.
+    pub async fn get_group_users(
+        &self,
+        id: i64,
+    ) -> Result<Vec<get_group_users::GetGroupUsersGroupsSingleUsers>, WikiError> {
+        let response = self
+            .post::<GetGroupUsers>(get_group_users::Variables { id })
+            .await?;
+
+        let data = into_data(response)?;
+
+        Ok(required(data.groups, "groups")?
+            .single
+            .and_then(|group| group.users)
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PageStore for WikiClient {
+    async fn find_page(&self, path: &str) -> Result<Option<i64>, Error> {
+        let mut response = self
+            .post::<FindPage>(find_page::Variables {
+                path: path.to_string(),
+                locale: self.options.locale.clone(),
+            })
+            .await?;
+
+        // Wiki.js reports missing pages as an error instead of an empty result
+        if ignore_errors(&mut response, |error| {
+            error.message.contains("This page does not exist.")
+        }) {
+            return Ok(None);
+        }
+
+        let data = into_data(response)?;
+
+        Ok(required(data.pages, "pages")?
+            .single_by_path
+            .map(|page| page.id))
+    }
+
+    async fn create_page(&self, page: &WikiPage) -> Result<i64, Error> {
+        let response = self
+            .post_once::<CreatePage>(create_page::Variables {
+                content: page.content.clone(),
+                description: page.description.clone(),
+                locale: self.options.locale.clone(),
+                path: page.path.clone(),
+                tags: page.tags.iter().cloned().map(Some).collect(),
+                title: page.title.clone(),
+            })
+            .await?;
+
+        let data = into_data(response)?;
+        let create = required(required(data.pages, "pages")?.create, "pages.create")?;
+
+        create.response_result.into_result()?;
+
+        Ok(required(create.page, "pages.create.page")?.id)
+    }
+
+    async fn update_page(&self, id: i64, page: &WikiPage) -> Result<(), Error> {
+        let response = self
+            .post::<UpdatePage>(update_page::Variables {
+                id,
+                content: page.content.clone(),
+                description: page.description.clone(),
+                tags: page.tags.iter().cloned().map(Some).collect(),
+                title: page.title.clone(),
+            })
+            .await?;
+
+        let data = into_data(response)?;
+
+        required(required(data.pages, "pages")?.update, "pages.update")?
+            .response_result
+            .into_result()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+//! Reconciling wiki group membership with the member database.
+//!
+//! Every member with a wiki account should be in exactly one of the managed
+//! groups (the wiki groups of the roles, or `guest_group_id`), as given by
+//! [`Member::wiki_group`]. Mismatches are found by comparing the groups' users
+//! with the database and can be fixed one by one, so a single failure does not
+//! stop the remaining fixes.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use tracing::warn;
+
+use super::WIKI;
+use crate::{
+    database::models::{member::Member, role::Role},
+    error::Error,
+    i18n::t,
+    jobs,
+    reconcile::FixStatus,
+    silent, SETTINGS,
+};
+
+/// Group a member's wiki account should be in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExpectedGroup {
+    pub member: String,
+    pub wiki_id: i64,
+    pub group_id: i64,
+}
+
+/// A wiki user assigned to a managed group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GroupUser {
+    pub wiki_id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GroupMismatch {
+    /// The member's account is not in the group it should be in.
+    Missing {
+        member: String,
+        wiki_id: i64,
+        group_id: i64,
+    },
+    /// The member's account is in a managed group it should not be in.
+    Extra {
+        member: String,
+        wiki_id: i64,
+        group_id: i64,
+    },
+    /// The account in a managed group does not belong to any member. These
+    /// are only reported, as they may be e.g. administrators.
+    Unknown {
+        name: String,
+        wiki_id: i64,
+        group_id: i64,
+    },
+}
+
+impl GroupMismatch {
+    pub(crate) fn localized(&self, locale: &str) -> String {
+        match self {
+            GroupMismatch::Missing {
+                member,
+                wiki_id,
+                group_id,
+            } => t!(
+                locale,
+                "wiki.group_missing",
+                member = member,
+                user = wiki_id,
+                group = group_id
+            ),
+            GroupMismatch::Extra {
+                member,
+                wiki_id,
+                group_id,
+            } => t!(
+                locale,
+                "wiki.group_extra",
+                member = member,
+                user = wiki_id,
+                group = group_id
+            ),
+            GroupMismatch::Unknown {
+                name,
+                wiki_id,
+                group_id,
+            } => t!(
+                locale,
+                "wiki.group_unknown",
+                name = name,
+                user = wiki_id,
+                group = group_id
+            ),
+        }
+    }
+
+    fn is_fixable(&self) -> bool {
+        !matches!(self, GroupMismatch::Unknown { .. })
+    }
+
+    async fn fix(&self) -> Result<(), Error> {
+        match self {
+            GroupMismatch::Missing {
+                wiki_id, group_id, ..
+            } => Ok(WIKI.assign_user_group(*wiki_id, *group_id).await?),
+            GroupMismatch::Extra {
+                wiki_id, group_id, ..
+            } => Ok(WIKI.unassign_user_group(*wiki_id, *group_id).await?),
+            GroupMismatch::Unknown { .. } => Ok(()),
+        }
+    }
+}
+
+/// Compares the users of the managed groups, keyed by group ID, with the
+/// groups members should be in.
+pub(crate) fn find_mismatches(
+    expected: &[ExpectedGroup],
+    groups: &BTreeMap<i64, Vec<GroupUser>>,
+) -> Vec<GroupMismatch> {
+    let mut mismatches = Vec::new();
+
+    for member in expected {
+        let in_group = groups
+            .get(&member.group_id)
+            .is_some_and(|users| users.iter().any(|user| user.wiki_id == member.wiki_id));
+
+        if !in_group {
+            mismatches.push(GroupMismatch::Missing {
+                member: member.member.clone(),
+                wiki_id: member.wiki_id,
+                group_id: member.group_id,
+            });
+        }
+    }
+
+    for (&group_id, users) in groups {
+        for user in users {
+            match expected
+                .iter()
+                .find(|member| member.wiki_id == user.wiki_id)
+            {
+                None => mismatches.push(GroupMismatch::Unknown {
+                    name: user.name.clone(),
+                    wiki_id: user.wiki_id,
+                    group_id,
+                }),
+                Some(member) if member.group_id != group_id => {
+                    mismatches.push(GroupMismatch::Extra {
+                        member: member.member.clone(),
+                        wiki_id: user.wiki_id,
+                        group_id,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    mismatches
+}
+
+/// Lists the mismatches between the wiki groups and the database. When
+/// `apply` is set, every fixable mismatch is fixed, continuing past failures.
+pub(crate) async fn reconcile(apply: bool) -> Result<Vec<(GroupMismatch, FixStatus)>, Error> {
+    let mut expected = Vec::new();
+    for member in Member::list_with_wiki_account()? {
+        if let Some(wiki_id) = member.wiki_id() {
+            expected.push(ExpectedGroup {
+                member: member.name(),
+                wiki_id,
+                group_id: member.wiki_group()?,
+            });
+        }
+    }
+
+    let managed_groups = Role::all()?
+        .iter()
+        .filter_map(Role::wiki_group_id)
+        .chain([SETTINGS.wiki.guest_group_id])
+        .collect::<BTreeSet<_>>();
+
+    let mut groups = BTreeMap::new();
+    for group_id in managed_groups {
+        let users = WIKI
+            .get_group_users(group_id)
+            .await?
+            .into_iter()
+            .map(|user| GroupUser {
+                wiki_id: user.id,
+                name: user.name,
+            })
+            .collect();
+
+        groups.insert(group_id, users);
+    }
+
+    let mut results = Vec::new();
+    for mismatch in find_mismatches(&expected, &groups) {
+        let status = if apply && mismatch.is_fixable() {
+            match mismatch.fix().await {
+                Ok(()) => FixStatus::Fixed,
+                Err(why) => FixStatus::Failed(why),
+            }
+        } else {
+            FixStatus::NotApplied
+        };
+
+        results.push((mismatch, status));
+    }
+
+    Ok(results)
+}
+
+/// Periodically reconciles wiki groups. Mismatches are logged, and fixed
+/// when `wiki.reconcile_auto_fix` is set and silent mode is disabled.
+pub(crate) fn spawn_reconciliation_job() {
+    jobs::spawn_periodic(
+        "Wiki group reconciliation",
+        SETTINGS.wiki.reconcile_interval_hours,
+        false,
+        || async {
+            let apply = SETTINGS.wiki.reconcile_auto_fix && !silent::is_enabled();
+
+            for (mismatch, status) in reconcile(apply).await? {
+                warn!("Wiki group mismatch: {:?} ({:?})", mismatch, status);
+            }
+
+            Ok(())
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{find_mismatches, ExpectedGroup, GroupMismatch, GroupUser};
+
+    const MEMBERS: i64 = 1;
+    const GUESTS: i64 = 2;
+
+    fn expected(member: &str, wiki_id: i64, group_id: i64) -> ExpectedGroup {
+        ExpectedGroup {
+            member: member.to_string(),
+            wiki_id,
+            group_id,
+        }
+    }
+
+    fn user(name: &str, wiki_id: i64) -> GroupUser {
+        GroupUser {
+            wiki_id,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn matching_groups_have_no_mismatches() {
+        let expected = vec![expected("Alice", 10, MEMBERS), expected("Bob", 11, GUESTS)];
+        let groups = BTreeMap::from([
+            (MEMBERS, vec![user("Alice", 10)]),
+            (GUESTS, vec![user("Bob", 11)]),
+        ]);
+
+        assert!(find_mismatches(&expected, &groups).is_empty());
+    }
+
+    #[test]
+    fn member_in_wrong_group_is_missing_and_extra() {
+        let expected = vec![expected("Alice", 10, MEMBERS)];
+        let groups = BTreeMap::from([(MEMBERS, vec![]), (GUESTS, vec![user("Alice", 10)])]);
+
+        assert_eq!(
+            find_mismatches(&expected, &groups),
+            vec![
+                GroupMismatch::Missing {
+                    member: "Alice".to_string(),
+                    wiki_id: 10,
+                    group_id: MEMBERS,
+                },
+                GroupMismatch::Extra {
+                    member: "Alice".to_string(),
+                    wiki_id: 10,
+                    group_id: GUESTS,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn user_without_member_is_unknown() {
+        let groups = BTreeMap::from([(MEMBERS, vec![user("Admin", 1)]), (GUESTS, vec![])]);
+
+        assert_eq!(
+            find_mismatches(&[], &groups),
+            vec![GroupMismatch::Unknown {
+                name: "Admin".to_string(),
+                wiki_id: 1,
+                group_id: MEMBERS,
+            }]
+        );
+    }
+}
@@ -1,9 +1,44 @@
+//! Wiki.js integration.
+//!
+//! All requests go through [`WikiClient`]; [`WIKI`] is the client configured
+//! from the settings. Responses are checked for errors and missing data, so
+//! a misbehaving wiki surfaces as a [`WikiError`] instead of a panic.
+
+use std::sync::OnceLock;
+
 use graphql_client::GraphQLQuery;
-use reqwest::header;
+use lazy_static::lazy_static;
 use thiserror::Error;
-use tracing::info;
 
-use crate::{error::Error, SETTINGS};
+pub use self::client::{ClientOptions, WikiClient};
+
+mod client;
+pub(crate) mod groups;
+pub(crate) mod pages;
+pub(crate) mod profile;
+#[cfg(test)]
+mod tests;
+
+static CLIENT: OnceLock<WikiClient> = OnceLock::new();
+
+lazy_static! {
+    /// The client created by [`init`] at startup.
+    pub static ref WIKI: &'static WikiClient = CLIENT
+        .get()
+        .expect("The wiki client is created by wiki::init at startup");
+}
+
+/// Creates [`WIKI`] from the settings, e.g. failing on an invalid token, so
+/// the bot does not start instead of failing in the first command using the
+/// wiki.
+pub fn init() -> Result<(), WikiError> {
+    let client = WikiClient::from_settings()?;
+
+    // only the first client is kept if `init` is called again
+    let _ = CLIENT.set(client);
+
+    Ok(())
+}
 
 #[derive(Error, Debug)]
 pub enum WikiError {
@@ -17,6 +52,12 @@ pub enum WikiError {
     ReqwestError { source: reqwest::Error },
     #[error("Wiki graphql error: {errors:?}")]
     GraphqlClientError { errors: Vec<graphql_client::Error> },
+    #[error("Wiki responded with HTTP status {status}")]
+    HttpStatus { status: reqwest::StatusCode },
+    #[error("Wiki rejected the API token")]
+    Unauthorized,
+    #[error("Wiki response is missing {field}")]
+    MissingData { field: &'static str },
 }
 
 impl From<Vec<graphql_client::Error>> for WikiError {
@@ -25,6 +66,34 @@ impl From<Vec<graphql_client::Error>> for WikiError {
     }
 }
 
+impl From<reqwest::Error> for WikiError {
+    fn from(source: reqwest::Error) -> Self {
+        WikiError::ReqwestError { source }
+    }
+}
+
+impl WikiError {
+    /// Whether the request may succeed when retried, e.g. after a timeout or
+    /// a server error.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WikiError::ReqwestError { source } => {
+                source.is_timeout() || source.is_connect() || source.is_request()
+            }
+            WikiError::HttpStatus { status } => {
+                status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the request failed before reaching the wiki, so retrying it
+    /// cannot apply it twice.
+    pub fn is_unsent(&self) -> bool {
+        matches!(self, WikiError::ReqwestError { source } if source.is_connect())
+    }
+}
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "wiki/schema.graphql",
@@ -57,185 +126,66 @@ pub struct CreateUser;
 )]
 pub struct SearchUser;
 
-fn get_client() -> Result<reqwest::Client, Error> {
-    let mut headers = header::HeaderMap::new();
-
-    let mut auth_value = header::HeaderValue::from_static(&SETTINGS.wiki.token);
-    auth_value.set_sensitive(true);
-    headers.insert(header::AUTHORIZATION, auth_value);
-
-    let client = reqwest::Client::builder()
-        .user_agent("octobot/".to_owned() + env!("CARGO_PKG_VERSION"))
-        .default_headers(headers)
-        .build()?;
-
-    Ok(client)
-}
-
-pub async fn assign_user_group(variables: assign_user_group::Variables) -> Result<(), Error> {
-    let client = get_client()?;
-
-    let response_body = graphql_client::reqwest::post_graphql::<AssignUserGroup, _>(
-        &client,
-        &SETTINGS.wiki.graphql,
-        variables,
-    )
-    .await?;
-
-    if let Some(errors) = response_body.errors {
-        let errors = errors
-            .into_iter()
-            .filter(|error| {
-                info!("Wiki user is already in the group");
-                !error.message.contains("User is already assigned to group.")
-            })
-            .collect::<Vec<_>>();
-
-        if errors.is_empty() {
-            return Ok(());
-        }
-
-        Err(Into::<WikiError>::into(errors))?;
-    }
-
-    let response_result = response_body
-        .data
-        .unwrap()
-        .groups
-        .unwrap()
-        .assign_user
-        .unwrap()
-        .response_result
-        .unwrap();
-
-    if response_result.succeeded {
-        Ok(())
-    } else {
-        Err(WikiError::Error {
-            code: response_result.error_code,
-            slug: response_result.slug,
-            message: response_result.message,
-        }
-        .into())
-    }
-}
-
-pub async fn unassign_user_group(variables: unassign_user_group::Variables) -> Result<(), Error> {
-    let client = get_client()?;
-
-    let response_body = graphql_client::reqwest::post_graphql::<UnassignUserGroup, _>(
-        &client,
-        &SETTINGS.wiki.graphql,
-        variables,
-    )
-    .await?;
-
-    if let Some(errors) = response_body.errors {
-        Err(Into::<WikiError>::into(errors))?;
-    }
-
-    let response_result = response_body
-        .data
-        .unwrap()
-        .groups
-        .unwrap()
-        .unassign_user
-        .unwrap()
-        .response_result
-        .unwrap();
-
-    if response_result.succeeded {
-        Ok(())
-    } else {
-        Err(WikiError::Error {
-            code: response_result.error_code,
-            slug: response_result.slug,
-            message: response_result.message,
-        }
-        .into())
-    }
-}
-
-pub async fn create_user(variables: create_user::Variables) -> Result<Option<i64>, Error> {
-    let client = get_client()?;
-
-    let response_body = graphql_client::reqwest::post_graphql::<CreateUser, _>(
-        &client,
-        &SETTINGS.wiki.graphql,
-        variables,
-    )
-    .await?;
-
-    if let Some(errors) = response_body.errors {
-        Err(Into::<WikiError>::into(errors))?;
-    }
-
-    let create_user_users_create = response_body.data.unwrap().users.unwrap().create;
-
-    let response_result = &create_user_users_create.as_ref().unwrap().response_result;
-
-    if response_result.succeeded {
-        Ok(create_user_users_create
-            .unwrap()
-            .user
-            .as_ref()
-            .map(|user| user.id))
-    } else {
-        Err(WikiError::Error {
-            code: response_result.error_code,
-            slug: response_result.slug.clone(),
-            message: response_result.message.clone(),
-        }
-        .into())
-    }
-}
-
-pub async fn find_user_by_email(email: String) -> Result<Option<i64>, Error> {
-    let client = get_client()?;
-
-    let variables = search_user::Variables { query: email };
-
-    let response_body = graphql_client::reqwest::post_graphql::<SearchUser, _>(
-        &client,
-        &SETTINGS.wiki.graphql,
-        variables,
-    )
-    .await?;
-
-    if let Some(errors) = response_body.errors {
-        Err(Into::<WikiError>::into(errors))?;
-    }
-
-    let search_user_users = response_body.data.unwrap().users.unwrap().search.unwrap();
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/mutations/create_page.graphql",
+    response_derives = "Debug"
+)]
+pub struct CreatePage;
 
-    let len = search_user_users.len();
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/mutations/update_page.graphql",
+    response_derives = "Debug"
+)]
+pub struct UpdatePage;
 
-    if len == 0 {
-        Ok(None)
-    } else {
-        Ok(search_user_users[0].as_ref().map(|user| user.id))
-    }
-}
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/queries/find_page.graphql",
+    response_derives = "Debug"
+)]
+pub struct FindPage;
 
-pub async fn find_or_create_user(email: String, name: String) -> Result<i64, Error> {
-    let user_id = find_user_by_email(email.clone()).await?;
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/queries/get_user.graphql",
+    response_derives = "Debug,Clone"
+)]
+pub struct GetUser;
 
-    if let Some(id) = user_id {
-        return Ok(id);
-    }
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/mutations/update_user_name.graphql",
+    response_derives = "Debug"
+)]
+pub struct UpdateUserName;
 
-    let variables = create_user::Variables {
-        email: email.clone(),
-        name,
-        provider_key: SETTINGS.wiki.provider_key.clone(),
-        groups: vec![Some(SETTINGS.wiki.guest_group_id)],
-    };
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/mutations/activate_user.graphql",
+    response_derives = "Debug"
+)]
+pub struct ActivateUser;
 
-    let user_id = create_user(variables).await?;
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/mutations/deactivate_user.graphql",
+    response_derives = "Debug"
+)]
+pub struct DeactivateUser;
 
-    if let Some(id) = user_id {
-        Ok(id)
-    } else {
-        find_user_by_email(email).await.map(|id| id.unwrap())
-    }
-}
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "wiki/schema.graphql",
+    query_path = "wiki/queries/get_group_users.graphql",
+    response_derives = "Debug,Clone"
+)]
+pub struct GetGroupUsers;
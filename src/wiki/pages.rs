@@ -0,0 +1,131 @@
+//! Publishing pages on the wiki.
+//!
+//! Page operations go through the [`PageStore`] trait, so code publishing
+//! pages can be tested without a running wiki. [`WikiClient`] talks to the
+//! Wiki.js instance.
+//!
+//! [`WikiClient`]: super::WikiClient
+
+use async_trait::async_trait;
+
+use crate::error::Error;
+
+/// Content of a wiki page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WikiPage {
+    /// Path of the page, without a leading slash, e.g. `meetings/2024-05-17`.
+    pub path: String,
+    pub title: String,
+    pub description: String,
+    /// Markdown content of the page.
+    pub content: String,
+    pub tags: Vec<String>,
+}
+
+#[async_trait]
+pub(crate) trait PageStore: Send + Sync {
+    /// Returns the ID of the page at `path`, if it exists.
+    async fn find_page(&self, path: &str) -> Result<Option<i64>, Error>;
+
+    /// Creates the page. Returns the new page's ID.
+    async fn create_page(&self, page: &WikiPage) -> Result<i64, Error>;
+
+    /// Replaces the content of the page with the given ID.
+    async fn update_page(&self, id: i64, page: &WikiPage) -> Result<(), Error>;
+}
+
+/// Creates the page, or updates it if a page already exists at its path.
+/// Returns the page's ID.
+pub(crate) async fn publish_page(store: &dyn PageStore, page: &WikiPage) -> Result<i64, Error> {
+    match store.find_page(&page.path).await? {
+        Some(id) => {
+            store.update_page(id, page).await?;
+
+            Ok(id)
+        }
+        None => store.create_page(page).await,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use async_trait::async_trait;
+
+    use super::{publish_page, PageStore, WikiPage};
+    use crate::error::Error;
+
+    /// In-memory wiki, keyed by page path.
+    #[derive(Default)]
+    pub(crate) struct MockPageStore {
+        pub pages: Mutex<HashMap<String, (i64, WikiPage)>>,
+    }
+
+    #[async_trait]
+    impl PageStore for MockPageStore {
+        async fn find_page(&self, path: &str) -> Result<Option<i64>, Error> {
+            Ok(self.pages.lock().unwrap().get(path).map(|(id, _)| *id))
+        }
+
+        async fn create_page(&self, page: &WikiPage) -> Result<i64, Error> {
+            let mut pages = self.pages.lock().unwrap();
+            let id = pages.len() as i64 + 1;
+
+            pages.insert(page.path.clone(), (id, page.clone()));
+
+            Ok(id)
+        }
+
+        async fn update_page(&self, id: i64, page: &WikiPage) -> Result<(), Error> {
+            let mut pages = self.pages.lock().unwrap();
+
+            let (_, stored) = pages
+                .values_mut()
+                .find(|(page_id, _)| *page_id == id)
+                .expect("updated page exists");
+            *stored = page.clone();
+
+            Ok(())
+        }
+    }
+
+    fn page(path: &str, content: &str) -> WikiPage {
+        WikiPage {
+            path: path.to_string(),
+            title: "Title".to_string(),
+            description: "Description".to_string(),
+            content: content.to_string(),
+            tags: vec!["meeting".to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_page_is_created() {
+        let store = MockPageStore::default();
+
+        let id = publish_page(&store, &page("meetings/2024-05-17", "content"))
+            .await
+            .unwrap();
+
+        let pages = store.pages.lock().unwrap();
+        assert_eq!(pages["meetings/2024-05-17"].0, id);
+    }
+
+    #[tokio::test]
+    async fn existing_page_is_updated() {
+        let store = MockPageStore::default();
+
+        let first = publish_page(&store, &page("meetings/2024-05-17", "old"))
+            .await
+            .unwrap();
+        let second = publish_page(&store, &page("meetings/2024-05-17", "new"))
+            .await
+            .unwrap();
+
+        let pages = store.pages.lock().unwrap();
+        assert_eq!(first, second);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages["meetings/2024-05-17"].1.content, "new");
+    }
+}
@@ -0,0 +1,132 @@
+//! Keeping wiki accounts in sync with member profiles.
+//!
+//! The member database is the source of truth: the wiki user's name follows
+//! the member's display name, and accounts of ex-members are deactivated.
+
+use tracing::info;
+
+use super::WIKI;
+use crate::{error::Error, i18n::t};
+
+/// Profile data of a wiki user that is kept in sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WikiProfile {
+    pub name: String,
+    pub is_active: bool,
+}
+
+/// A difference between a member and their wiki account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProfileChange {
+    Rename { from: String, to: String },
+    Activate,
+    Deactivate,
+}
+
+impl ProfileChange {
+    pub(crate) fn localized(&self, locale: &str) -> String {
+        match self {
+            ProfileChange::Rename { from, to } => {
+                t!(locale, "member.sync_renamed", from = from, to = to)
+            }
+            ProfileChange::Activate => t!(locale, "member.sync_activated"),
+            ProfileChange::Deactivate => t!(locale, "member.sync_deactivated"),
+        }
+    }
+}
+
+/// Lists the changes needed for the wiki profile to match the member's.
+pub(crate) fn profile_changes(current: &WikiProfile, desired: &WikiProfile) -> Vec<ProfileChange> {
+    let mut changes = Vec::new();
+
+    if current.name != desired.name {
+        changes.push(ProfileChange::Rename {
+            from: current.name.clone(),
+            to: desired.name.clone(),
+        });
+    }
+
+    match (current.is_active, desired.is_active) {
+        (false, true) => changes.push(ProfileChange::Activate),
+        (true, false) => changes.push(ProfileChange::Deactivate),
+        _ => {}
+    }
+
+    changes
+}
+
+/// Pushes the desired profile to the wiki user. Returns the applied changes.
+pub(crate) async fn sync_profile(
+    user_id: i64,
+    desired: &WikiProfile,
+) -> Result<Vec<ProfileChange>, Error> {
+    let user = WIKI
+        .get_user(user_id)
+        .await?
+        .ok_or(Error::WikiUserNotFound(user_id))?;
+
+    let current = WikiProfile {
+        name: user.name,
+        is_active: user.is_active,
+    };
+
+    let changes = profile_changes(&current, desired);
+
+    for change in &changes {
+        match change {
+            ProfileChange::Rename { to, .. } => WIKI.update_user_name(user_id, to.clone()).await?,
+            ProfileChange::Activate => WIKI.set_user_active(user_id, true).await?,
+            ProfileChange::Deactivate => WIKI.set_user_active(user_id, false).await?,
+        }
+    }
+
+    if !changes.is_empty() {
+        info!("Synced wiki user {user_id}: {changes:?}");
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{profile_changes, ProfileChange, WikiProfile};
+
+    fn profile(name: &str, is_active: bool) -> WikiProfile {
+        WikiProfile {
+            name: name.to_string(),
+            is_active,
+        }
+    }
+
+    #[test]
+    fn matching_profile_has_no_changes() {
+        assert!(profile_changes(&profile("Alice", true), &profile("Alice", true)).is_empty());
+    }
+
+    #[test]
+    fn renamed_member_renames_wiki_user() {
+        assert_eq!(
+            profile_changes(&profile("Alice", true), &profile("Alicja", true)),
+            vec![ProfileChange::Rename {
+                from: "Alice".to_string(),
+                to: "Alicja".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn ex_member_is_deactivated() {
+        assert_eq!(
+            profile_changes(&profile("Alice", true), &profile("Alice", false)),
+            vec![ProfileChange::Deactivate]
+        );
+    }
+
+    #[test]
+    fn returning_member_is_activated() {
+        assert_eq!(
+            profile_changes(&profile("Alice", false), &profile("Alice", true)),
+            vec![ProfileChange::Activate]
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Tests of [`WikiClient`] against a mock GraphQL server.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use wiremock::{
+    matchers::{body_partial_json, header, method},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use super::{pages::PageStore, ClientOptions, WikiClient, WikiError};
+
+const TOKEN: &str = "Bearer test-token";
+
+fn client(server: &MockServer) -> WikiClient {
+    WikiClient::new(
+        format!("{}/graphql", server.uri()),
+        TOKEN,
+        ClientOptions {
+            timeout: Duration::from_millis(200),
+            max_retries: 2,
+            backoff: Duration::from_millis(10),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn succeeded() -> Value {
+    json!({ "succeeded": true, "errorCode": 0, "slug": "ok", "message": null })
+}
+
+fn assign_response(response_result: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "data": { "groups": { "assignUser": { "responseResult": response_result } } }
+    }))
+}
+
+fn operation(name: &str) -> wiremock::matchers::BodyPartialJsonMatcher {
+    body_partial_json(json!({ "operationName": name }))
+}
+
+#[tokio::test]
+async fn assigns_user_to_group() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(header("authorization", TOKEN))
+        .and(operation("AssignUserGroup"))
+        .and(body_partial_json(
+            json!({ "variables": { "userId": 7, "groupId": 3 } }),
+        ))
+        .respond_with(assign_response(succeeded()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server).assign_user_group(7, 3).await.unwrap();
+}
+
+#[tokio::test]
+async fn user_already_in_group_is_not_an_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "groups": { "assignUser": null } },
+            "errors": [{ "message": "User is already assigned to group." }]
+        })))
+        .mount(&server)
+        .await;
+
+    client(&server).assign_user_group(7, 3).await.unwrap();
+}
+
+#[tokio::test]
+async fn failed_response_result_is_an_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(assign_response(json!({
+            "succeeded": false,
+            "errorCode": 1003,
+            "slug": "AuthUnauthorized",
+            "message": "You are not authorized."
+        })))
+        .mount(&server)
+        .await;
+
+    let err = client(&server).assign_user_group(7, 3).await.unwrap_err();
+
+    assert!(matches!(err, WikiError::Error { code: 1003, .. }));
+}
+
+#[tokio::test]
+async fn missing_data_is_an_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "data": { "groups": null } })),
+        )
+        .mount(&server)
+        .await;
+
+    let err = client(&server).assign_user_group(7, 3).await.unwrap_err();
+
+    assert!(matches!(err, WikiError::MissingData { field: "groups" }));
+}
+
+#[tokio::test]
+async fn rejected_token_is_unauthorized() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client(&server).assign_user_group(7, 3).await.unwrap_err();
+
+    assert!(matches!(err, WikiError::Unauthorized));
+}
+
+#[tokio::test]
+async fn forbidden_graphql_error_is_unauthorized() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "users": null },
+            "errors": [{ "message": "Forbidden" }]
+        })))
+        .mount(&server)
+        .await;
+
+    let err = client(&server).get_user(7).await.unwrap_err();
+
+    assert!(matches!(err, WikiError::Unauthorized));
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(assign_response(succeeded()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server).assign_user_group(7, 3).await.unwrap();
+}
+
+#[tokio::test]
+async fn retries_are_bounded() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        // the first attempt and two retries
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let err = client(&server).assign_user_group(7, 3).await.unwrap_err();
+
+    assert!(matches!(err, WikiError::HttpStatus { status } if status.as_u16() == 503));
+}
+
+#[tokio::test]
+async fn timeouts_are_retried() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(assign_response(succeeded()).set_delay(Duration::from_secs(1)))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(assign_response(succeeded()))
+        .mount(&server)
+        .await;
+
+    client(&server).assign_user_group(7, 3).await.unwrap();
+}
+
+#[tokio::test]
+async fn timed_out_creates_are_not_retried() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(operation("CreateUser"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(1)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client(&server)
+        .create_user(
+            "alice@example.com".to_string(),
+            "Alice".to_string(),
+            "local".to_string(),
+            vec![1],
+        )
+        .await
+        .unwrap_err();
+
+    assert!(err.is_transient() && !err.is_unsent());
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client(&server).assign_user_group(7, 3).await.unwrap_err();
+
+    assert!(!err.is_transient());
+}
+
+#[tokio::test]
+async fn finds_user_by_email() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(operation("SearchUser"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "users": { "search": [
+                { "id": 12, "name": "Alice", "email": "alice@example.com" }
+            ] } }
+        })))
+        .mount(&server)
+        .await;
+
+    let id = client(&server)
+        .find_user_by_email("alice@example.com".to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(id, Some(12));
+}
+
+#[tokio::test]
+async fn missing_page_is_not_found() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(operation("FindPage"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "pages": { "singleByPath": null } },
+            "errors": [{ "message": "This page does not exist." }]
+        })))
+        .mount(&server)
+        .await;
+
+    let page = client(&server).find_page("meetings/2024").await.unwrap();
+
+    assert_eq!(page, None);
+}
+
+#[tokio::test]
+async fn group_users_are_listed() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(operation("GetGroupUsers"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "groups": { "single": { "users": [
+                { "id": 1, "name": "Admin", "email": "admin@example.com" },
+                null,
+                { "id": 2, "name": "Alice", "email": "alice@example.com" }
+            ] } } }
+        })))
+        .mount(&server)
+        .await;
+
+    let users = client(&server).get_group_users(3).await.unwrap();
+
+    assert_eq!(
+        users.iter().map(|user| user.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+}